@@ -16,7 +16,7 @@
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-fn basic_setup(c: &mut Criterion) {}
+fn basic_setup(_c: &mut Criterion) {}
 
 criterion_group!(core, basic_setup);
 
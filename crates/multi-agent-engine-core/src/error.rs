@@ -14,23 +14,122 @@
  * limitations under the License.
  */
 
+use crate::{AgentId, Endpoint};
 use std::{
     any::Any,
+    backtrace::Backtrace,
     error,
     fmt::{self, Debug, Display, Formatter},
+    io,
+    ops::RangeInclusive,
+    time::Duration,
 };
 
 #[non_exhaustive]
 pub enum Error {
     Thread(Box<dyn Any + Send + 'static>),
-    MessageSender,
+    AgentPanic {
+        agent: AgentId,
+        message: String,
+        backtrace: Backtrace,
+    },
+    Disconnected {
+        endpoint: Endpoint,
+    },
+    Timeout {
+        duration: Duration,
+    },
+    Deadlock {
+        agents: usize,
+    },
+    Transport(io::Error),
+    SignalHandler(Box<dyn error::Error + Send + Sync + 'static>),
+    CircuitOpen,
+    SchemaMismatch {
+        found: u8,
+        expected: u8,
+    },
+    StartupFailed {
+        agent: AgentId,
+    },
+    IncompatibleVersion {
+        local: RangeInclusive<u16>,
+        remote: RangeInclusive<u16>,
+    },
+    ConnectFailed {
+        attempts: u32,
+        source: io::Error,
+    },
+    Multiple(Vec<Error>),
+    SenderInUse {
+        clones: usize,
+    },
+    AgentRunning {
+        agent: AgentId,
+    },
+    NoFrameBoundary,
+    AlreadyLinked,
+    CancellationCycle,
+    Agent {
+        agent: AgentId,
+        source: Box<dyn error::Error + Send + 'static>,
+    },
+}
+
+impl Error {
+    pub fn from_agent<E>(agent: AgentId, err: E) -> Self
+    where
+        E: error::Error + Send + 'static,
+    {
+        let err: Box<dyn error::Error + Send> = Box::new(err);
+
+        match err.downcast::<Self>() {
+            Ok(err) => *err,
+            Err(source) => Self::Agent { agent, source },
+        }
+    }
+
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Transport(err) => matches!(
+                err.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted | io::ErrorKind::TimedOut
+            ),
+            _ => false,
+        }
+    }
 }
 
 impl Debug for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Thread(err) => write!(f, "{err:?}"),
-            Self::MessageSender => write!(f, "MessageSenderError(..)"),
+            Self::AgentPanic { agent, message, .. } => {
+                write!(f, "AgentPanicError({agent}, {message:?})")
+            }
+            Self::Disconnected { endpoint } => write!(f, "DisconnectedError({endpoint:?})"),
+            Self::Timeout { duration } => write!(f, "TimeoutError({duration:?})"),
+            Self::Deadlock { agents } => write!(f, "DeadlockError({agents})"),
+            Self::Transport(err) => write!(f, "TransportError({err:?})"),
+            Self::SignalHandler(err) => write!(f, "SignalHandlerError({err:?})"),
+            Self::CircuitOpen => write!(f, "CircuitOpenError(..)"),
+            Self::SchemaMismatch { found, expected } => {
+                write!(f, "SchemaMismatchError({found}, {expected})")
+            }
+            Self::StartupFailed { agent } => write!(f, "StartupFailedError({agent})"),
+            Self::IncompatibleVersion { local, remote } => {
+                write!(f, "IncompatibleVersionError({local:?}, {remote:?})")
+            }
+            Self::ConnectFailed { attempts, source } => {
+                write!(f, "ConnectFailedError({attempts}, {source:?})")
+            }
+            Self::Multiple(errors) => f.debug_tuple("MultipleErrors").field(errors).finish(),
+            Self::SenderInUse { clones } => write!(f, "SenderInUseError({clones})"),
+            Self::AgentRunning { agent } => write!(f, "AgentRunningError({agent})"),
+            Self::NoFrameBoundary => write!(f, "NoFrameBoundaryError(..)"),
+            Self::AlreadyLinked => write!(f, "AlreadyLinkedError(..)"),
+            Self::CancellationCycle => write!(f, "CancellationCycleError(..)"),
+            Self::Agent { agent, source } => write!(f, "AgentError({agent}, {source:?})"),
         }
     }
 }
@@ -39,7 +138,51 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Thread(err) => write!(f, "{err:?}"),
-            Self::MessageSender => write!(f, "sending on a disconnected channel"),
+            Self::AgentPanic { agent, message, .. } => write!(f, "{agent} panicked: {message}"),
+            Self::Disconnected {
+                endpoint: Endpoint::Sender,
+            } => write!(f, "sending on a disconnected channel"),
+            Self::Disconnected {
+                endpoint: Endpoint::Receiver,
+            } => write!(f, "receiving on an empty and disconnected channel"),
+            Self::Timeout { duration } => write!(f, "operation timed out after {duration:?}"),
+            Self::Deadlock { agents } => write!(
+                f,
+                "all {agents} agents are blocked on receive with empty queues"
+            ),
+            Self::Transport(err) => write!(f, "transport error: {err}"),
+            Self::SignalHandler(err) => write!(f, "failed to install signal handler: {err}"),
+            Self::CircuitOpen => write!(f, "circuit breaker is open"),
+            Self::SchemaMismatch { found, expected } => write!(
+                f,
+                "found schema version {found} but expected version {expected}"
+            ),
+            Self::StartupFailed { agent } => write!(f, "{agent} failed to start"),
+            Self::IncompatibleVersion { local, remote } => write!(
+                f,
+                "no common protocol version between local {local:?} and remote {remote:?}"
+            ),
+            Self::ConnectFailed { attempts, source } => {
+                write!(f, "failed to connect after {attempts} attempts: {source}")
+            }
+            Self::Multiple(errors) => {
+                write!(f, "{} errors occurred", errors.len())?;
+                for err in errors {
+                    write!(f, "; {err}")?;
+                }
+                Ok(())
+            }
+            Self::SenderInUse { clones } => write!(
+                f,
+                "cannot close a channel while {clones} other senders are alive"
+            ),
+            Self::AgentRunning { agent } => write!(f, "{agent} is still running"),
+            Self::NoFrameBoundary => write!(f, "sender is not tied to a frame boundary"),
+            Self::AlreadyLinked => write!(f, "cancellation token is already linked to a parent"),
+            Self::CancellationCycle => {
+                write!(f, "linking cancellation tokens would form a cycle")
+            }
+            Self::Agent { agent, source } => write!(f, "{agent} failed: {source}"),
         }
     }
 }
@@ -48,7 +191,30 @@ impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             Self::Thread(_) => None,
-            Self::MessageSender => None,
+            Self::AgentPanic { .. } => None,
+            Self::Disconnected { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Deadlock { .. } => None,
+            Self::Transport(err) => Some(err),
+            Self::SignalHandler(err) => Some(err.as_ref()),
+            Self::CircuitOpen => None,
+            Self::SchemaMismatch { .. } => None,
+            Self::StartupFailed { .. } => None,
+            Self::IncompatibleVersion { .. } => None,
+            Self::ConnectFailed { source, .. } => Some(source),
+            Self::Multiple(errors) => errors.first().map(|err| err as _),
+            Self::SenderInUse { .. } => None,
+            Self::AgentRunning { .. } => None,
+            Self::NoFrameBoundary => None,
+            Self::AlreadyLinked => None,
+            Self::CancellationCycle => None,
+            Self::Agent { source, .. } => Some(source.as_ref()),
         }
     }
 }
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Transport(err)
+    }
+}
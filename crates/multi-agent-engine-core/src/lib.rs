@@ -14,8 +14,12 @@
  * limitations under the License.
  */
 
+mod agent_id;
+mod endpoint;
 mod error;
 mod result;
 
+pub use agent_id::AgentId;
+pub use endpoint::Endpoint;
 pub use error::Error;
 pub use result::Result;
@@ -14,7 +14,9 @@
  * limitations under the License.
  */
 
-use multi_agent_engine::{Controller, MultiAgentEngine, Result, Shared, Simulator, message};
+use multi_agent_engine::{
+    AgentContext, Controller, Error, MultiAgentEngine, Result, Shared, Simulator, message,
+};
 use std::cmp::PartialEq;
 use std::{thread, time::Duration};
 
@@ -51,10 +53,12 @@ struct MyController {
 }
 
 impl Controller for MyController {
+    type Error = Error;
+
     fn run(self) -> Result<()> {
         println!("  Controller Start");
 
-        self.sender.send(ControllerMessage::Hello);
+        self.sender.send(ControllerMessage::Hello)?;
 
         for i in 0..20 {
             let messages: Vec<SimulatorMessage> = self.receiver.receive();
@@ -63,18 +67,26 @@ impl Controller for MyController {
             }
 
             if i == 20 {
-                self.sender.send(ControllerMessage::HalfDone);
+                self.sender.send(ControllerMessage::HalfDone)?;
             }
 
             println!("□ frame");
             thread::sleep(Duration::from_millis(1000 / 60));
         }
 
-        self.sender.send(ControllerMessage::Bye);
+        let _ = self.sender.send(ControllerMessage::Bye);
 
         println!("  Controller Stop");
         Ok(())
     }
+
+    // Wait for the other agent so that the first `Hello` is not sent early
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        if ctx.wait_for_start().is_break() {
+            return Ok(());
+        }
+        self.run()
+    }
 }
 
 struct MySimulator {
@@ -85,10 +97,12 @@ struct MySimulator {
 }
 
 impl Simulator for MySimulator {
+    type Error = Error;
+
     fn run(self) -> Result<()> {
         println!("  Simulator Start");
 
-        self.sender.send(SimulatorMessage::Hello);
+        self.sender.send(SimulatorMessage::Hello)?;
 
         for i in 0..10 {
             let messages: Vec<ControllerMessage> = self.receiver.receive();
@@ -97,18 +111,25 @@ impl Simulator for MySimulator {
             }
 
             if i == 10 {
-                self.sender.send(SimulatorMessage::HalfDone);
+                self.sender.send(SimulatorMessage::HalfDone)?;
             }
 
             println!("● tick");
             thread::sleep(Duration::from_millis(1000 / 30));
         }
 
-        self.sender.send(SimulatorMessage::Bye);
+        let _ = self.sender.send(SimulatorMessage::Bye);
 
         println!("  Simulator Stop");
         Ok(())
     }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        if ctx.wait_for_start().is_break() {
+            return Ok(());
+        }
+        self.run()
+    }
 }
 
 fn main() -> Result<()> {
@@ -132,7 +153,7 @@ fn main() -> Result<()> {
         receiver: controller_receiver,
     };
 
-    let engine = MultiAgentEngine::new(controller, simulator);
+    let engine = MultiAgentEngine::new(controller, simulator).with_start_barrier();
 
     engine.run()
 }
@@ -0,0 +1,115 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Clock, SystemClock};
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_WINDOW: usize = 8;
+const DECREASE: f64 = 0.8;
+const INCREASE: f64 = 1.1;
+
+#[derive(Debug)]
+pub struct AdaptiveRate {
+    min_hz: f64,
+    max_hz: f64,
+    rate_hz: f64,
+    window: usize,
+    frames: VecDeque<Duration>,
+    frame_start: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl AdaptiveRate {
+    pub fn new(min_hz: f64, max_hz: f64) -> Self {
+        assert!(
+            0.0 < min_hz && min_hz <= max_hz,
+            "tick rate bounds must satisfy 0 < min <= max"
+        );
+
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            min_hz,
+            max_hz,
+            rate_hz: max_hz,
+            window: DEFAULT_WINDOW,
+            frames: VecDeque::with_capacity(DEFAULT_WINDOW),
+            frame_start: clock.now(),
+            clock,
+        }
+    }
+
+    pub fn with_window(mut self, frames: usize) -> Self {
+        self.window = frames.max(1);
+        self.frames = VecDeque::with_capacity(self.window);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.frame_start = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate_hz
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.rate_hz)
+    }
+
+    pub fn tick(&mut self) {
+        let elapsed = self.clock.now().saturating_duration_since(self.frame_start);
+        self.record_frame(elapsed);
+
+        if let Some(remaining) = self.interval().checked_sub(elapsed) {
+            self.clock.sleep(remaining);
+        }
+        self.frame_start = self.clock.now();
+    }
+
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        if self.frames.len() == self.window {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_time);
+
+        if self.frames.len() < self.window {
+            return;
+        }
+
+        let budget = self.interval();
+        if self.frames.iter().all(|&frame| frame > budget) {
+            self.adjust(DECREASE);
+        } else if self.frames.iter().all(|&frame| frame < budget / 2) {
+            self.adjust(INCREASE);
+        }
+    }
+
+    fn adjust(&mut self, factor: f64) {
+        let rate = (self.rate_hz * factor).clamp(self.min_hz, self.max_hz);
+
+        if rate != self.rate_hz {
+            self.rate_hz = rate;
+            self.frames.clear();
+        }
+    }
+}
@@ -0,0 +1,415 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    CancellationToken, Clock, EngineEvent, FrameBatch, Heartbeat, Metrics, Observer, SimClock,
+    StartupReport, SystemClock, Warning, metrics, observer,
+    rendezvous::{self, Rendezvous},
+};
+use multi_agent_engine_core::AgentId;
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::ControlFlow,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often a paused agent checks whether it may resume.
+const PAUSE_POLL: Duration = Duration::from_millis(1);
+
+pub(crate) type Observers = Arc<Mutex<Vec<Box<dyn Observer>>>>;
+/// Run on every frame advance; a hook returning `false` is removed.
+pub(crate) type FrameHooks = Arc<Mutex<Vec<Box<dyn FnMut() -> bool + Send>>>>;
+
+/// Engine services handed to an agent when it is run by a
+/// [`MultiAgentEngine`](crate::MultiAgentEngine).
+///
+/// The frame counter and observers are shared by every agent of the engine;
+/// the cancellation token is the agent's own.
+#[derive(Clone)]
+pub struct AgentContext {
+    agent: AgentId,
+    token: CancellationToken,
+    frame: Arc<AtomicU64>,
+    completed: Arc<AtomicU64>,
+    clock: Arc<dyn Clock>,
+    observers: Option<Observers>,
+    frame_hooks: FrameHooks,
+    ready: Option<crossbeam_channel::Sender<AgentId>>,
+    start: Option<Arc<Rendezvous>>,
+    stop: Option<Arc<Rendezvous>>,
+    release: Option<Arc<Rendezvous>>,
+    released: Arc<AtomicBool>,
+    pause: Option<SimClock>,
+    heartbeat: Option<Heartbeat>,
+    metrics: Option<Metrics>,
+    startup: Option<(StartupReport, Option<Duration>)>,
+    warnings: Option<crossbeam_channel::Sender<Warning>>,
+}
+
+impl AgentContext {
+    /// A context detached from any engine, for running an agent directly.
+    pub fn new(agent: AgentId) -> Self {
+        Self {
+            agent,
+            token: CancellationToken::new(),
+            frame: Arc::new(AtomicU64::new(0)),
+            completed: Arc::default(),
+            clock: Arc::new(SystemClock),
+            observers: None,
+            frame_hooks: FrameHooks::default(),
+            ready: None,
+            start: None,
+            stop: None,
+            release: None,
+            released: Arc::default(),
+            pause: None,
+            heartbeat: None,
+            metrics: None,
+            startup: None,
+            warnings: None,
+        }
+    }
+
+    pub(crate) fn for_engine(
+        agent: AgentId,
+        token: CancellationToken,
+        frame: Arc<AtomicU64>,
+        clock: Arc<dyn Clock>,
+        observers: Observers,
+        frame_hooks: FrameHooks,
+        ready: crossbeam_channel::Sender<AgentId>,
+    ) -> Self {
+        // Observers are all added before the engine spawns, so a frame
+        // advance without any skips the lock.
+        let observed = !observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty();
+
+        Self {
+            agent,
+            token,
+            frame,
+            completed: Arc::default(),
+            clock,
+            observers: observed.then_some(observers),
+            frame_hooks,
+            ready: Some(ready),
+            start: None,
+            stop: None,
+            release: None,
+            released: Arc::default(),
+            pause: None,
+            heartbeat: None,
+            metrics: None,
+            startup: None,
+            warnings: None,
+        }
+    }
+
+    pub(crate) fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub(crate) fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub(crate) fn with_pause(mut self, clock: SimClock) -> Self {
+        self.pause = Some(clock);
+        self
+    }
+
+    pub(crate) fn with_barriers(
+        mut self,
+        start: Option<Arc<Rendezvous>>,
+        stop: Option<Arc<Rendezvous>>,
+    ) -> Self {
+        self.start = start;
+        self.stop = stop;
+        self
+    }
+
+    pub(crate) fn with_startup_report(
+        mut self,
+        report: StartupReport,
+        threshold: Option<Duration>,
+    ) -> Self {
+        self.startup = Some((report, threshold));
+        self
+    }
+
+    pub(crate) fn with_warnings(mut self, warnings: crossbeam_channel::Sender<Warning>) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    pub(crate) fn with_ordered_start(mut self, release: Option<Arc<Rendezvous>>) -> Self {
+        self.release = release;
+        self
+    }
+
+    #[inline]
+    pub fn agent(&self) -> AgentId {
+        self.agent
+    }
+
+    /// Signals that the agent finished its setup. Only required when the
+    /// engine has a [startup timeout](crate::MultiAgentEngine::with_startup_timeout),
+    /// which then fails every agent that never calls it.
+    pub fn mark_ready(&self) {
+        self.record_startup();
+        if let Some(ready) = &self.ready {
+            let _ = ready.send(self.agent);
+        }
+    }
+
+    /// Blocks until both agents reached their start barrier, so that neither
+    /// sends before the other is listening. Breaks if cancelled while waiting.
+    /// Returns at once unless the engine was built
+    /// [`with_start_barrier`](crate::MultiAgentEngine::with_start_barrier).
+    ///
+    /// With an [ordered start](crate::MultiAgentEngine::with_ordered_start)
+    /// the simulator then keeps waiting until the controller
+    /// [released](Self::release_start) it.
+    pub fn wait_for_start(&self) -> ControlFlow<()> {
+        if let Some(start) = &self.start {
+            start.wait(&self.token)?;
+        }
+        match &self.release {
+            Some(release) if self.agent == AgentId::SIMULATOR => release.wait(&self.token),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+
+    /// Ends the controller's startup phase under an
+    /// [ordered start](crate::MultiAgentEngine::with_ordered_start), letting
+    /// the simulator past [`wait_for_start`](Self::wait_for_start) once every
+    /// startup message has been sent. Also happens at the controller's first
+    /// [`checkpoint`](Self::checkpoint), [`advance_frame`](Self::advance_frame)
+    /// or sent message, and when it returns. Does nothing for the simulator or
+    /// without an ordered start.
+    pub fn release_start(&self) {
+        if self.agent == AgentId::CONTROLLER
+            && let Some(release) = &self.release
+            && !self.released.swap(true, Ordering::AcqRel)
+        {
+            // Counts as the controller's start barrier in case it never
+            // waited at it, which a plain `run` cannot do.
+            if let Some(start) = &self.start {
+                start.arrive();
+            }
+            release.arrive();
+        }
+    }
+
+    /// Blocks until both agents reached their stop barrier, so that neither
+    /// drops its queues while the other still sends. Breaks if cancelled while
+    /// waiting. Returns at once unless the engine was built
+    /// [`with_stop_barrier`](crate::MultiAgentEngine::with_stop_barrier).
+    pub fn wait_for_stop(&self) -> ControlFlow<()> {
+        match &self.stop {
+            Some(stop) => stop.wait(&self.token),
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    #[inline]
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// A cooperative shutdown point to call once per frame. Beats the agent's
+    /// heartbeat, blocks while the engine is
+    /// [paused](crate::EngineHandle::pause) and breaks once cancellation was
+    /// requested, at which point the agent should return. It is cheap enough
+    /// to also call from inside a long frame, keeping it interruptible and
+    /// its heartbeat fresh.
+    pub fn checkpoint(&self) -> ControlFlow<()> {
+        self.beat();
+        self.record_startup();
+        self.release_start();
+        while self.is_paused() && !self.is_cancelled() {
+            thread::sleep(PAUSE_POLL);
+        }
+
+        if self.is_cancelled() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn beat(&self) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.beat();
+        }
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.pause.as_ref().is_some_and(SimClock::is_paused)
+    }
+
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::Acquire)
+    }
+
+    /// Advances the shared frame counter, releases messages held by
+    /// [`Sender::send_at_next_frame`](crate::message::Sender::send_at_next_frame),
+    /// reports the new frame to the observers as [`EngineEvent::Frame`], and
+    /// returns it. Also counts as [completing](Self::complete_frame) a frame
+    /// of this agent.
+    #[inline]
+    pub fn advance_frame(&self) -> u64 {
+        self.release_start();
+        self.complete_frame();
+        let frame = self.frame.fetch_add(1, Ordering::AcqRel) + 1;
+        self.frame_hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain_mut(|hook| hook());
+        self.emit(EngineEvent::Frame { frame });
+        frame
+    }
+
+    /// Counts a frame of this agent without touching the shared frame
+    /// counter, for agents running at their own rate, and returns how many
+    /// frames it completed so far. Beats the agent's heartbeat.
+    #[inline]
+    pub fn complete_frame(&self) -> u64 {
+        self.beat();
+        self.record_startup();
+        self.completed.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// Frames this agent completed, see [`complete_frame`](Self::complete_frame).
+    #[inline]
+    pub fn frames_completed(&self) -> u64 {
+        self.completed.load(Ordering::Acquire)
+    }
+
+    /// A local batch of frame advances for agents that advance the frame
+    /// often enough for the shared counter to become contended.
+    pub fn frame_batch(&self) -> FrameBatch {
+        FrameBatch::new(Arc::clone(&self.frame), self.observers.clone())
+    }
+
+    pub(crate) fn on_frame(&self, hook: impl FnMut() -> bool + Send + 'static) {
+        self.frame_hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(hook));
+    }
+
+    #[inline]
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub(crate) fn shared_clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn emit(&self, event: EngineEvent<'_>) {
+        let Some(observers) = &self.observers else {
+            return;
+        };
+
+        for observer in observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter_mut()
+        {
+            observer.observe(event);
+        }
+    }
+
+    /// Reports the messages sent from the calling agent thread to the
+    /// observers, see [`EngineEvent::Sent`], and records them in the engine
+    /// [`Metrics`].
+    pub(crate) fn observe_sends(&self) {
+        if let Some(observers) = &self.observers {
+            observer::observe_sends(self.agent, Arc::clone(observers));
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics::record_agent_sends(metrics.clone());
+        }
+    }
+
+    /// Ends the controller's startup phase at the first message it sends
+    /// from the calling thread, see [`release_start`](Self::release_start).
+    pub(crate) fn release_start_on_send(&self) {
+        if self.release.is_some() {
+            let context = self.clone();
+            rendezvous::release_on_send(move || context.release_start());
+        }
+    }
+
+    /// Hands a non-fatal warning to the engine's
+    /// [`WarningMonitor`](crate::WarningMonitor) and carries on; agents that
+    /// have to stop return an error from `run` instead. Dropped when the agent
+    /// runs without an engine.
+    pub fn report(&self, warning: Warning) {
+        if let Some(warnings) = &self.warnings {
+            let _ = warnings.send(warning);
+        }
+    }
+
+    fn record_startup(&self) {
+        let Some((report, threshold)) = &self.startup else {
+            return;
+        };
+
+        if let Some(latency) = report.record(self.agent, self.clock.now())
+            && threshold.is_some_and(|threshold| latency > threshold)
+        {
+            self.emit(EngineEvent::SlowStartup {
+                agent: self.agent,
+                latency,
+            });
+        }
+    }
+}
+
+impl Debug for AgentContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentContext")
+            .field("agent", &self.agent)
+            .field("token", &self.token)
+            .field("frame", &self.frame())
+            .field("clock", &self.clock)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,34 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::Result;
+
+/// What each agent returned, as far as known when the engine was joined.
+///
+/// An agent that was still running when a deadline passed, and was therefore
+/// detached, is `None`.
+#[derive(Debug)]
+pub struct AgentOutcomes {
+    pub controller: Option<Result<()>>,
+    pub simulator: Option<Result<()>>,
+}
+
+impl AgentOutcomes {
+    /// Whether both agents finished.
+    pub fn is_complete(&self) -> bool {
+        self.controller.is_some() && self.simulator.is_some()
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    CancellationToken, Heartbeat, PanicPolicy, ShutdownReason, Warning, panic,
+    thread_config::ThreadConfig,
+};
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use multi_agent_engine_core::{AgentId, Error, Result};
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+#[cfg(feature = "cpu-time")]
+use std::{sync::OnceLock, time::Duration};
+
+#[derive(Debug)]
+pub(crate) struct AgentThread {
+    handle: Option<JoinHandle<()>>,
+    done: Receiver<()>,
+    result: Arc<Mutex<Option<Result<()>>>>,
+    #[cfg(feature = "cpu-time")]
+    cpu_time: Arc<OnceLock<Duration>>,
+}
+
+impl AgentThread {
+    pub(crate) fn spawn<F>(
+        agent: AgentId,
+        heartbeat: Heartbeat,
+        config: ThreadConfig,
+        errored: Arc<AtomicBool>,
+        cancellation: CancellationToken,
+        run: F,
+    ) -> (Self, Receiver<Vec<Warning>>)
+    where
+        F: FnOnce() -> Result<()> + Send + 'static,
+    {
+        let (finished, done) = crossbeam_channel::bounded::<()>(0);
+        let (setup, warnings) = crossbeam_channel::bounded(1);
+        let result = Arc::new(Mutex::new(None));
+        let published = Arc::clone(&result);
+        #[cfg(feature = "cpu-time")]
+        let cpu_time = Arc::new(OnceLock::new());
+        #[cfg(feature = "cpu-time")]
+        let spent = Arc::clone(&cpu_time);
+
+        let handle = thread::spawn(move || {
+            let _finished = finished;
+            heartbeat.beat();
+            let _ = setup.send(config.apply(agent));
+            #[cfg(feature = "scheduler-hook")]
+            crate::scheduler_hook::yield_point(crate::SchedulePoint::Start);
+
+            let result = panic::catch_unwind(agent, run);
+            #[cfg(feature = "scheduler-hook")]
+            crate::scheduler_hook::finish();
+            if result.is_err() {
+                errored.store(true, Ordering::Release);
+            }
+            if matches!(result, Err(Error::AgentPanic { .. })) && config.panic == PanicPolicy::Abort
+            {
+                cancellation.cancel_with(ShutdownReason::Error);
+            }
+            #[cfg(feature = "cpu-time")]
+            if let Some(time) = crate::cpu_time::current_thread() {
+                let _ = spent.set(time);
+            }
+            *published.lock().unwrap_or_else(PoisonError::into_inner) = Some(result);
+        });
+
+        (
+            Self {
+                handle: Some(handle),
+                done,
+                result,
+                #[cfg(feature = "cpu-time")]
+                cpu_time,
+            },
+            warnings,
+        )
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    pub(crate) fn wait_deadline(&self, deadline: Instant) -> bool {
+        !matches!(
+            self.done.recv_deadline(deadline),
+            Err(RecvTimeoutError::Timeout)
+        )
+    }
+
+    /// CPU time the thread consumed, known once it has finished.
+    #[cfg(feature = "cpu-time")]
+    pub(crate) fn cpu_time(&self) -> Option<Duration> {
+        self.cpu_time.get().copied()
+    }
+
+    /// Takes the agent's result, published as soon as it returns, without
+    /// joining the thread. `None` while it is still running or once taken.
+    pub(crate) fn take_result(&self) -> Option<Result<()>> {
+        self.result
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Whether the agent returned an error that has not been taken yet.
+    pub(crate) fn has_failed(&self) -> bool {
+        matches!(
+            *self.result.lock().unwrap_or_else(PoisonError::into_inner),
+            Some(Err(_))
+        )
+    }
+
+    /// Joins the thread. Once joined, later calls return `Ok(())`.
+    pub(crate) fn join(&mut self) -> Result<()> {
+        let Some(handle) = self.handle.take() else {
+            return Ok(());
+        };
+        handle.join().map_err(Error::Thread)?;
+        self.take_result().unwrap_or(Ok(()))
+    }
+}
@@ -0,0 +1,140 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// A `Copy` value that packs into 64 bits, so [`SharedAtomic`](crate::SharedAtomic)
+/// can store it in a single atomic word.
+///
+/// `from_bits(into_bits(v))` must return `v`. Implement this for small state
+/// of your own, such as a flag set or a packed grid position.
+pub trait AtomicBits: Copy {
+    fn into_bits(self) -> u64;
+
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_atomic_bits {
+    ($($ty:ty => $bits:ty),* $(,)?) => {
+        $(
+            impl AtomicBits for $ty {
+                #[inline]
+                fn into_bits(self) -> u64 {
+                    self as $bits as u64
+                }
+
+                #[inline]
+                fn from_bits(bits: u64) -> Self {
+                    bits as $bits as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_bits! {
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize,
+}
+
+impl AtomicBits for bool {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        u64::from(self)
+    }
+
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+}
+
+impl AtomicBits for f32 {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        u64::from(self.to_bits())
+    }
+
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl AtomicBits for f64 {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        self.to_bits()
+    }
+
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+}
+
+impl<A: AtomicHalf, B: AtomicHalf> AtomicBits for (A, B) {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        (u64::from(self.0.into_half()) << 32) | u64::from(self.1.into_half())
+    }
+
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        (A::from_half((bits >> 32) as u32), B::from_half(bits as u32))
+    }
+}
+
+/// A 32-bit value, two of which pack into one [`AtomicBits`] pair.
+pub trait AtomicHalf: Copy {
+    fn into_half(self) -> u32;
+
+    fn from_half(bits: u32) -> Self;
+}
+
+impl AtomicHalf for u32 {
+    #[inline]
+    fn into_half(self) -> u32 {
+        self
+    }
+
+    #[inline]
+    fn from_half(bits: u32) -> Self {
+        bits
+    }
+}
+
+impl AtomicHalf for i32 {
+    #[inline]
+    fn into_half(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    fn from_half(bits: u32) -> Self {
+        bits as i32
+    }
+}
+
+impl AtomicHalf for f32 {
+    #[inline]
+    fn into_half(self) -> u32 {
+        self.to_bits()
+    }
+
+    #[inline]
+    fn from_half(bits: u32) -> Self {
+        f32::from_bits(bits)
+    }
+}
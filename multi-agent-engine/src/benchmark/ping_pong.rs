@@ -0,0 +1,86 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Controller, MultiAgentEngine, Simulator, benchmark::LatencyReport, message};
+use multi_agent_engine_core::{Error, Result};
+use std::{
+    mem,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Measures the round-trip latency of `iterations` messages bounced between
+/// a controller and a simulator over [`message::Queue`] channels.
+///
+/// This runs a full engine, so the numbers include thread wake-ups and give a
+/// baseline for what agents can expect on the current machine.
+pub fn ping_pong(iterations: usize) -> Result<LatencyReport> {
+    let (ping, pings) = message::Queue::channel();
+    let (pong, pongs) = message::Queue::channel();
+    let samples = Arc::new(Mutex::new(Vec::with_capacity(iterations)));
+
+    let pinger = Pinger {
+        iterations,
+        ping,
+        pongs,
+        samples: Arc::clone(&samples),
+    };
+    let ponger = Ponger { pings, pong };
+    MultiAgentEngine::new(pinger, ponger).run()?;
+
+    let samples = mem::take(&mut *samples.lock().unwrap_or_else(PoisonError::into_inner));
+    Ok(LatencyReport::from_samples(samples))
+}
+
+struct Pinger {
+    iterations: usize,
+    ping: message::Sender<Instant>,
+    pongs: message::Receiver<Instant>,
+    samples: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl Controller for Pinger {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let mut samples = Vec::with_capacity(self.iterations);
+        for _ in 0..self.iterations {
+            self.ping.send(Instant::now())?;
+            let sent = self.pongs.recv_blocking()?;
+            samples.push(sent.elapsed());
+        }
+        *self.samples.lock().unwrap_or_else(PoisonError::into_inner) = samples;
+
+        Ok(())
+    }
+}
+
+struct Ponger {
+    pings: message::Receiver<Instant>,
+    pong: message::Sender<Instant>,
+}
+
+impl Simulator for Ponger {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while let Ok(sent) = self.pings.recv_blocking() {
+            self.pong.send(sent)?;
+        }
+
+        Ok(())
+    }
+}
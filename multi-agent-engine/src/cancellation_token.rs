@@ -0,0 +1,117 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Error, Result, ShutdownReason};
+use std::sync::{
+    Arc, OnceLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    cancelled: AtomicBool,
+    watched: AtomicBool,
+    reason: OnceLock<ShutdownReason>,
+    parent: OnceLock<CancellationToken>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn child_token(&self) -> Self {
+        self.state.watched.store(true, Ordering::Relaxed);
+        Self {
+            state: Arc::new(State {
+                parent: OnceLock::from(self.clone()),
+                ..State::default()
+            }),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancel_with(ShutdownReason::Normal);
+    }
+
+    /// Cancels the token, recording `reason` unless it was already cancelled.
+    pub fn cancel_with(&self, reason: ShutdownReason) {
+        let _ = self.state.reason.set(reason);
+        self.state.cancelled.store(true, Ordering::Release);
+    }
+
+    /// The reason of the first cancellation of this token or, failing that,
+    /// of its nearest cancelled ancestor.
+    pub fn reason(&self) -> Option<ShutdownReason> {
+        self.state
+            .reason
+            .get()
+            .copied()
+            .or_else(|| self.state.parent.get()?.reason())
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.watched.store(true, Ordering::Relaxed);
+        self.state.cancelled.load(Ordering::Acquire)
+            || self
+                .state
+                .parent
+                .get()
+                .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Whether anyone checked this token, directly or through a token linked
+    /// to it, so that cancelling it is known to be noticed.
+    pub(crate) fn is_watched(&self) -> bool {
+        self.state.watched.load(Ordering::Relaxed)
+    }
+
+    /// Makes this token cancelled whenever `parent` is.
+    ///
+    /// Fails with [`Error::AlreadyLinked`] if this token already has a
+    /// parent, and with [`Error::CancellationCycle`] if `parent` is this
+    /// token or one of its descendants.
+    pub(crate) fn link_parent(&self, parent: &CancellationToken) -> Result<()> {
+        if parent.has_ancestor(self) {
+            return Err(Error::CancellationCycle);
+        }
+        self.state
+            .parent
+            .set(parent.clone())
+            .map_err(|_| Error::AlreadyLinked)?;
+        parent.state.watched.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Whether `token` is this token or one of its ancestors.
+    fn has_ancestor(&self, token: &CancellationToken) -> bool {
+        let mut current = self;
+        loop {
+            if Arc::ptr_eq(&current.state, &token.state) {
+                return true;
+            }
+            match current.state.parent.get() {
+                Some(parent) => current = parent,
+                None => return false,
+            }
+        }
+    }
+}
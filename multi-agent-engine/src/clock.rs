@@ -0,0 +1,198 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    fmt::Debug,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, dur: Duration);
+
+    /// Lets other threads run, as a receive waiting on an empty queue does
+    /// between checks.
+    fn yield_now(&self) {
+        thread::yield_now();
+    }
+
+    /// Whether the clock is the system's own, so that waits can block on the
+    /// operating system instead of polling the clock.
+    fn is_system(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[inline]
+    fn sleep(&self, dur: Duration) {
+        thread::sleep(dur);
+    }
+
+    #[inline]
+    fn is_system(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+    yields: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+            yields: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn advance(&self, dur: Duration) {
+        self.elapsed_nanos
+            .fetch_add(dur.as_nanos() as u64, Ordering::AcqRel);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::Acquire))
+    }
+
+    /// How many times a waiting receive [yielded](Clock::yield_now) on this
+    /// clock.
+    pub fn yields(&self) -> u64 {
+        self.yields.load(Ordering::Acquire)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    #[inline]
+    fn sleep(&self, dur: Duration) {
+        self.advance(dur);
+    }
+
+    #[inline]
+    fn yield_now(&self) {
+        self.yields.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// A clock that can be frozen, used by the engine so that
+/// [pausing](crate::EngineHandle::pause) it stops simulated time as well.
+///
+/// While paused, [`now`](Clock::now) keeps returning the instant of the pause;
+/// after resuming it continues from there, the paused wall-clock time cut out,
+/// so agents see no jump.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    inner: Arc<dyn Clock>,
+    pause: Arc<Mutex<Pause>>,
+}
+
+#[derive(Debug, Default)]
+struct Pause {
+    excluded: Duration,
+    since: Option<Instant>,
+}
+
+impl SimClock {
+    pub fn new() -> Self {
+        Self::wrapping(SystemClock)
+    }
+
+    pub fn wrapping(clock: impl Clock + 'static) -> Self {
+        Self::from_arc(Arc::new(clock))
+    }
+
+    pub(crate) fn from_arc(inner: Arc<dyn Clock>) -> Self {
+        Self {
+            inner,
+            pause: Arc::new(Mutex::new(Pause::default())),
+        }
+    }
+
+    pub fn pause(&self) {
+        let mut pause = self.lock();
+        if pause.since.is_none() {
+            pause.since = Some(self.inner.now());
+        }
+    }
+
+    pub fn resume(&self) {
+        let mut pause = self.lock();
+        if let Some(since) = pause.since.take() {
+            pause.excluded += self.inner.now().saturating_duration_since(since);
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lock().since.is_some()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pause> {
+        self.pause.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Instant {
+        let pause = self.lock();
+        pause.since.unwrap_or_else(|| self.inner.now()) - pause.excluded
+    }
+
+    #[inline]
+    fn sleep(&self, dur: Duration) {
+        self.inner.sleep(dur);
+    }
+
+    #[inline]
+    fn yield_now(&self) {
+        self.inner.yield_now();
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentionStats {
+    pub waits: u64,
+    pub max_wait: Duration,
+    pub total_wait: Duration,
+}
+
+impl ContentionStats {
+    pub(crate) fn record(&mut self, wait: Duration) {
+        self.waits += 1;
+        self.max_wait = self.max_wait.max(wait);
+        self.total_wait += wait;
+    }
+}
@@ -14,8 +14,28 @@
  * limitations under the License.
  */
 
-use multi_agent_engine_core::Result;
+use crate::{AgentContext, CancellationToken, SeededRng};
+use std::error;
 
 pub trait Controller {
-    fn run(self) -> Result<()>;
+    type Error: error::Error + Send + 'static;
+
+    fn run(self) -> Result<(), Self::Error>;
+
+    /// Entry point used by the engine. Override it instead of [`run`](Self::run)
+    /// to use the engine services in `ctx`.
+    fn run_with_context(self, _ctx: &AgentContext) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        self.run()
+    }
+
+    fn seed(&mut self, _rng: SeededRng) {}
+
+    /// Links the agent's own cancellation to `parent`, the token of its
+    /// [`AgentContext`], so that cancelling the engine reaches it.
+    fn link_cancellation(&mut self, _parent: &CancellationToken) -> crate::Result<()> {
+        Ok(())
+    }
 }
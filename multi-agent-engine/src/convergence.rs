@@ -0,0 +1,67 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{CancellationToken, EngineEvent, Observer, Shared};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Cancels the engine on the first frame at which `is_converged` holds for
+/// the shared state, see
+/// [`MultiAgentEngine::run_until_converged`](crate::MultiAgentEngine::run_until_converged).
+pub(crate) struct ConvergenceObserver<T, F> {
+    state: Shared<T>,
+    is_converged: F,
+    token: CancellationToken,
+    converged: Arc<AtomicBool>,
+}
+
+impl<T, F> ConvergenceObserver<T, F> {
+    pub(crate) fn new(
+        state: Shared<T>,
+        is_converged: F,
+        token: CancellationToken,
+    ) -> (Self, Arc<AtomicBool>) {
+        let converged = Arc::new(AtomicBool::new(false));
+
+        (
+            Self {
+                state,
+                is_converged,
+                token,
+                converged: Arc::clone(&converged),
+            },
+            converged,
+        )
+    }
+}
+
+impl<T, F> Observer for ConvergenceObserver<T, F>
+where
+    T: Send + Sync,
+    F: Fn(&T) -> bool + Send,
+{
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        if !matches!(event, EngineEvent::Frame { .. }) || self.converged.load(Ordering::Acquire) {
+            return;
+        }
+        if (self.is_converged)(&self.state.load()) {
+            self.converged.store(true, Ordering::Release);
+            self.token.cancel();
+        }
+    }
+}
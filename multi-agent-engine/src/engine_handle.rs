@@ -0,0 +1,540 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    AgentContext, AgentHealth, AgentOutcomes, CancellationToken, Health, Heartbeat, LogLevel,
+    LogSink, NoLog, ShutdownComplete, ShutdownOrder, SimClock, Simulator, TeardownReport, Warning,
+    agent_thread::AgentThread, thread_config::ThreadConfig,
+};
+use multi_agent_engine_core::{AgentId, Error, Result};
+use std::{
+    fmt::{self, Debug, Formatter},
+    mem,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Debug)]
+pub(crate) struct AgentSlot {
+    pub(crate) thread: AgentThread,
+    pub(crate) heartbeat: Heartbeat,
+    pub(crate) config: ThreadConfig,
+    pub(crate) context: AgentContext,
+}
+
+pub(crate) type BeforeJoin = Box<dyn FnOnce() + Send>;
+pub(crate) type ShutdownFlush = Box<dyn FnOnce() -> Result<()> + Send>;
+
+pub struct EngineHandle {
+    controller: AgentSlot,
+    simulator: Mutex<Option<AgentSlot>>,
+    warnings: Mutex<Vec<Warning>>,
+    heartbeat_timeout: Option<Duration>,
+    cancellation: CancellationToken,
+    shutdown_order: ShutdownOrder,
+    errored: Arc<AtomicBool>,
+    startup_failure: Option<AgentId>,
+    before_join: Option<BeforeJoin>,
+    shutdown_flush: Vec<ShutdownFlush>,
+    shutdown_grace: Duration,
+    teardown: TeardownReport,
+    clock: SimClock,
+    log_sink: Arc<dyn LogSink>,
+}
+
+impl EngineHandle {
+    pub(crate) fn new(
+        controller: AgentSlot,
+        simulator: AgentSlot,
+        warnings: Vec<Warning>,
+        heartbeat_timeout: Option<Duration>,
+        cancellation: CancellationToken,
+        shutdown_order: ShutdownOrder,
+        errored: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            controller,
+            simulator: Mutex::new(Some(simulator)),
+            warnings: Mutex::new(warnings),
+            heartbeat_timeout,
+            cancellation,
+            shutdown_order,
+            errored,
+            startup_failure: None,
+            before_join: None,
+            shutdown_flush: Vec::new(),
+            shutdown_grace: Duration::ZERO,
+            teardown: TeardownReport::default(),
+            clock: SimClock::new(),
+            log_sink: Arc::new(NoLog),
+        }
+    }
+
+    pub(crate) fn with_before_join(mut self, hook: Option<BeforeJoin>) -> Self {
+        self.before_join = hook;
+        self
+    }
+
+    pub(crate) fn with_shutdown_flush(mut self, flush: Vec<ShutdownFlush>) -> Self {
+        self.shutdown_flush = flush;
+        self
+    }
+
+    pub(crate) fn with_shutdown_grace(mut self, grace: Duration) -> Self {
+        self.shutdown_grace = grace;
+        self
+    }
+
+    pub(crate) fn with_teardown_report(mut self, report: TeardownReport) -> Self {
+        self.teardown = report;
+        self
+    }
+
+    pub(crate) fn with_clock(mut self, clock: SimClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub(crate) fn with_log_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.log_sink = sink;
+        self
+    }
+
+    pub(crate) fn with_startup_failure(mut self, agent: AgentId) -> Self {
+        self.startup_failure = Some(agent);
+        self
+    }
+
+    pub fn health(&self) -> Health {
+        let simulator = self.simulator();
+
+        Health {
+            controller: self.agent_health(&self.controller),
+            simulator: self.agent_health(Self::slot(&simulator)),
+            errored: self.errored.load(Ordering::Acquire) || self.startup_failure.is_some(),
+        }
+    }
+
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.lock_warnings().clone()
+    }
+
+    /// Freezes the engine: agents block in [`AgentContext::checkpoint`] and the
+    /// shared clock stops, so no simulated time passes until [`resume`].
+    ///
+    /// [`resume`]: Self::resume
+    pub fn pause(&self) {
+        self.clock.pause();
+    }
+
+    pub fn resume(&self) {
+        self.clock.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.clock.is_paused()
+    }
+
+    /// The clock shared by both agents' contexts.
+    pub fn clock(&self) -> &SimClock {
+        &self.clock
+    }
+
+    /// CPU time `agent` spent on its thread, available once that thread has
+    /// finished. A CPU time well below the wall time of the run means the
+    /// agent was mostly waiting. `None` on platforms without a per-thread CPU
+    /// clock, currently everything but Linux.
+    #[cfg(feature = "cpu-time")]
+    pub fn cpu_time(&self, agent: AgentId) -> Option<Duration> {
+        match agent {
+            AgentId::CONTROLLER => self.controller.thread.cpu_time(),
+            AgentId::SIMULATOR => Self::slot(&self.simulator()).thread.cpu_time(),
+            _ => None,
+        }
+    }
+
+    /// Frames `agent` completed so far, counted by
+    /// [`AgentContext::complete_frame`] and [`AgentContext::advance_frame`].
+    /// Unlike the shared frame counter this is kept per agent, so it shows
+    /// the rate each agent actually ran at.
+    pub fn frames_completed(&self, agent: AgentId) -> u64 {
+        match agent {
+            AgentId::CONTROLLER => self.controller.context.frames_completed(),
+            AgentId::SIMULATOR => Self::slot(&self.simulator()).context.frames_completed(),
+            _ => 0,
+        }
+    }
+
+    /// Replaces a simulator that has exited with a fresh instance built by
+    /// `factory`, and returns the outcome of the previous run.
+    ///
+    /// Fails with [`Error::AgentRunning`], leaving the engine untouched, while
+    /// the current simulator is still running, and likewise with the error of
+    /// [`Simulator::link_cancellation`] if the new instance cannot be linked.
+    /// The new instance is spawned with the same heartbeat, thread settings
+    /// and [`AgentContext`]; the engine counts as [errored](Health::errored)
+    /// again only if the controller failed, and the new thread's setup
+    /// warnings are added to [`warnings`](Self::warnings). `Shared` handles and channel endpoints
+    /// captured by `factory` are reused as-is: messages still queued when the
+    /// old instance stopped are preserved and delivered to the new one, while
+    /// a message the old instance had already received but not finished
+    /// handling is lost.
+    pub fn restart_simulator<S, F>(&self, factory: F) -> Result<()>
+    where
+        S: Simulator + Send + 'static,
+        F: FnOnce() -> S,
+    {
+        let mut slot = self.simulator();
+        if !Self::slot(&slot).thread.is_finished() {
+            return Err(Error::AgentRunning {
+                agent: AgentId::SIMULATOR,
+            });
+        }
+        let mut simulator = factory();
+        simulator.link_cancellation(Self::slot(&slot).context.cancellation_token())?;
+
+        let AgentSlot {
+            mut thread,
+            heartbeat,
+            config,
+            context,
+        } = slot
+            .take()
+            .expect("simulator slot is only vacated during a restart");
+        let previous = thread.join();
+        self.errored
+            .store(self.controller.thread.has_failed(), Ordering::Release);
+
+        let (thread, setup) = AgentThread::spawn(
+            AgentId::SIMULATOR,
+            heartbeat.clone(),
+            config.clone(),
+            Arc::clone(&self.errored),
+            self.cancellation.clone(),
+            {
+                let context = context.clone();
+                move || {
+                    context.observe_sends();
+                    simulator
+                        .run_with_context(&context)
+                        .map_err(|err| Error::from_agent(AgentId::SIMULATOR, err))
+                }
+            },
+        );
+        *slot = Some(AgentSlot {
+            thread,
+            heartbeat,
+            config,
+            context,
+        });
+        drop(slot);
+        if let Ok(warnings) = setup.recv() {
+            self.lock_warnings().extend(warnings);
+        }
+
+        previous
+    }
+
+    /// Cancels the agents in the configured [`ShutdownOrder`], waiting for
+    /// each one to exit before cancelling the next, then joins the engine.
+    /// Every agent is cancelled, even once an earlier one timed out.
+    ///
+    /// An agent is stopped through its own token, the one of its
+    /// [`AgentContext`] and [`Controller::link_cancellation`](crate::Controller::link_cancellation).
+    /// An agent that never checked that token, such as a plain `run` agent
+    /// watching only the engine's
+    /// [cancellation token](crate::MultiAgentEngine::cancellation_token), is
+    /// stopped by cancelling the engine token instead, which stops the agents
+    /// of later stages at the same time.
+    ///
+    /// With a [shutdown grace period](crate::MultiAgentEngine::with_shutdown_grace),
+    /// the stages share the grace period to exit cooperatively. Agents still
+    /// running after it are escalated to the engine token, which stops every
+    /// agent at once, and get `timeout` more. Without one, the stages share
+    /// `timeout` instead. Agents still running in the end are detached and
+    /// this fails with [`Error::Timeout`].
+    pub fn shutdown(mut self, timeout: Duration) -> Result<()> {
+        let grace = self.shutdown_grace;
+        let finished = if grace.is_zero() {
+            self.cancel_stages(Instant::now() + timeout)
+        } else {
+            self.cancel_stages(Instant::now() + grace) || {
+                self.cancellation.cancel();
+                self.wait_deadline(Instant::now() + timeout)
+            }
+        };
+
+        if finished {
+            self.join()
+        } else {
+            self.timed_out(grace + timeout)
+        }
+    }
+
+    /// Cancels the [`ShutdownOrder`] stages in turn, each waiting until
+    /// `deadline` at most, and returns whether every agent exited.
+    fn cancel_stages(&self, deadline: Instant) -> bool {
+        let simulator = self.simulator();
+        let simulator = Self::slot(&simulator);
+
+        let stages = match self.shutdown_order {
+            ShutdownOrder::Simultaneous => {
+                self.cancellation.cancel();
+                [&self.controller, simulator]
+            }
+            ShutdownOrder::ControllerFirst => [&self.controller, simulator],
+            ShutdownOrder::SimulatorFirst => [simulator, &self.controller],
+        };
+
+        let mut finished = true;
+        for slot in stages {
+            let token = slot.context.cancellation_token();
+            if token.is_watched() {
+                token.cancel();
+            } else {
+                self.cancellation.cancel();
+            }
+            finished &= slot.thread.wait_deadline(deadline);
+        }
+        finished
+    }
+
+    fn wait_deadline(&self, deadline: Instant) -> bool {
+        self.controller.thread.wait_deadline(deadline)
+            && Self::slot(&self.simulator()).thread.wait_deadline(deadline)
+    }
+
+    /// Cancels agents still running after a join timeout and gives them the
+    /// [shutdown grace period](crate::MultiAgentEngine::with_shutdown_grace)
+    /// to exit, returning whether they did. Without a grace period they are
+    /// still cancelled, just not waited for.
+    fn cancel_within_grace(&self) -> bool {
+        self.cancellation.cancel();
+        if self.shutdown_grace.is_zero() {
+            return false;
+        }
+
+        self.wait_deadline(Instant::now() + self.shutdown_grace)
+    }
+
+    /// Waits for both agents to finish. This never cancels nor detaches them,
+    /// so the [shutdown grace period](crate::MultiAgentEngine::with_shutdown_grace)
+    /// does not apply.
+    pub fn join(mut self) -> Result<()> {
+        self.join_agents()
+    }
+
+    /// Like [`join`](Self::join), but returns the controller's and the
+    /// simulator's results separately instead of combining them, so the
+    /// result of an agent that succeeded is kept when the other one failed.
+    /// An agent that failed to start reports [`Error::StartupFailed`].
+    /// Errors of [shutdown flushes](crate::MultiAgentEngine::with_shutdown_flush)
+    /// are not reported.
+    pub fn join_detailed(mut self) -> (Result<()>, Result<()>) {
+        let (mut controller, mut simulator, _) = self.join_each();
+
+        if let Some(agent) = self.startup_failure.take() {
+            let failed = match agent {
+                AgentId::CONTROLLER => &mut controller,
+                _ => &mut simulator,
+            };
+            if failed.is_ok() {
+                *failed = Err(Error::StartupFailed { agent });
+            }
+        }
+
+        (controller, simulator)
+    }
+
+    /// Hands the engine to a future that resolves with the [`join`](Self::join)
+    /// result once both agents have finished, for supervising async tasks.
+    pub fn shutdown_complete(self) -> ShutdownComplete {
+        ShutdownComplete::new(self)
+    }
+
+    /// Joins the engine if both agents have finished, without blocking.
+    ///
+    /// Returns `None` while either agent is still running. After the result
+    /// has been returned once, later calls return `Some(Ok(()))`.
+    pub fn try_join(&mut self) -> Option<Result<()>> {
+        let finished = self.controller.thread.is_finished()
+            && Self::slot(&self.simulator()).thread.is_finished();
+
+        finished.then(|| self.join_agents())
+    }
+
+    /// Waits up to `dur` for both agents to finish. Agents still running are
+    /// then cancelled and given the
+    /// [shutdown grace period](crate::MultiAgentEngine::with_shutdown_grace)
+    /// before they are detached and this fails with [`Error::Timeout`].
+    pub fn join_timeout(mut self, dur: Duration) -> Result<()> {
+        let finished = self.wait_deadline(Instant::now() + dur) || self.cancel_within_grace();
+
+        if finished {
+            self.join()
+        } else {
+            self.timed_out(dur + self.shutdown_grace)
+        }
+    }
+
+    /// Like [`join_timeout`](Self::join_timeout), but reports what each agent
+    /// returned. Agents still running once `dur` and the grace period have
+    /// elapsed are detached and reported as `None`, while the results of
+    /// those that finished in time are kept. Errors of
+    /// [shutdown flushes](crate::MultiAgentEngine::with_shutdown_flush) are
+    /// not reported.
+    pub fn join_timeout_outcomes(mut self, dur: Duration) -> AgentOutcomes {
+        let finished = self.wait_deadline(Instant::now() + dur) || self.cancel_within_grace();
+
+        if finished {
+            let (controller, simulator, _) = self.join_each();
+            AgentOutcomes {
+                controller: Some(controller),
+                simulator: Some(simulator),
+            }
+        } else {
+            self.teardown.record();
+            let _ = self.flush();
+            AgentOutcomes {
+                controller: self.controller.thread.take_result(),
+                simulator: Self::slot(&self.simulator()).thread.take_result(),
+            }
+        }
+    }
+
+    fn join_each(&mut self) -> (Result<()>, Result<()>, Result<()>) {
+        let simulator = self
+            .simulator
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+            .expect("simulator slot is only vacated during a restart");
+
+        let controller = self.controller.thread.join();
+        let simulator = simulator.thread.join();
+        self.teardown.record();
+        // Only the first join logs, later ones see already joined threads.
+        let sink = mem::replace(&mut self.log_sink, Arc::new(NoLog));
+        for (agent, result) in [
+            (AgentId::CONTROLLER, &controller),
+            (AgentId::SIMULATOR, &simulator),
+        ] {
+            if let Err(err) = result {
+                sink.log(LogLevel::Error, &format!("{agent} failed: {err}"));
+            }
+        }
+        sink.log(LogLevel::Info, "engine stopped");
+        let flushed = self.flush();
+
+        if let Some(hook) = self.before_join.take() {
+            hook();
+        }
+
+        (controller, simulator, flushed)
+    }
+
+    fn join_agents(&mut self) -> Result<()> {
+        let (controller, simulator, flushed) = self.join_each();
+        let startup = match self.startup_failure.take() {
+            Some(agent) => Err(Error::StartupFailed { agent }),
+            None => Ok(()),
+        };
+
+        combine([startup, controller, simulator, flushed])
+    }
+
+    /// Flushes recordings after agents had to be detached, reporting the
+    /// timeout together with any flush error.
+    fn timed_out(&mut self, duration: Duration) -> Result<()> {
+        self.log_sink.log(
+            LogLevel::Warn,
+            &format!("engine did not stop within {duration:?}"),
+        );
+        self.teardown.record();
+        let flushed = self.flush();
+        combine([Err(Error::Timeout { duration }), flushed])
+    }
+
+    /// Runs every shutdown flush once, returning the first error.
+    fn flush(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for flush in mem::take(&mut self.shutdown_flush) {
+            let flushed = flush();
+            if result.is_ok() {
+                result = flushed;
+            }
+        }
+        result
+    }
+
+    fn lock_warnings(&self) -> MutexGuard<'_, Vec<Warning>> {
+        self.warnings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn simulator(&self) -> MutexGuard<'_, Option<AgentSlot>> {
+        self.simulator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn slot(slot: &Option<AgentSlot>) -> &AgentSlot {
+        slot.as_ref()
+            .expect("simulator slot is only vacated during a restart")
+    }
+
+    fn agent_health(&self, slot: &AgentSlot) -> AgentHealth {
+        let alive = !slot.thread.is_finished();
+        let last_heartbeat = slot.heartbeat.age();
+        let fresh = self
+            .heartbeat_timeout
+            .is_none_or(|timeout| last_heartbeat <= timeout);
+
+        AgentHealth {
+            alive,
+            last_heartbeat,
+            healthy: alive && fresh,
+        }
+    }
+}
+
+fn combine(results: impl IntoIterator<Item = Result<()>>) -> Result<()> {
+    let mut errors: Vec<Error> = results.into_iter().filter_map(Result::err).collect();
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(Error::Multiple(errors)),
+    }
+}
+
+impl Debug for EngineHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EngineHandle")
+            .field("controller", &self.controller)
+            .field("simulator", &self.simulator)
+            .field("warnings", &self.warnings)
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .field("shutdown_order", &self.shutdown_order)
+            .field("startup_failure", &self.startup_failure)
+            .field("shutdown_grace", &self.shutdown_grace)
+            .field("teardown", &self.teardown)
+            .field("clock", &self.clock)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{InspectorState, Metrics, QueueState, message::MessageTap};
+
+#[derive(Debug, Clone)]
+pub struct EngineInspector {
+    metrics: Metrics,
+    queues: Vec<(String, MessageTap)>,
+}
+
+impl EngineInspector {
+    pub fn new(metrics: Metrics) -> Self {
+        Self {
+            metrics,
+            queues: Vec::new(),
+        }
+    }
+
+    pub fn with_queue(mut self, name: impl Into<String>, tap: &MessageTap) -> Self {
+        self.queues.push((name.into(), tap.clone()));
+        self
+    }
+
+    pub fn state(&self) -> InspectorState {
+        InspectorState {
+            frames: self.metrics.frames(),
+            frame_time: self.metrics.frame_time(),
+            messages_sent: self.metrics.messages_sent(),
+            messages_dropped: self.metrics.messages_dropped(),
+            queues: self
+                .queues
+                .iter()
+                .map(|(name, tap)| QueueState {
+                    name: name.clone(),
+                    depth: tap.depth(),
+                    recent: tap.recent(),
+                })
+                .collect(),
+        }
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn ui(&self, ctx: &egui::Context) {
+        let state = self.state();
+
+        egui::Window::new("Engine Inspector").show(ctx, |ui| {
+            egui::Grid::new("engine_inspector_metrics").show(ui, |ui| {
+                ui.label("Frames");
+                ui.label(state.frames.to_string());
+                ui.end_row();
+                ui.label("Frame time");
+                ui.label(format!("{:?}", state.frame_time));
+                ui.end_row();
+                ui.label("Messages sent");
+                ui.label(state.messages_sent.to_string());
+                ui.end_row();
+                ui.label("Messages dropped");
+                ui.label(state.messages_dropped.to_string());
+                ui.end_row();
+            });
+
+            for queue in &state.queues {
+                ui.separator();
+                ui.collapsing(format!("{} ({} queued)", queue.name, queue.depth), |ui| {
+                    for msg in &queue.recent {
+                        ui.monospace(msg);
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// Wires a controller and a simulator together and builds the engine.
+///
+/// Both agent structs must have `config`, `state`, `sender` and `receiver`
+/// fields. `config` and `state` are [`Shared`](crate::Shared) handles, cloned
+/// into both agents. The macro creates one [`Queue`](crate::message::Queue)
+/// channel per direction and always connects the controller's `sender` to the
+/// simulator's `receiver` and the simulator's `sender` to the controller's
+/// `receiver`; only the message types are inferred, from those fields. It
+/// evaluates to a [`MultiAgentEngine`](crate::MultiAgentEngine), ready for
+/// further builder calls.
+///
+/// ```
+/// use multi_agent_engine::{Controller, Error, Result, Shared, Simulator, message};
+///
+/// struct Ping {
+///     config: Shared<u32>,
+///     state: Shared<u32>,
+///     sender: message::Sender<u32>,
+///     receiver: message::Receiver<u32>,
+/// }
+///
+/// impl Controller for Ping {
+///     type Error = Error;
+///
+///     fn run(self) -> Result<()> {
+///         self.sender.send(**self.config.load())?;
+///         let pong = self.receiver.recv_blocking()?;
+///         self.state.store(pong);
+///         Ok(())
+///     }
+/// }
+///
+/// struct Pong {
+///     config: Shared<u32>,
+///     state: Shared<u32>,
+///     sender: message::Sender<u32>,
+///     receiver: message::Receiver<u32>,
+/// }
+///
+/// impl Simulator for Pong {
+///     type Error = Error;
+///
+///     fn run(self) -> Result<()> {
+///         let ping = self.receiver.recv_blocking()?;
+///         self.sender.send(ping + **self.config.load() + **self.state.load())
+///     }
+/// }
+///
+/// let state = Shared::new(1);
+/// multi_agent_engine::engine! {
+///     controller: Ping,
+///     simulator: Pong,
+///     config: Shared::new(20),
+///     state: state.clone(),
+/// }
+/// .run()
+/// .unwrap();
+///
+/// assert_eq!(**state.load(), 41);
+/// ```
+///
+/// The directions are not swapped to match the types, so a simulator
+/// receiving something else than the controller sends does not compile:
+///
+/// ```compile_fail
+/// use multi_agent_engine::{Controller, Error, Result, Shared, Simulator, message};
+///
+/// struct Ping {
+///     config: Shared<()>,
+///     state: Shared<()>,
+///     sender: message::Sender<u32>,
+///     receiver: message::Receiver<String>,
+/// }
+///
+/// impl Controller for Ping {
+///     type Error = Error;
+///
+///     fn run(self) -> Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// struct Pong {
+///     config: Shared<()>,
+///     state: Shared<()>,
+///     sender: message::Sender<u32>,
+///     receiver: message::Receiver<String>,
+/// }
+///
+/// impl Simulator for Pong {
+///     type Error = Error;
+///
+///     fn run(self) -> Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let _ = multi_agent_engine::engine! {
+///     controller: Ping,
+///     simulator: Pong,
+///     config: Shared::new(()),
+///     state: Shared::new(()),
+/// };
+/// ```
+#[macro_export]
+macro_rules! engine {
+    (
+        controller: $controller:ident,
+        simulator: $simulator:ident,
+        config: $config:expr,
+        state: $state:expr $(,)?
+    ) => {{
+        let (controller_sender, controller_receiver) = $crate::message::Queue::channel();
+        let (simulator_sender, simulator_receiver) = $crate::message::Queue::channel();
+        let config: $crate::Shared<_> = $config;
+        let state: $crate::Shared<_> = $state;
+
+        $crate::MultiAgentEngine::new(
+            $controller {
+                config: config.clone(),
+                state: state.clone(),
+                sender: controller_sender,
+                receiver: simulator_receiver,
+            },
+            $simulator {
+                config,
+                state,
+                sender: simulator_sender,
+                receiver: controller_receiver,
+            },
+        )
+    }};
+}
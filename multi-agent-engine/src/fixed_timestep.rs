@@ -0,0 +1,128 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{AgentContext, Clock, EngineEvent, SystemClock};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Paces a loop at a fixed interval and reports every frame that took longer
+/// than the interval as [`EngineEvent::DeadlineMissed`].
+///
+/// Time is read from the timestep's [`Clock`]. With a
+/// [`SimClock`](crate::SimClock), such as the one behind
+/// [`with_context`](Self::with_context), time spent
+/// [paused](crate::EngineHandle::pause) does not count, so resuming neither
+/// reports a missed deadline nor makes [`due_steps`](Self::due_steps) catch up
+/// on the pause in one burst.
+#[derive(Debug)]
+pub struct FixedTimestep {
+    interval: Duration,
+    frame: u64,
+    frame_start: Instant,
+    missed: u64,
+    clock: Arc<dyn Clock>,
+    context: Option<AgentContext>,
+}
+
+impl FixedTimestep {
+    pub fn new(interval: Duration) -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            interval,
+            frame: 0,
+            frame_start: clock.now(),
+            missed: 0,
+            clock,
+            context: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.frame_start = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Reports missed deadlines to the observers of `ctx` and paces the loop
+    /// with its clock.
+    pub fn with_context(mut self, ctx: &AgentContext) -> Self {
+        self.clock = ctx.shared_clock();
+        self.frame_start = self.clock.now();
+        self.context = Some(ctx.clone());
+        self
+    }
+
+    #[inline]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Number of frames ended by [`tick`](Self::tick) so far.
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    #[inline]
+    pub fn missed_deadlines(&self) -> u64 {
+        self.missed
+    }
+
+    /// For loops that step a simulation as fast as they can render instead of
+    /// sleeping: returns how many whole intervals passed since the previous
+    /// call and keeps the remainder for the next one. Use either this or
+    /// [`tick`](Self::tick), not both.
+    pub fn due_steps(&mut self) -> u64 {
+        let elapsed = self.clock.now().saturating_duration_since(self.frame_start);
+        let interval = self.interval.as_nanos().max(1);
+        let due = elapsed.as_nanos() / interval;
+
+        self.frame_start += Duration::from_nanos((due * interval) as u64);
+        self.frame += due as u64;
+        due as u64
+    }
+
+    /// Ends the current frame. Sleeps for the rest of the interval, or returns
+    /// how far the frame overran it without sleeping.
+    pub fn tick(&mut self) -> Option<Duration> {
+        let elapsed = self.clock.now().saturating_duration_since(self.frame_start);
+        self.frame += 1;
+
+        let overrun = match self.interval.checked_sub(elapsed) {
+            Some(remaining) => {
+                self.clock.sleep(remaining);
+                None
+            }
+            None => {
+                let overrun = elapsed - self.interval;
+                self.missed += 1;
+                if let Some(ctx) = &self.context {
+                    ctx.emit(EngineEvent::DeadlineMissed {
+                        frame: self.frame,
+                        overrun,
+                    });
+                }
+                Some(overrun)
+            }
+        };
+
+        self.frame_start = self.clock.now();
+        overrun
+    }
+}
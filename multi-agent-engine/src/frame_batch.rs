@@ -0,0 +1,102 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{EngineEvent, agent_context::Observers};
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        Arc, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Accumulates frame advances locally and adds them to the shared frame
+/// counter in one atomic operation.
+///
+/// Until a flush, readers of [`AgentContext::frame`](crate::AgentContext::frame)
+/// see a count that lags by the advances still pending here. The count is
+/// exact again once every batch has flushed, which happens every
+/// `flush_every` advances, on [`flush`](Self::flush), and on drop. Observers
+/// get an [`EngineEvent::Frame`] for every frame of a flush.
+pub struct FrameBatch {
+    frame: Arc<AtomicU64>,
+    observers: Option<Observers>,
+    pending: u64,
+    flush_every: u64,
+}
+
+impl FrameBatch {
+    pub(crate) fn new(frame: Arc<AtomicU64>, observers: Option<Observers>) -> Self {
+        Self {
+            frame,
+            observers,
+            pending: 0,
+            flush_every: u64::MAX,
+        }
+    }
+
+    pub fn with_flush_every(mut self, advances: u64) -> Self {
+        self.flush_every = advances.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn advance(&mut self) {
+        self.pending += 1;
+        if self.pending >= self.flush_every {
+            self.flush();
+        }
+    }
+
+    #[inline]
+    pub fn pending(&self) -> u64 {
+        self.pending
+    }
+
+    /// Publishes the pending advances and returns the new shared frame.
+    pub fn flush(&mut self) -> u64 {
+        let pending = std::mem::take(&mut self.pending);
+        let frame = self.frame.fetch_add(pending, Ordering::AcqRel) + pending;
+
+        if let Some(observers) = &self.observers {
+            let mut observers = observers.lock().unwrap_or_else(PoisonError::into_inner);
+            for frame in frame - pending + 1..=frame {
+                for observer in observers.iter_mut() {
+                    observer.observe(EngineEvent::Frame { frame });
+                }
+            }
+        }
+        frame
+    }
+}
+
+impl Debug for FrameBatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameBatch")
+            .field("frame", &self.frame)
+            .field("pending", &self.pending)
+            .field("flush_every", &self.flush_every)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for FrameBatch {
+    fn drop(&mut self) {
+        if self.pending > 0 {
+            self.flush();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{AgentContext, Clock, EngineEvent, SystemClock};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Flags single frames that ran over a soft budget, reporting them as
+/// [`EngineEvent::SlowFrame`] with the time split between receiving and the
+/// rest of the frame.
+///
+/// Unlike [`FixedTimestep`](crate::FixedTimestep) it never sleeps, so it can
+/// watch loops paced by something else.
+#[derive(Debug)]
+pub struct FrameWatchdog {
+    budget: Duration,
+    frame: u64,
+    frame_start: Instant,
+    received_at: Option<Instant>,
+    slow: u64,
+    clock: Arc<dyn Clock>,
+    context: Option<AgentContext>,
+}
+
+impl FrameWatchdog {
+    pub fn new(budget: Duration) -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            budget,
+            frame: 0,
+            frame_start: clock.now(),
+            received_at: None,
+            slow: 0,
+            clock,
+            context: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.frame_start = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Reports slow frames to the observers of `ctx` and times frames with
+    /// its clock.
+    pub fn with_context(mut self, ctx: &AgentContext) -> Self {
+        self.clock = ctx.shared_clock();
+        self.frame_start = self.clock.now();
+        self.context = Some(ctx.clone());
+        self
+    }
+
+    #[inline]
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    #[inline]
+    pub fn slow_frames(&self) -> u64 {
+        self.slow
+    }
+
+    pub fn start_frame(&mut self) {
+        self.frame_start = self.clock.now();
+        self.received_at = None;
+    }
+
+    /// Marks the end of the receive phase of the current frame. Without it
+    /// the whole frame is counted as compute.
+    pub fn mark_received(&mut self) {
+        self.received_at = Some(self.clock.now());
+    }
+
+    /// Ends the current frame and returns its duration if it exceeded the
+    /// budget. The next frame starts now unless
+    /// [`start_frame`](Self::start_frame) is called.
+    pub fn end_frame(&mut self) -> Option<Duration> {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.frame_start);
+        let receive = self.received_at.map_or(Duration::ZERO, |at| {
+            at.saturating_duration_since(self.frame_start)
+        });
+        self.frame += 1;
+        self.frame_start = now;
+        self.received_at = None;
+
+        if elapsed <= self.budget {
+            return None;
+        }
+
+        self.slow += 1;
+        if let Some(ctx) = &self.context {
+            ctx.emit(EngineEvent::SlowFrame {
+                frame: self.frame,
+                elapsed,
+                receive,
+            });
+        }
+        Some(elapsed)
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Clock, SystemClock};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    clock: Arc<dyn Clock>,
+    origin: Instant,
+    last_nanos: Arc<AtomicU64>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            origin: clock.now(),
+            clock,
+            last_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.origin = clock.now();
+        self.clock = Arc::new(clock);
+        self.last_nanos.store(0, Ordering::Release);
+        self
+    }
+
+    pub fn beat(&self) {
+        let nanos = self.clock.now().duration_since(self.origin).as_nanos() as u64;
+        self.last_nanos.fetch_max(nanos, Ordering::AcqRel);
+    }
+
+    pub fn age(&self) -> Duration {
+        let last = self.origin + Duration::from_nanos(self.last_nanos.load(Ordering::Acquire));
+        self.clock.now().saturating_duration_since(last)
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
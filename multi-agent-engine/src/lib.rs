@@ -14,16 +14,128 @@
  * limitations under the License.
  */
 
+mod adaptive_rate;
+mod agent_context;
+mod agent_outcomes;
+mod agent_thread;
+mod atomic_bits;
+mod cancellation_token;
+mod clock;
+mod commit_outcome;
+mod contention_stats;
 mod controller;
+mod convergence;
+#[cfg(feature = "cpu-time")]
+mod cpu_time;
+mod diff;
+mod engine_handle;
+mod engine_inspector;
+mod engine_macro;
+mod fixed_timestep;
+mod frame_batch;
+mod frame_watchdog;
+mod health;
+mod heartbeat;
+mod inspector_state;
+mod log_sink;
+mod metrics;
+#[cfg(feature = "prometheus")]
+mod metrics_collector;
 mod multi_agent_engine;
+mod no_agent;
+mod observer;
+mod panic;
+mod panic_policy;
+mod patch;
+#[cfg(feature = "scheduler-hook")]
+mod pct_scheduler;
+mod pipeline;
+mod rate_relation;
+mod read_only;
+mod rendezvous;
+mod reproduction;
+#[cfg(feature = "rerun")]
+mod rerun_sink;
+mod runtime;
+#[cfg(feature = "scheduler-hook")]
+mod scheduler_hook;
+mod seed_log;
+mod seeded_rng;
 mod shared;
+mod shared_atomic;
+mod shared_rcu;
+mod shutdown_complete;
+mod shutdown_order;
+mod shutdown_reason;
 mod simulator;
+mod startup_report;
+mod step;
+mod stepped_engine;
+mod teardown_report;
+mod thread_config;
+mod two_phase_commit;
+mod warning;
+mod warning_monitor;
 
+pub mod benchmark;
 pub mod message;
+pub mod record;
+pub mod transport;
 
+pub use adaptive_rate::AdaptiveRate;
+pub use agent_context::AgentContext;
+pub use agent_outcomes::AgentOutcomes;
+pub use atomic_bits::{AtomicBits, AtomicHalf};
+pub use cancellation_token::CancellationToken;
+pub use clock::{Clock, MockClock, SimClock, SystemClock};
+pub use commit_outcome::CommitOutcome;
+pub use contention_stats::ContentionStats;
 pub use controller::Controller;
+pub use diff::Diff;
+pub use engine_handle::EngineHandle;
+pub use engine_inspector::EngineInspector;
+pub use fixed_timestep::FixedTimestep;
+pub use frame_batch::FrameBatch;
+pub use frame_watchdog::FrameWatchdog;
+pub use health::{AgentHealth, Health};
+pub use heartbeat::Heartbeat;
+pub use inspector_state::{InspectorState, QueueState};
+pub use log_sink::{LogLevel, LogSink, NoLog, StderrLog};
+pub use metrics::Metrics;
 pub use multi_agent_engine::MultiAgentEngine;
+pub use no_agent::NoAgent;
+pub use observer::{EngineEvent, Observer};
+pub use panic_policy::PanicPolicy;
+pub use patch::Patch;
+pub use pipeline::Pipeline;
+pub use rate_relation::RateRelation;
+pub use read_only::ReadOnly;
+pub use reproduction::Reproduction;
+pub use runtime::Runtime;
+pub use seed_log::SeedLog;
+pub use seeded_rng::SeededRng;
 pub use shared::Shared;
+pub use shared_atomic::SharedAtomic;
+pub use shared_rcu::SharedRcu;
+pub use shutdown_complete::ShutdownComplete;
+pub use shutdown_order::ShutdownOrder;
+pub use shutdown_reason::ShutdownReason;
 pub use simulator::Simulator;
+pub use startup_report::StartupReport;
+pub use step::Step;
+pub use stepped_engine::{StepOutcome, SteppedEngine};
+pub use teardown_report::{TeardownReport, UndrainedQueue};
+pub use two_phase_commit::TwoPhaseCommit;
+pub use warning::Warning;
+pub use warning_monitor::WarningMonitor;
 
-pub use multi_agent_engine_core::{Error, Result};
+#[cfg(feature = "scheduler-hook")]
+pub use pct_scheduler::PctScheduler;
+#[cfg(feature = "rerun")]
+pub use rerun_sink::RerunSink;
+#[cfg(feature = "scheduler-hook")]
+pub use scheduler_hook::{SchedulePoint, SchedulerHook};
+#[cfg(feature = "thread-priority")]
+pub use thread_priority::ThreadPriority;
+
+pub use multi_agent_engine_core::{AgentId, Endpoint, Error, Result};
@@ -0,0 +1,63 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        };
+        f.write_str(level)
+    }
+}
+
+/// Receives the engine's lifecycle messages, such as its start and stop,
+/// spawn-time [`Warning`](crate::Warning)s and agent failures. A lightweight
+/// alternative to a full tracing setup, installed with
+/// [`MultiAgentEngine::with_log_sink`](crate::MultiAgentEngine::with_log_sink).
+pub trait LogSink: Debug + Send + Sync {
+    fn log(&self, level: LogLevel, msg: &str);
+}
+
+/// Discards every message; the engine's default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoLog;
+
+impl LogSink for NoLog {
+    fn log(&self, _level: LogLevel, _msg: &str) {}
+}
+
+/// Writes every message to stderr, prefixed with its level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrLog;
+
+impl LogSink for StderrLog {
+    fn log(&self, level: LogLevel, msg: &str) {
+        eprintln!("[{level}] {msg}");
+    }
+}
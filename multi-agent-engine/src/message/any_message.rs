@@ -0,0 +1,89 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+type Pending = HashMap<TypeId, Vec<Box<dyn Any + Send>>>;
+
+/// A queue carrying messages of any type, for agents that exchange a few
+/// unrelated message types without an umbrella enum.
+///
+/// [`receive::<T>`](Self::receive) only takes the messages of type `T`; the
+/// others stay queued for their own typed receive. Each message is boxed, so
+/// prefer a typed [`Queue`](super::Queue) on hot paths.
+#[derive(Debug)]
+pub struct AnyMessage {
+    pending: Arc<Mutex<Pending>>,
+}
+
+impl AnyMessage {
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::default(),
+        }
+    }
+
+    pub fn send<T: Any + Send>(&self, msg: T) {
+        self.lock()
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(Box::new(msg));
+    }
+
+    /// Drains the queued messages of type `T` in the order they were sent.
+    pub fn receive<T: Any>(&self) -> Vec<T> {
+        let batch = self.lock().remove(&TypeId::of::<T>()).unwrap_or_default();
+
+        batch
+            .into_iter()
+            .map(|msg| {
+                *msg.downcast::<T>()
+                    .expect("messages are filed under their own type id")
+            })
+            .collect()
+    }
+
+    /// Number of queued messages across all types.
+    pub fn len(&self) -> usize {
+        self.lock().values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for AnyMessage {
+    fn clone(&self) -> Self {
+        Self {
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+impl Default for AnyMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
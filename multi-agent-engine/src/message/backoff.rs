@@ -0,0 +1,70 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// Escalation policy for [`Receiver::receive_backoff`](super::Receiver::receive_backoff).
+///
+/// While the queue stays empty the receiver first spins in bursts that double
+/// in length up to `2^spin_limit` iterations, checking the queue on each, then
+/// yields the thread
+/// `yield_limit` times, and finally parks in a blocking receive. Short gaps are
+/// thereby caught without a wake-up, while long idle periods cost no CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    spin_limit: u32,
+    yield_limit: u32,
+}
+
+impl Backoff {
+    /// The highest [`spin_limit`](Self::spin_limit), a final burst of 1024
+    /// iterations.
+    pub const MAX_SPIN_LIMIT: u32 = 10;
+
+    pub const fn new() -> Self {
+        Self {
+            spin_limit: 6,
+            yield_limit: 10,
+        }
+    }
+
+    /// Clamped to [`MAX_SPIN_LIMIT`](Self::MAX_SPIN_LIMIT).
+    pub const fn with_spin_limit(mut self, limit: u32) -> Self {
+        self.spin_limit = if limit < Self::MAX_SPIN_LIMIT {
+            limit
+        } else {
+            Self::MAX_SPIN_LIMIT
+        };
+        self
+    }
+
+    pub const fn with_yield_limit(mut self, limit: u32) -> Self {
+        self.yield_limit = limit;
+        self
+    }
+
+    pub const fn spin_limit(&self) -> u32 {
+        self.spin_limit
+    }
+
+    pub const fn yield_limit(&self) -> u32 {
+        self.yield_limit
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{OverflowPolicy, Receiver};
+use crossbeam_channel::TrySendError;
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError, Weak,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Delivers every message to all subscribers, each through its own bounded
+/// queue, so a slow subscriber never blocks the publisher or its peers.
+#[derive(Debug)]
+pub struct Broadcast<T> {
+    subscribers: Mutex<Vec<Subscriber<T>>>,
+    policy: OverflowPolicy,
+    overflowed: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Subscriber<T> {
+    sender: crossbeam_channel::Sender<T>,
+    receiver: Weak<crossbeam_channel::Receiver<T>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T: Clone> Broadcast<T> {
+    pub fn new(policy: OverflowPolicy) -> Self {
+        Self {
+            subscribers: Mutex::new(Vec::new()),
+            policy,
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn subscribe(&self, capacity: usize) -> Receiver<T> {
+        let (sender, receiver) = crossbeam_channel::bounded(capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let receiver = Receiver::new(receiver).with_drop_counter(Arc::clone(&dropped));
+
+        self.lock().push(Subscriber {
+            sender,
+            receiver: receiver.downgrade(),
+            dropped,
+        });
+        receiver
+    }
+
+    /// Sends a copy of `msg` to every subscriber and returns how many
+    /// accepted it. Subscribers that dropped their receiver are removed.
+    pub fn send(&self, msg: T) -> usize {
+        let mut delivered = 0;
+
+        self.lock()
+            .retain(|subscriber| match subscriber.sender.try_send(msg.clone()) {
+                Ok(()) => {
+                    delivered += 1;
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+                Err(TrySendError::Full(msg)) => {
+                    self.overflowed.fetch_add(1, Ordering::Relaxed);
+
+                    match self.policy {
+                        OverflowPolicy::DropOldest => {
+                            let Some(receiver) = subscriber.receiver.upgrade() else {
+                                return false;
+                            };
+                            if receiver.try_recv().is_ok() {
+                                subscriber.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            if subscriber.sender.try_send(msg).is_ok() {
+                                delivered += 1;
+                            }
+                            true
+                        }
+                        OverflowPolicy::Disconnect => false,
+                    }
+                }
+            });
+
+        delivered
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Number of deliveries that found a subscriber's queue full.
+    #[inline]
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Subscriber<T>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
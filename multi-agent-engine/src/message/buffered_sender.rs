@@ -0,0 +1,90 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Sender;
+use crate::AgentContext;
+use multi_agent_engine_core::Result;
+use std::{cell::RefCell, mem};
+
+/// Collects messages in a buffer owned by the sending thread and hands them
+/// to the queue as one `Vec` per [`flush`](Self::flush), so hundreds of small
+/// sends cost a single channel operation.
+///
+/// This trades latency for throughput: nothing reaches the receiver before
+/// the next flush. Set up [`with_frame_boundary`](Self::with_frame_boundary)
+/// to flush the previous frame's messages on the first send of a new frame;
+/// the last batch still needs an explicit flush, or goes out when the sender
+/// is dropped.
+#[derive(Debug)]
+pub struct BufferedSender<T> {
+    sender: Sender<Vec<T>>,
+    buffer: RefCell<Vec<T>>,
+    frame: RefCell<Option<(AgentContext, u64)>>,
+}
+
+impl<T> BufferedSender<T> {
+    pub fn new(sender: Sender<Vec<T>>) -> Self {
+        Self {
+            sender,
+            buffer: RefCell::new(Vec::new()),
+            frame: RefCell::new(None),
+        }
+    }
+
+    pub fn with_frame_boundary(self, ctx: &AgentContext) -> Self {
+        *self.frame.borrow_mut() = Some((ctx.clone(), ctx.frame()));
+        self
+    }
+
+    /// Buffers `msg`, first flushing the buffer if a frame boundary passed
+    /// since the previous send.
+    pub fn send(&self, msg: T) -> Result<()> {
+        let crossed = match &mut *self.frame.borrow_mut() {
+            Some((ctx, last)) => {
+                let frame = ctx.frame();
+                mem::replace(last, frame) != frame
+            }
+            None => false,
+        };
+        if crossed {
+            self.flush()?;
+        }
+
+        self.buffer.borrow_mut().push(msg);
+        Ok(())
+    }
+
+    /// Sends the buffered messages as one batch. Does nothing when the buffer
+    /// is empty.
+    pub fn flush(&self) -> Result<()> {
+        let batch = mem::take(&mut *self.buffer.borrow_mut());
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.sender.send(batch)
+    }
+
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffer.borrow().len()
+    }
+}
+
+impl<T> Drop for BufferedSender<T> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,53 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{MessageSize, byte_budget::ByteBudget};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct ByteBoundedReceiver<T> {
+    receiver: crossbeam_channel::Receiver<T>,
+    budget: Arc<ByteBudget>,
+}
+
+impl<T: MessageSize> ByteBoundedReceiver<T> {
+    #[inline]
+    pub(super) fn new(receiver: crossbeam_channel::Receiver<T>, budget: Arc<ByteBudget>) -> Self {
+        Self { receiver, budget }
+    }
+
+    pub fn receive(&self) -> Vec<T> {
+        let batch: Vec<T> = self.receiver.try_iter().collect();
+        self.budget
+            .release(batch.iter().map(MessageSize::size_hint).sum());
+        batch
+    }
+
+    pub fn recv_blocking(&self) -> Result<T> {
+        let msg = self.receiver.recv().map_err(|_| Error::Disconnected {
+            endpoint: Endpoint::Receiver,
+        })?;
+        self.budget.release(msg.size_hint());
+        Ok(msg)
+    }
+}
+
+impl<T> Drop for ByteBoundedReceiver<T> {
+    fn drop(&mut self) {
+        self.budget.close();
+    }
+}
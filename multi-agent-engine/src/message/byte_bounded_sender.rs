@@ -0,0 +1,70 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{MessageSize, byte_budget::ByteBudget};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct ByteBoundedSender<T> {
+    sender: crossbeam_channel::Sender<T>,
+    budget: Arc<ByteBudget>,
+}
+
+impl<T: MessageSize> ByteBoundedSender<T> {
+    #[inline]
+    pub(super) fn new(sender: crossbeam_channel::Sender<T>, budget: Arc<ByteBudget>) -> Self {
+        Self { sender, budget }
+    }
+
+    /// Sends `msg`, blocking while the queued bytes plus its
+    /// [`size_hint`](MessageSize::size_hint) would exceed the byte limit.
+    pub fn send(&self, msg: T) -> Result<()> {
+        let bytes = msg.size_hint();
+        let disconnected = Error::Disconnected {
+            endpoint: Endpoint::Sender,
+        };
+
+        if !self.budget.acquire(bytes) {
+            return Err(disconnected);
+        }
+        self.sender.send(msg).map_err(|_| {
+            self.budget.release(bytes);
+            disconnected
+        })
+    }
+
+    #[inline]
+    pub fn queued_bytes(&self) -> usize {
+        self.budget.queued()
+    }
+
+    /// The byte limit, which is the whole pool for a queue created with
+    /// [`Queue::byte_bounded_in`](super::Queue::byte_bounded_in).
+    #[inline]
+    pub fn byte_limit(&self) -> usize {
+        self.budget.limit()
+    }
+}
+
+impl<T> Clone for ByteBoundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            budget: Arc::clone(&self.budget),
+        }
+    }
+}
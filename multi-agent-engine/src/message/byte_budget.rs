@@ -0,0 +1,138 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+/// Byte accounting for one byte-bounded queue, backed by a [`BytePool`] that
+/// it either owns alone or shares with the other queues of a
+/// [`MemoryBudget`](super::MemoryBudget).
+#[derive(Debug)]
+pub(super) struct ByteBudget {
+    pool: Arc<BytePool>,
+    slot: usize,
+}
+
+/// A byte limit shared by the queues registered with it.
+#[derive(Debug)]
+pub(super) struct BytePool {
+    total: usize,
+    slots: Mutex<Vec<Slot>>,
+    freed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    queued: usize,
+    closed: bool,
+}
+
+impl ByteBudget {
+    pub(super) fn new(limit: usize) -> Self {
+        BytePool::new(limit).register()
+    }
+
+    /// Blocks until `bytes` fit in what is left of the pool and reserves them.
+    /// A message larger than the whole pool is admitted once the pool is
+    /// empty so it cannot wedge the producer. Returns `false` if the receiver
+    /// is gone.
+    pub(super) fn acquire(&self, bytes: usize) -> bool {
+        let mut slots = self.pool.lock();
+
+        loop {
+            let queued = BytePool::sum(&slots);
+            let oversized = bytes > self.pool.total && queued == 0;
+            if slots[self.slot].closed || oversized || queued + bytes <= self.pool.total {
+                break;
+            }
+            slots = self
+                .pool
+                .freed
+                .wait(slots)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        let slot = &mut slots[self.slot];
+        if !slot.closed {
+            slot.queued += bytes;
+        }
+        !slot.closed
+    }
+
+    pub(super) fn release(&self, bytes: usize) {
+        let mut slots = self.pool.lock();
+        let slot = &mut slots[self.slot];
+        slot.queued = slot.queued.saturating_sub(bytes);
+        self.pool.freed.notify_all();
+    }
+
+    /// Marks the receiver as gone and gives the bytes its queue still holds
+    /// back to the pool.
+    pub(super) fn close(&self) {
+        let mut slots = self.pool.lock();
+        let slot = &mut slots[self.slot];
+        slot.closed = true;
+        slot.queued = 0;
+        self.pool.freed.notify_all();
+    }
+
+    pub(super) fn queued(&self) -> usize {
+        self.pool.lock()[self.slot].queued
+    }
+
+    /// The limit of the whole pool, shared with the other queues registered
+    /// with it.
+    #[inline]
+    pub(super) fn limit(&self) -> usize {
+        self.pool.total
+    }
+}
+
+impl BytePool {
+    pub(super) fn new(total: usize) -> Arc<Self> {
+        Arc::new(Self {
+            total,
+            slots: Mutex::default(),
+            freed: Condvar::new(),
+        })
+    }
+
+    /// Adds a queue drawing from the pool.
+    pub(super) fn register(self: Arc<Self>) -> ByteBudget {
+        let mut slots = self.lock();
+        let slot = slots.len();
+        slots.push(Slot::default());
+        drop(slots);
+
+        ByteBudget { pool: self, slot }
+    }
+
+    #[inline]
+    pub(super) fn total(&self) -> usize {
+        self.total
+    }
+
+    pub(super) fn queued(&self) -> usize {
+        Self::sum(&self.lock())
+    }
+
+    fn sum(slots: &[Slot]) -> usize {
+        slots.iter().map(|slot| slot.queued).sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Slot>> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// A message tagged with its Lamport timestamp and the timestamps of the
+/// messages that must be delivered before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Causal<T> {
+    message: T,
+    stamp: u64,
+    after: Vec<u64>,
+}
+
+impl<T> Causal<T> {
+    #[inline]
+    pub fn new(message: T, stamp: u64) -> Self {
+        Self {
+            message,
+            stamp,
+            after: Vec::new(),
+        }
+    }
+
+    /// Declares that this message happens after the one stamped `stamp`.
+    pub fn after(mut self, stamp: u64) -> Self {
+        self.after.push(stamp);
+        self
+    }
+
+    #[inline]
+    pub fn stamp(&self) -> u64 {
+        self.stamp
+    }
+
+    #[inline]
+    pub fn dependencies(&self) -> &[u64] {
+        &self.after
+    }
+
+    #[inline]
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.message
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Causal, Receiver};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashSet, VecDeque},
+    mem,
+    sync::{Mutex, PoisonError},
+};
+
+/// Merges several queues of [`Causal`] messages and delivers each message only
+/// once every message it depends on has been delivered.
+///
+/// Messages whose dependencies have not arrived yet stay buffered, so a single
+/// late message holds back everything that depends on it, even when it is
+/// on another queue. Each queue is delivered in the order it was sent, so its
+/// stamps must increase, as Lamport timestamps do, and be unique across all
+/// merged queues.
+///
+/// Only the stamps above the lowest per-queue watermark, the stamp each queue
+/// delivered last, are remembered: a dependency at or below it counts as
+/// delivered, since every queue is past it. A dependency that is never sent
+/// keeps its dependents buffered until then.
+#[derive(Debug)]
+pub struct CausalReceiver<T> {
+    receivers: Vec<Receiver<Causal<T>>>,
+    state: Mutex<State<T>>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    queues: Vec<VecDeque<Causal<T>>>,
+    watermarks: Vec<Option<u64>>,
+    delivered: HashSet<u64>,
+    /// Queues whose oldest message waits, keyed by the dependency it misses.
+    waiting: BTreeMap<u64, Vec<usize>>,
+}
+
+impl<T> CausalReceiver<T> {
+    pub fn new(receivers: impl IntoIterator<Item = Receiver<Causal<T>>>) -> Self {
+        let receivers: Vec<_> = receivers.into_iter().collect();
+        let count = receivers.len();
+
+        Self {
+            receivers,
+            state: Mutex::new(State {
+                queues: (0..count).map(|_| VecDeque::new()).collect(),
+                watermarks: vec![None; count],
+                delivered: HashSet::new(),
+                waiting: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Drains every queue and returns the messages that are now deliverable,
+    /// each after its dependencies and otherwise in stamp order.
+    pub fn receive(&self) -> Vec<Causal<T>> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut ready = BinaryHeap::new();
+
+        for (queue, receiver) in self.receivers.iter().enumerate() {
+            let was_empty = state.queues[queue].is_empty();
+            state.queues[queue].extend(receiver.try_iter());
+            if was_empty && let Some(head) = state.queues[queue].front() {
+                ready.push(Reverse((head.stamp(), queue)));
+            }
+        }
+
+        let mut batch = Vec::new();
+        loop {
+            while let Some(Reverse((_, queue))) = ready.pop() {
+                if let Some(msg) = state.deliver(queue, &mut ready) {
+                    batch.push(msg);
+                }
+            }
+
+            // Dependencies the watermark moved past may never be sent at all.
+            let Some(floor) = state.floor() else { break };
+            let passed = match floor.checked_add(1) {
+                Some(above) => {
+                    let above = state.waiting.split_off(&above);
+                    mem::replace(&mut state.waiting, above)
+                }
+                None => mem::take(&mut state.waiting),
+            };
+            if passed.is_empty() {
+                break;
+            }
+            for queue in passed.into_values().flatten() {
+                let head = state.queues[queue].front().map(Causal::stamp);
+                ready.extend(head.map(|stamp| Reverse((stamp, queue))));
+            }
+        }
+
+        if let Some(floor) = state.floor() {
+            state.delivered.retain(|&stamp| stamp > floor);
+        }
+        batch
+    }
+
+    /// Messages received but still waiting on a dependency.
+    pub fn pending(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queues
+            .iter()
+            .map(VecDeque::len)
+            .sum()
+    }
+}
+
+impl<T> State<T> {
+    /// Delivers the oldest message of `queue` unless it misses a dependency,
+    /// in which case the queue waits on it.
+    fn deliver(
+        &mut self,
+        queue: usize,
+        ready: &mut BinaryHeap<Reverse<(u64, usize)>>,
+    ) -> Option<Causal<T>> {
+        let head = self.queues[queue].front()?;
+        let missing = head
+            .dependencies()
+            .iter()
+            .copied()
+            .find(|&stamp| !self.is_delivered(stamp));
+        if let Some(missing) = missing {
+            self.waiting.entry(missing).or_default().push(queue);
+            return None;
+        }
+
+        let msg = self.queues[queue].pop_front()?;
+        let stamp = msg.stamp();
+        self.delivered.insert(stamp);
+        self.watermarks[queue] = Some(stamp);
+
+        if let Some(head) = self.queues[queue].front() {
+            ready.push(Reverse((head.stamp(), queue)));
+        }
+        for waiting in self.waiting.remove(&stamp).into_iter().flatten() {
+            if let Some(head) = self.queues[waiting].front() {
+                ready.push(Reverse((head.stamp(), waiting)));
+            }
+        }
+        Some(msg)
+    }
+
+    fn is_delivered(&self, stamp: u64) -> bool {
+        self.delivered.contains(&stamp) || self.floor().is_some_and(|floor| stamp <= floor)
+    }
+
+    /// The lowest watermark, once every queue delivered something.
+    fn floor(&self) -> Option<u64> {
+        self.watermarks.iter().copied().min().flatten()
+    }
+}
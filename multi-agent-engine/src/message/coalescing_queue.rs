@@ -0,0 +1,79 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Folds every message sent since the last receive into one using `merge`.
+pub struct CoalescingQueue<T, F> {
+    pending: Arc<Mutex<Option<T>>>,
+    merge: Arc<F>,
+}
+
+impl<T, F> CoalescingQueue<T, F>
+where
+    F: Fn(T, T) -> T,
+{
+    pub fn new(merge: F) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(None)),
+            merge: Arc::new(merge),
+        }
+    }
+
+    pub fn send(&self, msg: T) {
+        let mut pending = self.lock();
+
+        *pending = Some(match pending.take() {
+            Some(previous) => (self.merge)(previous, msg),
+            None => msg,
+        });
+    }
+
+    pub fn receive(&self) -> Option<T> {
+        self.lock().take()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_none()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<T>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T, F> Clone for CoalescingQueue<T, F> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: Arc::clone(&self.pending),
+            merge: Arc::clone(&self.merge),
+        }
+    }
+}
+
+impl<T, F> Debug for CoalescingQueue<T, F>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoalescingQueue")
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+#[derive(Debug)]
+pub struct ConflatingQueue<K, T> {
+    pending: Arc<Mutex<Pending<K, T>>>,
+}
+
+#[derive(Debug)]
+struct Pending<K, T> {
+    index: HashMap<K, usize>,
+    messages: Vec<T>,
+}
+
+impl<K, T> ConflatingQueue<K, T>
+where
+    K: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(Pending {
+                index: HashMap::new(),
+                messages: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn send(&self, key: K, msg: T) {
+        let mut pending = self.lock();
+
+        match pending.index.get(&key) {
+            Some(&slot) => pending.messages[slot] = msg,
+            None => {
+                let slot = pending.messages.len();
+                pending.index.insert(key, slot);
+                pending.messages.push(msg);
+            }
+        }
+    }
+
+    pub fn receive(&self) -> Vec<T> {
+        let mut pending = self.lock();
+        pending.index.clear();
+
+        std::mem::take(&mut pending.messages)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pending<K, T>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K, T> Clone for ConflatingQueue<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+impl<K, T> Default for ConflatingQueue<K, T>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
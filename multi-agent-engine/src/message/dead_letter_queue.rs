@@ -0,0 +1,68 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Messages that could not be delivered while the engine shut down, kept
+/// for inspection instead of being lost silently. Senders route into it once
+/// set up with [`Sender::with_dead_letters`](super::Sender::with_dead_letters).
+#[derive(Debug)]
+pub struct DeadLetterQueue<T> {
+    messages: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> DeadLetterQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            messages: Arc::default(),
+        }
+    }
+
+    pub(super) fn push(&self, msg: T) {
+        self.lock().push(msg);
+    }
+
+    /// Takes every dead letter, in the order they were rejected.
+    pub fn drain(&self) -> Vec<T> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.messages.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for DeadLetterQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            messages: Arc::clone(&self.messages),
+        }
+    }
+}
+
+impl<T> Default for DeadLetterQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
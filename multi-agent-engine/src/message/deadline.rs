@@ -0,0 +1,50 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline<T> {
+    message: T,
+    due: Instant,
+}
+
+impl<T> Deadline<T> {
+    #[inline]
+    pub fn new(message: T, due: Instant) -> Self {
+        Self { message, due }
+    }
+
+    #[inline]
+    pub fn due(&self) -> Instant {
+        self.due
+    }
+
+    #[inline]
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now > self.due
+    }
+
+    #[inline]
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.message
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crossbeam_channel::{Select, TryRecvError};
+use multi_agent_engine_core::{AgentId, Endpoint, Error, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+    time::Duration,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+type IsEmpty = Box<dyn Fn() -> Option<bool> + Send>;
+
+/// Reports a deadlock once every agent owning a watched receiver is blocked
+/// on one of them while all of them are empty.
+///
+/// Receivers are grouped by the agent that owns them, so an agent reading
+/// from several queues counts once and is blocked as soon as it waits on any
+/// of them.
+#[derive(Clone)]
+pub struct DeadlockDetector {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    blocked: BTreeMap<AgentId, usize>,
+    deadlocked: Option<usize>,
+    queues: Vec<(AgentId, IsEmpty)>,
+}
+
+impl DeadlockDetector {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                blocked: BTreeMap::new(),
+                deadlocked: None,
+                queues: Vec::new(),
+            })),
+        }
+    }
+
+    pub(super) fn register<T: Send + 'static>(
+        &self,
+        agent: AgentId,
+        receiver: Weak<crossbeam_channel::Receiver<T>>,
+    ) {
+        self.lock().queues.push((
+            agent,
+            Box::new(move || receiver.upgrade().map(|receiver| receiver.is_empty())),
+        ));
+    }
+
+    pub(super) fn recv<T>(
+        &self,
+        agent: AgentId,
+        receiver: &crossbeam_channel::Receiver<T>,
+    ) -> Result<T> {
+        let mut blocked = false;
+
+        loop {
+            {
+                let mut state = self.lock();
+
+                let result = match receiver.try_recv() {
+                    Ok(msg) => Some(Ok(msg)),
+                    Err(_) if state.deadlocked.is_some() => Some(Err(Error::Deadlock {
+                        agents: state.deadlocked.unwrap_or_default(),
+                    })),
+                    Err(TryRecvError::Disconnected) => Some(Err(Error::Disconnected {
+                        endpoint: Endpoint::Receiver,
+                    })),
+                    Err(TryRecvError::Empty) => None,
+                };
+
+                if let Some(result) = result {
+                    if blocked {
+                        state.unblock(agent);
+                    }
+                    return result;
+                }
+
+                if !blocked {
+                    *state.blocked.entry(agent).or_default() += 1;
+                    blocked = true;
+                }
+
+                if let Some(agents) = state.deadlocked_agents() {
+                    state.deadlocked = Some(agents);
+                    state.unblock(agent);
+                    return Err(Error::Deadlock { agents });
+                }
+            }
+
+            let mut select = Select::new();
+            select.recv(receiver);
+            let _ = select.ready_timeout(POLL_INTERVAL);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn unblock(&mut self, agent: AgentId) {
+        if let Some(blocked) = self.blocked.get_mut(&agent) {
+            *blocked -= 1;
+            if *blocked == 0 {
+                self.blocked.remove(&agent);
+            }
+        }
+    }
+
+    fn deadlocked_agents(&self) -> Option<usize> {
+        let mut agents = BTreeSet::new();
+
+        for (agent, is_empty) in &self.queues {
+            match is_empty() {
+                Some(true) => {
+                    agents.insert(*agent);
+                }
+                Some(false) => return None,
+                None => {}
+            }
+        }
+
+        (!agents.is_empty() && agents.iter().all(|agent| self.blocked.contains_key(agent)))
+            .then_some(agents.len())
+    }
+}
+
+impl Default for DeadlockDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for DeadlockDetector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+
+        f.debug_struct("DeadlockDetector")
+            .field("queues", &state.queues.len())
+            .field("blocked", &state.blocked)
+            .field("deadlocked", &state.deadlocked)
+            .finish()
+    }
+}
@@ -0,0 +1,79 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Receiver;
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+pub struct DedupReceiver<T, K, F> {
+    receiver: Receiver<T>,
+    key: F,
+    window: Duration,
+    seen: Mutex<HashMap<K, Instant>>,
+}
+
+impl<T, K, F> DedupReceiver<T, K, F>
+where
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    pub(super) fn new(receiver: Receiver<T>, key: F, window: Duration) -> Self {
+        Self {
+            receiver,
+            key,
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn receive(&self) -> Vec<T> {
+        let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = self.receiver.clock().now();
+
+        seen.retain(|_, at| at.checked_add(self.window).is_none_or(|end| now < end));
+
+        self.receiver
+            .receive()
+            .into_iter()
+            .filter(|msg| {
+                let key = (self.key)(msg);
+
+                if seen.contains_key(&key) {
+                    return false;
+                }
+                seen.insert(key, now);
+                true
+            })
+            .collect()
+    }
+}
+
+impl<T, K, F> Debug for DedupReceiver<T, K, F>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DedupReceiver")
+            .field("receiver", &self.receiver)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{StateSync, SyncFrame};
+use crate::Patch;
+use std::collections::BTreeMap;
+
+/// Rebuilds full states from the [`SyncFrame`]s of a
+/// [`DeltaEncoder`](super::DeltaEncoder).
+///
+/// Reconstructed snapshots are kept until the encoder stops diffing against
+/// them, so deltas on an older acknowledged base still apply.
+#[derive(Debug)]
+pub struct DeltaDecoder<S> {
+    states: BTreeMap<u64, S>,
+    sync: Option<StateSync<S>>,
+}
+
+impl<S> DeltaDecoder<S> {
+    pub fn new() -> Self {
+        Self {
+            states: BTreeMap::new(),
+            sync: None,
+        }
+    }
+
+    /// Publishes every reconstructed state to `sync`.
+    pub fn with_state_sync(mut self, sync: &StateSync<S>) -> Self {
+        self.sync = Some(sync.clone());
+        self
+    }
+
+    /// Applies `frame` and returns the version to acknowledge to the encoder.
+    ///
+    /// Returns `None` when the frame is a delta on a base this decoder never
+    /// saw, in which case it is dropped until the next keyframe.
+    pub fn decode(&mut self, frame: SyncFrame<S>) -> Option<u64>
+    where
+        S: Patch + Clone,
+    {
+        let (base, version, state) = match frame {
+            SyncFrame::Keyframe { version, state } => (version, version, state),
+            SyncFrame::Delta {
+                base,
+                version,
+                delta,
+            } => {
+                let mut state = self.states.get(&base)?.clone();
+                state.patch(delta);
+                (base, version, state)
+            }
+        };
+
+        self.states = self.states.split_off(&base);
+        if version >= self.latest_version().unwrap_or(0)
+            && let Some(sync) = &self.sync
+        {
+            sync.publish(&state);
+        }
+        self.states.insert(version, state);
+
+        Some(version)
+    }
+
+    /// The newest reconstructed state.
+    pub fn state(&self) -> Option<&S> {
+        self.states.last_key_value().map(|(_, state)| state)
+    }
+
+    fn latest_version(&self) -> Option<u64> {
+        self.states.last_key_value().map(|(version, _)| *version)
+    }
+}
+
+impl<S> Default for DeltaDecoder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
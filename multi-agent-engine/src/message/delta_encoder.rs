@@ -0,0 +1,101 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::SyncFrame;
+use crate::Diff;
+use std::collections::VecDeque;
+
+/// Turns a stream of full states into [`SyncFrame`]s for a
+/// [`DeltaDecoder`](super::DeltaDecoder), typically on the other end of a
+/// network transport.
+///
+/// Each state is sent as the delta from the newest snapshot the receiver
+/// [acknowledged](Self::acknowledge). Until something is acknowledged, and
+/// every `keyframe_interval` frames, the full state is sent instead so a
+/// receiver that lost frames can resync.
+#[derive(Debug)]
+pub struct DeltaEncoder<S> {
+    keyframe_interval: u64,
+    version: u64,
+    since_keyframe: u64,
+    acked: Option<(u64, S)>,
+    unacked: VecDeque<(u64, S)>,
+}
+
+impl<S> DeltaEncoder<S> {
+    pub fn new(keyframe_interval: u64) -> Self {
+        Self {
+            keyframe_interval: keyframe_interval.max(1),
+            version: 0,
+            since_keyframe: 0,
+            acked: None,
+            unacked: VecDeque::new(),
+        }
+    }
+
+    pub fn encode(&mut self, state: &S) -> SyncFrame<S>
+    where
+        S: Diff + Clone,
+    {
+        self.version += 1;
+        let version = self.version;
+
+        let frame = match &self.acked {
+            Some((base, prev)) if self.since_keyframe < self.keyframe_interval => {
+                self.since_keyframe += 1;
+                SyncFrame::Delta {
+                    base: *base,
+                    version,
+                    delta: prev.diff(state),
+                }
+            }
+            _ => {
+                self.since_keyframe = 1;
+                SyncFrame::Keyframe {
+                    version,
+                    state: state.clone(),
+                }
+            }
+        };
+
+        // Snapshots older than a keyframe interval are never worth diffing
+        // against: the receiver gets a keyframe before their ack would matter.
+        if self.unacked.len() as u64 >= self.keyframe_interval {
+            self.unacked.pop_front();
+        }
+        self.unacked.push_back((version, state.clone()));
+
+        frame
+    }
+
+    /// Records that the receiver has reconstructed `version`, making it the
+    /// base for the following deltas. Unknown or outdated versions are ignored.
+    pub fn acknowledge(&mut self, version: u64) {
+        if let Some(index) = self.unacked.iter().position(|(v, _)| *v == version) {
+            self.acked = self.unacked.drain(..=index).next_back();
+        }
+    }
+
+    /// Makes the next frame a keyframe, for a receiver asking to resync.
+    pub fn force_keyframe(&mut self) {
+        self.since_keyframe = self.keyframe_interval;
+    }
+
+    /// The version of the last encoded frame.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Clock, SeededRng, SystemClock};
+use std::{
+    ops::RangeInclusive,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// A queue for resilience tests that holds back each message for a random
+/// delay, simulating network jitter.
+///
+/// Each [`send`](Self::send) draws a delay within the configured range from a
+/// seeded RNG, so the same seed and send sequence always produce the same
+/// delays. [`receive`](Self::receive) only hands out messages whose delay has
+/// elapsed, in the order they become due, so a later message with a shorter
+/// delay overtakes an earlier one. Use [`with_clock`](Self::with_clock) and a
+/// [`MockClock`](crate::MockClock) to step through the delays without sleeping.
+#[derive(Debug)]
+pub struct JitterQueue<T> {
+    state: Arc<Mutex<Jitter<T>>>,
+    delay: RangeInclusive<Duration>,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug)]
+struct Jitter<T> {
+    messages: Vec<(Instant, u64, T)>,
+    rng: SeededRng,
+    sent: u64,
+}
+
+impl<T> JitterQueue<T> {
+    pub fn new(seed: u64, delay: RangeInclusive<Duration>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(Jitter {
+                messages: Vec::new(),
+                rng: SeededRng::new(seed),
+                sent: 0,
+            })),
+            delay,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Queues `msg` to be delivered once its delay has elapsed, and returns
+    /// that delay.
+    pub fn send(&self, msg: T) -> Duration {
+        let mut state = self.lock();
+
+        let (min, max) = (*self.delay.start(), *self.delay.end());
+        let span = max.saturating_sub(min).as_nanos() as u64;
+        let delay = min + Duration::from_nanos(state.rng.below(span.saturating_add(1)));
+
+        let seq = state.sent;
+        state.sent += 1;
+        state.messages.push((self.clock.now() + delay, seq, msg));
+        delay
+    }
+
+    /// Takes the messages whose delay has elapsed, earliest due first.
+    pub fn receive(&self) -> Vec<T> {
+        let now = self.clock.now();
+        let mut state = self.lock();
+
+        let (mut due, pending) = std::mem::take(&mut state.messages)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(at, _, _)| *at <= now);
+        state.messages = pending;
+        due.sort_by_key(|(at, seq, _)| (*at, *seq));
+
+        due.into_iter().map(|(_, _, msg)| msg).collect()
+    }
+
+    /// How many messages are held back, due or not.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().messages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Jitter<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for JitterQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            delay: self.delay.clone(),
+            clock: Arc::clone(&self.clock),
+        }
+    }
+}
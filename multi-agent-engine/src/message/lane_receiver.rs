@@ -0,0 +1,43 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[derive(Debug, Clone)]
+pub struct LaneReceiver<T> {
+    control: crossbeam_channel::Receiver<T>,
+    data: crossbeam_channel::Receiver<T>,
+}
+
+impl<T> LaneReceiver<T> {
+    #[inline]
+    pub(super) fn new(
+        control: crossbeam_channel::Receiver<T>,
+        data: crossbeam_channel::Receiver<T>,
+    ) -> Self {
+        Self { control, data }
+    }
+
+    #[inline]
+    pub fn receive(&self) -> Vec<T> {
+        let mut batch: Vec<T> = self.control.try_iter().collect();
+        batch.extend(self.data.try_iter());
+        batch
+    }
+
+    #[inline]
+    pub fn receive_control(&self) -> Vec<T> {
+        self.control.try_iter().collect()
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::{Endpoint, Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct LaneSender<T> {
+    control: crossbeam_channel::Sender<T>,
+    data: crossbeam_channel::Sender<T>,
+}
+
+impl<T> LaneSender<T> {
+    #[inline]
+    pub(super) fn new(
+        control: crossbeam_channel::Sender<T>,
+        data: crossbeam_channel::Sender<T>,
+    ) -> Self {
+        Self { control, data }
+    }
+
+    #[inline]
+    pub fn send_control(&self, msg: T) -> Result<()> {
+        self.control.send(msg).map_err(|_| Error::Disconnected {
+            endpoint: Endpoint::Sender,
+        })
+    }
+
+    #[inline]
+    pub fn send_data(&self, msg: T) -> Result<()> {
+        self.data.send(msg).map_err(|_| Error::Disconnected {
+            endpoint: Endpoint::Sender,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::SeededRng;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A queue for resilience tests that drops a fraction of the messages sent.
+///
+/// Each [`send`](Self::send) draws from a seeded RNG and discards the message
+/// with probability `loss`, so the same seed and send sequence always lose the
+/// same messages. A `loss` of `0.0` delivers everything and `1.0` nothing.
+#[derive(Debug)]
+pub struct LossyQueue<T> {
+    state: Arc<Mutex<Loss<T>>>,
+    loss: f64,
+}
+
+#[derive(Debug)]
+struct Loss<T> {
+    messages: Vec<T>,
+    rng: SeededRng,
+    dropped: u64,
+}
+
+impl<T> LossyQueue<T> {
+    pub fn new(seed: u64, loss: f64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(Loss {
+                messages: Vec::new(),
+                rng: SeededRng::new(seed),
+                dropped: 0,
+            })),
+            loss: loss.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Queues `msg` unless it is lost; returns whether it was kept.
+    pub fn send(&self, msg: T) -> bool {
+        let mut state = self.lock();
+
+        if state.rng.next_f64() < self.loss {
+            state.dropped += 1;
+            false
+        } else {
+            state.messages.push(msg);
+            true
+        }
+    }
+
+    pub fn receive(&self) -> Vec<T> {
+        std::mem::take(&mut self.lock().messages)
+    }
+
+    /// How many messages have been lost so far.
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().messages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Loss<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for LossyQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            loss: self.loss,
+        }
+    }
+}
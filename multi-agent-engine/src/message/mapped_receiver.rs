@@ -0,0 +1,89 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Receiver;
+use multi_agent_engine_core::Result;
+use std::fmt::{self, Debug, Formatter};
+
+/// A receiver that converts every message with a mapping function as it is
+/// taken from the queue, created with [`Receiver::map`].
+///
+/// The source message type is erased so that agents only name the type they
+/// work with.
+pub struct MappedReceiver<U> {
+    source: Box<dyn Source<U> + Send>,
+}
+
+trait Source<U> {
+    fn receive(&self) -> Vec<U>;
+
+    fn try_recv_one(&self) -> Option<U>;
+
+    fn recv_blocking(&self) -> Result<U>;
+}
+
+struct Mapping<T, F> {
+    receiver: Receiver<T>,
+    map: F,
+}
+
+impl<T, U, F> Source<U> for Mapping<T, F>
+where
+    F: Fn(T) -> U,
+{
+    fn receive(&self) -> Vec<U> {
+        self.receiver.receive().into_iter().map(&self.map).collect()
+    }
+
+    fn try_recv_one(&self) -> Option<U> {
+        self.receiver.try_recv_one().map(&self.map)
+    }
+
+    fn recv_blocking(&self) -> Result<U> {
+        self.receiver.recv_blocking().map(&self.map)
+    }
+}
+
+impl<U> MappedReceiver<U> {
+    pub(super) fn new<T, F>(receiver: Receiver<T>, map: F) -> Self
+    where
+        T: Send + 'static,
+        F: Fn(T) -> U + Send + 'static,
+    {
+        Self {
+            source: Box::new(Mapping { receiver, map }),
+        }
+    }
+
+    pub fn receive(&self) -> Vec<U> {
+        self.source.receive()
+    }
+
+    #[inline]
+    pub fn try_recv_one(&self) -> Option<U> {
+        self.source.try_recv_one()
+    }
+
+    pub fn recv_blocking(&self) -> Result<U> {
+        self.source.recv_blocking()
+    }
+}
+
+impl<U> Debug for MappedReceiver<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedReceiver").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::byte_budget::{ByteBudget, BytePool};
+use std::sync::Arc;
+
+/// A single byte limit for a whole deployment, pooled among the byte-bounded
+/// queues created with [`Queue::byte_bounded_in`](super::Queue::byte_bounded_in).
+///
+/// Any queue may use whatever the others leave free; once the pool is full
+/// senders block as with [`Queue::byte_bounded`](super::Queue::byte_bounded),
+/// so the queues together never hold much more than the total. Dropping a
+/// queue's receiver gives the bytes it still held back to the pool.
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    pool: Arc<BytePool>,
+}
+
+impl MemoryBudget {
+    pub fn new(total_bytes: usize) -> Self {
+        Self {
+            pool: BytePool::new(total_bytes),
+        }
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.pool.total()
+    }
+
+    /// Bytes queued across all registered queues.
+    pub fn queued_bytes(&self) -> usize {
+        self.pool.queued()
+    }
+
+    pub(super) fn register(&self) -> ByteBudget {
+        Arc::clone(&self.pool).register()
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Keeps a copy of every `every`-th message sent on a
+/// [sampled channel](super::Queue::sampled_channel) in a ring buffer, for a
+/// representative view of a hot queue without cloning all of its traffic.
+#[derive(Debug)]
+pub struct MessageSampler<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    every: u64,
+    seen: u64,
+    capacity: usize,
+    samples: VecDeque<T>,
+}
+
+impl<T> MessageSampler<T> {
+    /// Samples one in `every` messages, keeping the newest `capacity`.
+    pub fn new(every: u64, capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                every: every.max(1),
+                seen: 0,
+                capacity,
+                samples: VecDeque::with_capacity(capacity),
+            })),
+        }
+    }
+
+    /// Counts a sent message and tells whether it is to be sampled.
+    pub(super) fn tick(&self) -> bool {
+        let mut state = self.lock();
+        state.seen += 1;
+        state.capacity > 0 && state.seen.is_multiple_of(state.every)
+    }
+
+    pub(super) fn push(&self, msg: T) {
+        let mut state = self.lock();
+
+        if state.samples.len() == state.capacity {
+            state.samples.pop_front();
+        }
+        state.samples.push_back(msg);
+    }
+
+    /// The retained samples, oldest first.
+    pub fn sampled(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.lock().samples.iter().cloned().collect()
+    }
+
+    /// Messages sent through the sampled channel so far.
+    pub fn seen(&self) -> u64 {
+        self.lock().seen
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for MessageSampler<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Warning;
+use std::any;
+
+/// Fails a debug build when a `T` takes more than `max_bytes`.
+///
+/// Every slot of a channel is as large as the largest variant of its message
+/// type, so one big variant slows down every message. Boxing the payload of
+/// large variants keeps the slots small. Does nothing in release builds.
+#[track_caller]
+pub fn debug_assert_message_size<T>(max_bytes: usize) {
+    if cfg!(debug_assertions)
+        && let Some(warning) = check_message_size::<T>(max_bytes)
+    {
+        panic!("{warning}");
+    }
+}
+
+/// Returns a [`Warning::MessageSize`] when a `T` takes more than `max_bytes`.
+pub fn check_message_size<T>(max_bytes: usize) -> Option<Warning> {
+    let size = size_of::<T>();
+
+    (size > max_bytes).then(|| Warning::MessageSize {
+        type_name: any::type_name::<T>(),
+        size,
+        max: max_bytes,
+    })
+}
@@ -0,0 +1,73 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::MessageKind;
+use multi_agent_engine_core::Result;
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+/// Delivery latencies per [message kind](MessageKind), either recorded
+/// directly or through [`Metrics::record_latency`](crate::Metrics::record_latency).
+#[derive(Debug, Clone, Default)]
+pub struct MessageStats {
+    latencies: Arc<Mutex<BTreeMap<&'static str, Vec<Duration>>>>,
+}
+
+impl MessageStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record<T: MessageKind>(&self, msg: &T, latency: Duration) {
+        self.lock().entry(msg.kind()).or_default().push(latency);
+    }
+
+    pub fn count(&self, kind: &str) -> usize {
+        self.lock().get(kind).map_or(0, Vec::len)
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(writer, "kind,count,p50_latency_ns,p99_latency_ns")?;
+
+        for (kind, latencies) in self.lock().iter_mut() {
+            latencies.sort_unstable();
+            writeln!(
+                writer,
+                "{kind},{},{},{}",
+                latencies.len(),
+                percentile(latencies, 50).as_nanos(),
+                percentile(latencies, 99).as_nanos(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<&'static str, Vec<Duration>>> {
+        self.latencies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
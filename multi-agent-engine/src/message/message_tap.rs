@@ -0,0 +1,91 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+};
+
+type Depth = Box<dyn Fn() -> Option<usize> + Send>;
+
+#[derive(Clone)]
+pub struct MessageTap {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    capacity: usize,
+    recent: VecDeque<String>,
+    depth: Option<Depth>,
+}
+
+impl MessageTap {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                capacity,
+                recent: VecDeque::with_capacity(capacity),
+                depth: None,
+            })),
+        }
+    }
+
+    pub(super) fn attach<T: Send + 'static>(&self, receiver: Weak<crossbeam_channel::Receiver<T>>) {
+        self.lock().depth = Some(Box::new(move || {
+            receiver.upgrade().map(|receiver| receiver.len())
+        }));
+    }
+
+    pub(super) fn push(&self, msg: String) {
+        let mut state = self.lock();
+
+        if state.capacity == 0 {
+            return;
+        }
+        if state.recent.len() == state.capacity {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(msg);
+    }
+
+    pub fn depth(&self) -> usize {
+        self.lock()
+            .depth
+            .as_ref()
+            .and_then(|depth| depth())
+            .unwrap_or(0)
+    }
+
+    pub fn recent(&self) -> Vec<String> {
+        self.lock().recent.iter().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Debug for MessageTap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+
+        f.debug_struct("MessageTap")
+            .field("capacity", &state.capacity)
+            .field("recent", &state.recent)
+            .finish()
+    }
+}
@@ -14,10 +14,122 @@
  * limitations under the License.
  */
 
+mod any_message;
+mod backoff;
+mod broadcast;
+mod buffered_sender;
+mod byte_bounded_receiver;
+mod byte_bounded_sender;
+mod byte_budget;
+mod causal;
+mod causal_receiver;
+mod coalescing_queue;
+mod conflating_queue;
+mod dead_letter_queue;
+mod deadline;
+mod deadlock_detector;
+mod dedup_receiver;
+mod delta_decoder;
+mod delta_encoder;
+#[cfg(feature = "testing")]
+mod jitter_queue;
+mod lane_receiver;
+mod lane_sender;
+#[cfg(feature = "testing")]
+mod lossy_queue;
+mod mapped_receiver;
+mod memory_budget;
+mod message_kind;
+mod message_sampler;
+mod message_size;
+mod message_size_limit;
+mod message_stats;
+mod message_tap;
+mod one_shot;
+mod ordered_receiver;
+mod ordered_sender;
+mod ordering_mode;
+mod overflow_policy;
+mod priority_queue;
+mod priority_select;
 mod queue;
+#[cfg(feature = "metrics")]
+mod queue_histogram;
+mod rate_limited_sender;
+mod receive_strategy;
 mod receiver;
+mod recv_state;
+mod reply;
+mod router;
 mod sender;
+mod sender_id;
+mod sequenced_receiver;
+mod sequenced_sender;
+mod sequencer;
+#[cfg(feature = "testing")]
+mod shuffle_queue;
+mod state_sync;
+mod sync_frame;
+mod tagged_receiver;
+mod tagged_sender;
+mod weak_sender;
 
+pub use any_message::AnyMessage;
+pub use backoff::Backoff;
+pub use broadcast::Broadcast;
+pub use buffered_sender::BufferedSender;
+pub use byte_bounded_receiver::ByteBoundedReceiver;
+pub use byte_bounded_sender::ByteBoundedSender;
+pub use causal::Causal;
+pub use causal_receiver::CausalReceiver;
+pub use coalescing_queue::CoalescingQueue;
+pub use conflating_queue::ConflatingQueue;
+pub use dead_letter_queue::DeadLetterQueue;
+pub use deadline::Deadline;
+pub use deadlock_detector::DeadlockDetector;
+pub use dedup_receiver::DedupReceiver;
+pub use delta_decoder::DeltaDecoder;
+pub use delta_encoder::DeltaEncoder;
+#[cfg(feature = "testing")]
+pub use jitter_queue::JitterQueue;
+pub use lane_receiver::LaneReceiver;
+pub use lane_sender::LaneSender;
+#[cfg(feature = "testing")]
+pub use lossy_queue::LossyQueue;
+pub use mapped_receiver::MappedReceiver;
+pub use memory_budget::MemoryBudget;
+pub use message_kind::MessageKind;
+pub use message_sampler::MessageSampler;
+pub use message_size::MessageSize;
+pub use message_size_limit::{check_message_size, debug_assert_message_size};
+pub use message_stats::MessageStats;
+pub use message_tap::MessageTap;
+pub use one_shot::OneShot;
+pub use ordered_receiver::OrderedReceiver;
+pub use ordered_sender::OrderedSender;
+pub use ordering_mode::OrderingMode;
+pub use overflow_policy::OverflowPolicy;
+pub use priority_queue::PriorityQueue;
+pub use priority_select::PrioritySelect;
 pub use queue::Queue;
+#[cfg(feature = "metrics")]
+pub use queue_histogram::QueueHistogram;
+pub use rate_limited_sender::{RateLimitPolicy, RateLimitedSender};
+pub use receive_strategy::ReceiveStrategy;
 pub use receiver::Receiver;
+pub(crate) use receiver::{set_thread_drain_limit, set_thread_recv_timeout};
+pub use recv_state::RecvState;
+pub use reply::Reply;
+pub use router::Router;
 pub use sender::Sender;
+pub use sender_id::SenderId;
+pub use sequenced_receiver::SequencedReceiver;
+pub use sequenced_sender::SequencedSender;
+pub use sequencer::Sequencer;
+#[cfg(feature = "testing")]
+pub use shuffle_queue::ShuffleQueue;
+pub use state_sync::StateSync;
+pub use sync_frame::SyncFrame;
+pub use tagged_receiver::TaggedReceiver;
+pub use tagged_sender::TaggedSender;
+pub use weak_sender::WeakSender;
@@ -0,0 +1,40 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Reply;
+use multi_agent_engine_core::{Endpoint, Error, Result};
+
+/// The responding half of a single-use reply channel, meant to travel inside
+/// a request message so each request can choose its own reply type.
+#[derive(Debug)]
+pub struct OneShot<R> {
+    sender: crossbeam_channel::Sender<R>,
+}
+
+impl<R> OneShot<R> {
+    pub fn channel() -> (Self, Reply<R>) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+
+        (Self { sender }, Reply::new(receiver))
+    }
+
+    /// Fulfills the request. Fails if the requester dropped its [`Reply`].
+    pub fn respond(self, value: R) -> Result<()> {
+        self.sender.send(value).map_err(|_| Error::Disconnected {
+            endpoint: Endpoint::Sender,
+        })
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{OrderingMode, Receiver, SequencedReceiver};
+
+/// The receiving half of a [`Queue::ordered_channel`](super::Queue::ordered_channel).
+#[derive(Debug)]
+pub struct OrderedReceiver<T> {
+    inner: Inner<T>,
+    mode: OrderingMode,
+}
+
+#[derive(Debug)]
+enum Inner<T> {
+    Plain(Receiver<T>),
+    Sequenced(SequencedReceiver<T>),
+}
+
+impl<T> OrderedReceiver<T> {
+    #[inline]
+    pub(super) fn plain(receiver: Receiver<T>, mode: OrderingMode) -> Self {
+        Self {
+            inner: Inner::Plain(receiver),
+            mode,
+        }
+    }
+
+    #[inline]
+    pub(super) fn sequenced(receiver: SequencedReceiver<T>) -> Self {
+        Self {
+            inner: Inner::Sequenced(receiver),
+            mode: OrderingMode::TotalOrder,
+        }
+    }
+
+    #[inline]
+    pub fn mode(&self) -> OrderingMode {
+        self.mode
+    }
+
+    /// Drains the queue. Under [`OrderingMode::TotalOrder`] messages behind a
+    /// stamp still in flight stay queued until it arrives.
+    pub fn receive(&self) -> Vec<T> {
+        match &self.inner {
+            Inner::Plain(receiver) => receiver.receive(),
+            Inner::Sequenced(receiver) => {
+                receiver.receive().into_iter().map(|(_, msg)| msg).collect()
+            }
+        }
+    }
+}
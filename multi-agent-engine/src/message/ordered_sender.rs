@@ -0,0 +1,53 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Sender, SequencedSender};
+use multi_agent_engine_core::Result;
+
+/// The sending half of a [`Queue::ordered_channel`](super::Queue::ordered_channel).
+#[derive(Debug, Clone)]
+pub struct OrderedSender<T> {
+    inner: Inner<T>,
+}
+
+#[derive(Debug, Clone)]
+enum Inner<T> {
+    Plain(Sender<T>),
+    Sequenced(SequencedSender<T>),
+}
+
+impl<T> OrderedSender<T> {
+    #[inline]
+    pub(super) fn plain(sender: Sender<T>) -> Self {
+        Self {
+            inner: Inner::Plain(sender),
+        }
+    }
+
+    #[inline]
+    pub(super) fn sequenced(sender: SequencedSender<T>) -> Self {
+        Self {
+            inner: Inner::Sequenced(sender),
+        }
+    }
+
+    pub fn send(&self, msg: T) -> Result<()> {
+        match &self.inner {
+            Inner::Plain(sender) => sender.send(msg),
+            Inner::Sequenced(sender) => sender.send(msg).map(drop),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// The delivery order promised by a queue built with
+/// [`Queue::ordered_channel`](super::Queue::ordered_channel).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OrderingMode {
+    /// No ordering promise at all. Backed by a plain channel, the cheapest
+    /// option, whose current FIFO behavior may change.
+    BestEffort,
+    /// Messages from one sender arrive in the order it sent them; messages
+    /// from different senders interleave arbitrarily. Costs the same as a
+    /// plain channel.
+    #[default]
+    PerSenderFifo,
+    /// Every message carries a stamp from a shared [`Sequencer`](super::Sequencer)
+    /// and is delivered in stamp order across all senders. Each send pays for
+    /// an atomic increment every sender contends on, and the receiver holds
+    /// back messages that overtook a smaller stamp still in flight.
+    TotalOrder,
+}
@@ -0,0 +1,128 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// A queue delivering the highest priority message first.
+///
+/// Messages of equal priority come out in the order they were sent: each
+/// entry carries an insertion sequence that breaks ties in the heap, so the
+/// delivery order is reproducible from run to run.
+#[derive(Debug)]
+pub struct PriorityQueue<T> {
+    pending: Arc<Mutex<Pending<T>>>,
+}
+
+#[derive(Debug)]
+struct Pending<T> {
+    heap: BinaryHeap<Entry<T>>,
+    next: u64,
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    priority: u32,
+    sequence: Reverse<u64>,
+    msg: T,
+}
+
+impl<T> PriorityQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(Pending {
+                heap: BinaryHeap::new(),
+                next: 0,
+            })),
+        }
+    }
+
+    pub fn send(&self, priority: u32, msg: T) {
+        let mut pending = self.lock();
+        let sequence = Reverse(pending.next);
+        pending.next += 1;
+
+        pending.heap.push(Entry {
+            priority,
+            sequence,
+            msg,
+        });
+    }
+
+    pub fn try_receive(&self) -> Option<T> {
+        self.lock().heap.pop().map(|entry| entry.msg)
+    }
+
+    /// Takes every pending message, highest priority first.
+    pub fn receive(&self) -> Vec<T> {
+        let heap = std::mem::take(&mut self.lock().heap);
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .rev()
+            .map(|entry| entry.msg)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pending<T>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for PriorityQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+impl<T> Default for PriorityQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.priority, self.sequence).cmp(&(other.priority, other.sequence))
+    }
+}
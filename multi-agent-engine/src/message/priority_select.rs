@@ -0,0 +1,96 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Receiver;
+use crossbeam_channel::{Select, TryRecvError};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+
+/// Waits on several queues at once and serves the highest-priority one that
+/// has a message, so control messages on their own queue overtake bulk data
+/// arriving on another.
+///
+/// Queues with equal priority are served in the order they were added.
+#[derive(Debug)]
+pub struct PrioritySelect<T> {
+    queues: Vec<(u32, Receiver<T>)>,
+}
+
+impl<T> PrioritySelect<T> {
+    pub fn new() -> Self {
+        Self { queues: Vec::new() }
+    }
+
+    /// Adds `receiver`; a higher `priority` is drained first.
+    pub fn with_receiver(mut self, receiver: Receiver<T>, priority: u32) -> Self {
+        let index = self.queues.partition_point(|(p, _)| *p >= priority);
+        self.queues.insert(index, (priority, receiver));
+        self
+    }
+
+    /// Takes the next message from the highest-priority ready queue without
+    /// blocking.
+    pub fn try_select(&self) -> Option<T> {
+        self.poll(|_| {}).ok().flatten()
+    }
+
+    /// Blocks until a queue has a message and takes it from the
+    /// highest-priority ready one. Fails once every queue is disconnected and
+    /// drained.
+    pub fn select(&self) -> Result<T> {
+        loop {
+            // Drained disconnected queues are always ready, waiting on them
+            // would spin.
+            let mut select = Select::new();
+            if let Some(msg) = self.poll(|receiver| {
+                select.recv(receiver.channel());
+            })? {
+                return Ok(msg);
+            }
+            select.ready();
+        }
+    }
+
+    /// Takes the first queued message, passing every empty but connected
+    /// queue to `empty` on the way.
+    fn poll<'a>(&'a self, mut empty: impl FnMut(&'a Receiver<T>)) -> Result<Option<T>> {
+        let mut connected = false;
+
+        for (_, receiver) in &self.queues {
+            match receiver.channel().try_recv() {
+                Ok(msg) => return Ok(Some(msg)),
+                Err(TryRecvError::Empty) => {
+                    connected = true;
+                    empty(receiver);
+                }
+                Err(TryRecvError::Disconnected) => {}
+            }
+        }
+
+        if connected {
+            Ok(None)
+        } else {
+            Err(Error::Disconnected {
+                endpoint: Endpoint::Receiver,
+            })
+        }
+    }
+}
+
+impl<T> Default for PrioritySelect<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -14,8 +14,16 @@
  * limitations under the License.
  */
 
-use super::{Receiver, Sender};
-use crossbeam_channel::unbounded;
+use super::{
+    ByteBoundedReceiver, ByteBoundedSender, LaneReceiver, LaneSender, MemoryBudget, MessageSampler,
+    MessageSize, MessageTap, OrderedReceiver, OrderedSender, OrderingMode, Receiver, Sender,
+    SequencedReceiver, Sequencer, TaggedReceiver, TaggedSender, byte_budget::ByteBudget,
+};
+use crossbeam_channel::{bounded, unbounded};
+use std::{
+    fmt::Debug,
+    sync::{Arc, atomic::AtomicU64},
+};
 
 pub struct Queue;
 
@@ -23,7 +31,133 @@ impl Queue {
     #[inline]
     pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
         let (sender, receiver) = unbounded();
+        let receiver = Receiver::<T>::new(receiver);
 
-        (Sender::<T>::new(sender), Receiver::<T>::new(receiver))
+        (Sender::<T>::new(sender).with_peer(&receiver), receiver)
+    }
+
+    /// A queue holding at most `capacity` messages. Its whole buffer is
+    /// allocated up front, so bursts up to `capacity` never reallocate.
+    #[inline]
+    pub fn bounded_channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+        let (sender, receiver) = bounded(capacity);
+        let receiver = Receiver::<T>::new(receiver);
+
+        (Sender::<T>::new(sender).with_peer(&receiver), receiver)
+    }
+
+    /// A queue holding at most `capacity` messages that discards its oldest
+    /// message instead of blocking when a new one arrives while full. Both
+    /// ends report the discards through `dropped_count`.
+    pub fn drop_oldest_channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+        let (sender, receiver) = bounded(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let receiver = Receiver::<T>::new(receiver).with_drop_counter(Arc::clone(&dropped));
+        let sender = Sender::<T>::new(sender)
+            .with_peer(&receiver)
+            .with_drop_oldest(receiver.downgrade(), dropped);
+
+        (sender, receiver)
+    }
+
+    /// A queue delivering in the order selected by `mode`; see
+    /// [`OrderingMode`] for what each guarantee costs.
+    pub fn ordered_channel<T>(mode: OrderingMode) -> (OrderedSender<T>, OrderedReceiver<T>) {
+        match mode {
+            OrderingMode::TotalOrder => {
+                let (sender, receiver) = Sequencer::new().channel();
+
+                (
+                    OrderedSender::sequenced(sender),
+                    OrderedReceiver::sequenced(SequencedReceiver::new([receiver])),
+                )
+            }
+            mode => {
+                let (sender, receiver) = Self::channel();
+
+                (
+                    OrderedSender::plain(sender),
+                    OrderedReceiver::plain(receiver, mode),
+                )
+            }
+        }
+    }
+
+    /// A queue bounded by the total [`size_hint`](MessageSize::size_hint) of
+    /// the messages it holds rather than by their count.
+    #[inline]
+    pub fn byte_bounded<T>(max_bytes: usize) -> (ByteBoundedSender<T>, ByteBoundedReceiver<T>)
+    where
+        T: MessageSize,
+    {
+        let (sender, receiver) = unbounded();
+        let budget = Arc::new(ByteBudget::new(max_bytes));
+
+        (
+            ByteBoundedSender::new(sender, Arc::clone(&budget)),
+            ByteBoundedReceiver::new(receiver, budget),
+        )
+    }
+
+    /// A byte-bounded queue drawing from the pool of `budget`.
+    pub fn byte_bounded_in<T>(
+        budget: &MemoryBudget,
+    ) -> (ByteBoundedSender<T>, ByteBoundedReceiver<T>)
+    where
+        T: MessageSize,
+    {
+        let (sender, receiver) = unbounded();
+        let budget = Arc::new(budget.register());
+
+        (
+            ByteBoundedSender::new(sender, Arc::clone(&budget)),
+            ByteBoundedReceiver::new(receiver, budget),
+        )
+    }
+
+    #[inline]
+    pub fn tagged_channel<T>() -> (TaggedSender<T>, TaggedReceiver<T>) {
+        let (sender, receiver) = unbounded();
+
+        (
+            TaggedSender::<T>::new(sender),
+            TaggedReceiver::<T>::new(receiver),
+        )
+    }
+
+    /// A channel whose sends are sampled into `sampler`.
+    #[inline]
+    pub fn sampled_channel<T>(sampler: &MessageSampler<T>) -> (Sender<T>, Receiver<T>)
+    where
+        T: Clone,
+    {
+        let (sender, receiver) = Self::channel();
+
+        (sender.with_sampler(sampler), receiver)
+    }
+
+    /// A channel recording the messages it carries into `tap`. A message is
+    /// kept as a clone until it is sent and only formatted once it was, so a
+    /// failed send costs no formatting.
+    #[inline]
+    pub fn tapped_channel<T>(tap: &MessageTap) -> (Sender<T>, Receiver<T>)
+    where
+        T: Clone + Debug + Send + 'static,
+    {
+        let (sender, receiver) = Self::channel();
+        tap.attach(receiver.downgrade());
+
+        (sender.with_tap(tap), receiver)
+    }
+
+    #[inline]
+    pub fn lanes<T>() -> (LaneSender<T>, LaneReceiver<T>) {
+        let (control_sender, control_receiver) = unbounded();
+        let (data_sender, data_receiver) = unbounded();
+
+        (
+            LaneSender::<T>::new(control_sender, data_sender),
+            LaneReceiver::<T>::new(control_receiver, data_receiver),
+        )
     }
 }
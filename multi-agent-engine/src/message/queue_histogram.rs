@@ -0,0 +1,84 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    collections::BTreeMap,
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Distribution of message sizes and per-frame message counts for one queue,
+/// fed by a [`Sender::with_histogram`](super::Sender::with_histogram).
+///
+/// Both distributions use power-of-two buckets keyed by their inclusive upper
+/// bound, so a 300-byte message lands in the `512` bucket. Frame counts are
+/// only recorded when the owning agent calls [`end_frame`](Self::end_frame).
+#[derive(Debug, Clone, Default)]
+pub struct QueueHistogram {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    sizes: BTreeMap<usize, u64>,
+    frame_counts: BTreeMap<usize, u64>,
+    in_frame: usize,
+}
+
+impl QueueHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn record(&self, size: usize) {
+        let mut state = self.lock();
+        *state.sizes.entry(bucket(size)).or_default() += 1;
+        state.in_frame += 1;
+    }
+
+    /// Closes the current frame, adding its message count to the histogram.
+    pub fn end_frame(&self) {
+        let mut state = self.lock();
+        let count = mem::take(&mut state.in_frame);
+        *state.frame_counts.entry(bucket(count)).or_default() += 1;
+    }
+
+    /// `(bucket upper bound in bytes, messages)` pairs in ascending order.
+    pub fn sizes(&self) -> Vec<(usize, u64)> {
+        self.lock().sizes.iter().map(|(k, v)| (*k, *v)).collect()
+    }
+
+    /// `(bucket upper bound in messages, frames)` pairs in ascending order.
+    pub fn frame_counts(&self) -> Vec<(usize, u64)> {
+        self.lock()
+            .frame_counts
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn bucket(value: usize) -> usize {
+    if value == 0 {
+        0
+    } else {
+        value.next_power_of_two()
+    }
+}
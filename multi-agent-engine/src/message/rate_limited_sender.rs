@@ -0,0 +1,102 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Sender;
+use crate::{Clock, Metrics, SystemClock};
+use multi_agent_engine_core::Result;
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    Block,
+    Drop,
+}
+
+#[derive(Debug)]
+pub struct RateLimitedSender<T> {
+    sender: Sender<T>,
+    policy: RateLimitPolicy,
+    interval: Duration,
+    burst: Duration,
+    next: Mutex<Instant>,
+    dropped: AtomicU64,
+    clock: Arc<dyn Clock>,
+}
+
+impl<T> RateLimitedSender<T> {
+    pub fn new(sender: Sender<T>, per_second: u32, policy: RateLimitPolicy) -> Self {
+        assert!(
+            per_second > 0,
+            "rate limit must allow at least one message per second"
+        );
+
+        let interval = Duration::from_secs(1) / per_second;
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            sender,
+            policy,
+            interval,
+            burst: interval * (per_second - 1),
+            next: Mutex::new(clock.now()),
+            dropped: AtomicU64::new(0),
+            clock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.next = Mutex::new(clock.now());
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn send(&self, msg: T) -> Result<()> {
+        {
+            let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+
+            loop {
+                let now = self.clock.now();
+                let tat = (*next).max(now);
+
+                if now + self.burst >= tat {
+                    *next = tat + self.interval;
+                    break;
+                }
+
+                match self.policy {
+                    RateLimitPolicy::Block => self.clock.sleep(tat - self.burst - now),
+                    RateLimitPolicy::Drop => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.sender.record_metrics(Metrics::record_dropped);
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        self.sender.send(msg)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{cell::Cell, time::Duration};
+
+thread_local! {
+    static THREAD_DEFAULT: Cell<ReceiveStrategy> = const { Cell::new(ReceiveStrategy::Park) };
+}
+
+/// How [`Receiver::wait`](super::Receiver::wait) blocks on an empty queue.
+///
+/// Receivers without their own strategy use the one configured on the engine
+/// running the current agent thread, or [`Park`](Self::Park) elsewhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReceiveStrategy {
+    /// Parks the thread in a blocking receive.
+    #[default]
+    Park,
+    /// Busy-polls for up to `max` before parking.
+    Spin { max: Duration },
+    /// Yields the thread a bounded number of times before parking.
+    Yield,
+}
+
+impl ReceiveStrategy {
+    pub fn thread_default() -> Self {
+        THREAD_DEFAULT.get()
+    }
+
+    pub(crate) fn set_thread_default(self) {
+        THREAD_DEFAULT.set(self);
+    }
+}
@@ -14,19 +14,655 @@
  * limitations under the License.
  */
 
+use super::{
+    Backoff, Deadline, DeadlockDetector, DedupReceiver, MappedReceiver, ReceiveStrategy, RecvState,
+    Router,
+};
+use crate::{CancellationToken, Clock, SystemClock};
+use multi_agent_engine_core::{AgentId, Endpoint, Error, Result};
+use std::{
+    cell::Cell,
+    hash::Hash,
+    hint, iter,
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// How often [`Receiver::recv_cancellable`] checks its token and
+/// [`Receiver::recv_timeout`] its queue while waiting.
+const CANCEL_POLL: Duration = Duration::from_millis(1);
+
+thread_local! {
+    static DRAIN_LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+    static RECV_TIMEOUT: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+pub(crate) fn set_thread_drain_limit(limit: Option<usize>) {
+    DRAIN_LIMIT.set(limit);
+}
+
+pub(crate) fn set_thread_recv_timeout(timeout: Option<Duration>) {
+    RECV_TIMEOUT.set(timeout);
+}
+
 #[derive(Debug, Clone)]
 pub struct Receiver<T> {
-    receiver: crossbeam_channel::Receiver<T>,
+    receiver: Arc<crossbeam_channel::Receiver<T>>,
+    detector: Option<(DeadlockDetector, AgentId)>,
+    clock: Arc<dyn Clock>,
+    expired: Arc<AtomicU64>,
+    strategy: Option<ReceiveStrategy>,
+    drain_limit: Option<usize>,
+    capacity_hint: usize,
+    timeout: Option<Duration>,
+    dropped: Arc<AtomicU64>,
+    backoff: Backoff,
+    depth_at_drop: Arc<AtomicUsize>,
 }
 
 impl<T> Receiver<T> {
+    pub const YIELD_ATTEMPTS: usize = 64;
+
+    #[inline]
+    pub(crate) fn new(receiver: crossbeam_channel::Receiver<T>) -> Self {
+        Self {
+            receiver: Arc::new(receiver),
+            detector: None,
+            clock: Arc::new(SystemClock),
+            expired: Arc::new(AtomicU64::new(0)),
+            strategy: None,
+            drain_limit: None,
+            capacity_hint: 0,
+            timeout: None,
+            dropped: Arc::default(),
+            backoff: Backoff::new(),
+            depth_at_drop: Arc::default(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn with_receive_strategy(mut self, strategy: ReceiveStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    #[inline]
+    pub fn receive_strategy(&self) -> ReceiveStrategy {
+        self.strategy
+            .unwrap_or_else(ReceiveStrategy::thread_default)
+    }
+
+    /// The policy [`receive_backoff`](Self::receive_backoff) escalates through.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Caps how many messages a single [`receive`](Self::receive) or
+    /// [`wait`](Self::wait) returns, overriding the engine's
+    /// `max_drain_per_frame`. Messages past the cap stay queued and come
+    /// first, in order, on the next call; [`backlog`](Self::backlog) tells how
+    /// many are waiting.
+    pub fn with_drain_at_most(mut self, max: usize) -> Self {
+        self.drain_limit = Some(max);
+        self
+    }
+
+    /// Reserves room for `hint` messages in every batch
+    /// [`receive`](Self::receive) returns, for receivers that know their
+    /// usual burst size. Bounded queues already allocate their whole
+    /// capacity when they are created, so this only saves the batch from
+    /// growing while it is drained.
+    pub fn with_capacity_hint(mut self, hint: usize) -> Self {
+        self.capacity_hint = hint;
+        self
+    }
+
+    /// Messages queued and not yet received, including those carried over
+    /// by a [drain cap](Self::with_drain_at_most).
+    #[inline]
+    pub fn backlog(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// The effective per-call cap: this receiver's own, else the one
+    /// configured on the engine running the current agent thread.
+    #[inline]
+    pub fn drain_limit(&self) -> Option<usize> {
+        self.drain_limit.or_else(|| DRAIN_LIMIT.get())
+    }
+
+    /// Sets how long [`recv_default`](Self::recv_default) waits, overriding
+    /// the engine's per-agent receive timeout.
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The effective default wait: this receiver's own, else the one
+    /// configured on the engine for the agent on the current thread. The
+    /// engine's is kept per thread, so a receiver moved to a thread the
+    /// agent spawned itself has none unless it has its own.
+    #[inline]
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.timeout.or_else(|| RECV_TIMEOUT.get())
+    }
+
+    /// Watches this receiver, read by `agent`, for a deadlock with the other
+    /// receivers of `detector`.
+    pub fn with_deadlock_detector(mut self, detector: &DeadlockDetector, agent: AgentId) -> Self
+    where
+        T: Send + 'static,
+    {
+        detector.register(agent, Arc::downgrade(&self.receiver));
+        self.detector = Some((detector.clone(), agent));
+        self
+    }
+
+    #[inline]
+    pub(super) fn with_drop_counter(mut self, dropped: Arc<AtomicU64>) -> Self {
+        self.dropped = dropped;
+        self
+    }
+
+    /// Messages the queue discarded before this receiver could take them,
+    /// such as evictions by a [drop-oldest](super::Queue::drop_oldest_channel)
+    /// queue. Expired [`Deadline`] messages are counted by
+    /// [`expired_count`](Receiver::expired_count) instead.
+    #[inline]
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
     #[inline]
-    pub(super) fn new(receiver: crossbeam_channel::Receiver<T>) -> Self {
-        Self { receiver }
+    pub(super) fn downgrade(&self) -> Weak<crossbeam_channel::Receiver<T>> {
+        Arc::downgrade(&self.receiver)
     }
 
+    /// How many messages were left when the last receiver was dropped.
+    #[inline]
+    pub(super) fn depth_at_drop(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.depth_at_drop)
+    }
+
+    /// Reports how many messages are queued, or how many were left when the
+    /// last receiver was dropped. Does not hold the queue open, so senders
+    /// still see it disconnect.
+    pub(crate) fn depth_probe(&self) -> impl Fn() -> usize + Send + 'static
+    where
+        T: Send + 'static,
+    {
+        let receiver = Arc::downgrade(&self.receiver);
+        let depth_at_drop = Arc::clone(&self.depth_at_drop);
+        move || {
+            receiver.upgrade().map_or_else(
+                || depth_at_drop.load(Ordering::Acquire),
+                |receiver| receiver.len(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(super) fn channel(&self) -> &crossbeam_channel::Receiver<T> {
+        &self.receiver
+    }
+
+    #[inline]
+    pub(super) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// The bound the queue was created with, or `None` if it is unbounded.
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.receiver.capacity()
+    }
+
+    /// Takes the queued messages without blocking, up to the
+    /// [drain limit](Self::with_drain_at_most). Messages sent before every
+    /// sender dropped are still delivered; an empty batch does not tell a
+    /// disconnected queue from an idle one, see
+    /// [`receive_state`](Self::receive_state) for that.
     #[inline]
     pub fn receive(&self) -> Vec<T> {
-        self.receiver.try_iter().collect()
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+        let limit = self.drain_limit().unwrap_or(usize::MAX);
+        let mut batch = if self.receiver.is_empty() {
+            Vec::new()
+        } else {
+            Vec::with_capacity(self.capacity_hint.min(limit))
+        };
+        batch.extend(self.receiver.try_iter().take(limit));
+
+        #[cfg(feature = "scheduler-hook")]
+        if batch.is_empty() {
+            crate::scheduler_hook::idle();
+        }
+        batch
+    }
+
+    /// Like [`receive`](Self::receive), but reports an empty queue as
+    /// [`RecvState::Disconnected`] once every sender has dropped, and as
+    /// [`RecvState::Empty`] while one is still connected. Buffered messages
+    /// are always delivered before the disconnection is reported.
+    pub fn receive_state(&self) -> RecvState<T> {
+        let batch = self.receive();
+        if !batch.is_empty() {
+            return RecvState::Messages(batch);
+        }
+
+        match self.receiver.try_recv() {
+            Ok(msg) => RecvState::Messages(vec![msg]),
+            Err(crossbeam_channel::TryRecvError::Empty) => RecvState::Empty,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => RecvState::Disconnected,
+        }
+    }
+
+    /// Like [`receive`](Self::receive), but hands out the messages queued
+    /// when called one at a time instead of collecting them. Messages not
+    /// pulled from the iterator stay queued for the next call.
+    pub fn drain_lazy(&self) -> impl Iterator<Item = T> + '_ {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+        let available = self.receiver.len();
+        self.receiver
+            .try_iter()
+            .take(available.min(self.drain_limit().unwrap_or(usize::MAX)))
+    }
+
+    /// Like [`receive`](Self::receive), but appends the batch to `buf` so an
+    /// agent can reuse one allocation across frames. `buf` is not cleared
+    /// first. Returns how many messages were appended.
+    pub fn drain_into(&self, buf: &mut Vec<T>) -> usize {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+        let len = buf.len();
+        buf.extend(
+            self.receiver
+                .try_iter()
+                .take(self.drain_limit().unwrap_or(usize::MAX)),
+        );
+        let appended = buf.len() - len;
+
+        #[cfg(feature = "scheduler-hook")]
+        if appended == 0 {
+            crate::scheduler_hook::idle();
+        }
+        appended
+    }
+
+    /// Drains the queue but keeps only the newest `keep_last` messages,
+    /// returning them with the number of older messages discarded.
+    pub fn catch_up(&self, keep_last: usize) -> (Vec<T>, usize) {
+        let mut batch: Vec<T> = self.receiver.try_iter().collect();
+        let dropped = batch.len().saturating_sub(keep_last);
+        batch.drain(..dropped);
+
+        (batch, dropped)
+    }
+
+    /// Takes a single message without blocking or allocating a batch.
+    #[inline]
+    pub fn try_recv_one(&self) -> Option<T> {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+        let msg = self.receiver.try_recv().ok();
+
+        #[cfg(feature = "scheduler-hook")]
+        if msg.is_none() {
+            crate::scheduler_hook::idle();
+        }
+        msg
+    }
+
+    pub fn recv_blocking(&self) -> Result<T> {
+        #[cfg(feature = "scheduler-hook")]
+        if crate::scheduler_hook::is_active() {
+            return self.recv_scheduled();
+        }
+
+        match &self.detector {
+            Some((detector, agent)) => detector.recv(*agent, &self.receiver),
+            None => self.receiver.recv().map_err(|_| Error::Disconnected {
+                endpoint: Endpoint::Receiver,
+            }),
+        }
+    }
+
+    /// Like [`recv_blocking`](Self::recv_blocking), but gives up with
+    /// `Ok(None)` once `token` is cancelled, checking it at least every
+    /// millisecond. Cancellation wins over messages that are already queued.
+    pub fn recv_cancellable(&self, token: &CancellationToken) -> Result<Option<T>> {
+        loop {
+            if token.is_cancelled() {
+                return Ok(None);
+            }
+
+            #[cfg(feature = "scheduler-hook")]
+            if crate::scheduler_hook::is_active() {
+                crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+                match self.receiver.try_recv() {
+                    Ok(msg) => return Ok(Some(msg)),
+                    Err(crossbeam_channel::TryRecvError::Empty) => {
+                        crate::scheduler_hook::idle();
+                        continue;
+                    }
+                    Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                        return Err(Error::Disconnected {
+                            endpoint: Endpoint::Receiver,
+                        });
+                    }
+                }
+            }
+
+            match self.receiver.recv_timeout(CANCEL_POLL) {
+                Ok(msg) => return Ok(Some(msg)),
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Receiver,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Waits up to `timeout`, measured on the receiver's clock, for one
+    /// message and returns `Ok(None)` if none arrived. Blocks on the queue
+    /// with the [`SystemClock`], and checks it at least every millisecond
+    /// on other clocks, which may not move on their own.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<T>> {
+        self.recv_deadline(self.clock.now().checked_add(timeout))
+    }
+
+    /// Waits on the receiver's clock until `deadline`, or for good when it is
+    /// too far ahead to be represented.
+    fn recv_deadline(&self, deadline: Option<Instant>) -> Result<Option<T>> {
+        let Some(deadline) = deadline else {
+            return self.recv_blocking().map(Some);
+        };
+
+        if self.clock.is_system() {
+            return match self.receiver.recv_deadline(deadline) {
+                Ok(msg) => Ok(Some(msg)),
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => Ok(None),
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                    Err(Error::Disconnected {
+                        endpoint: Endpoint::Receiver,
+                    })
+                }
+            };
+        }
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(msg) => return Ok(Some(msg)),
+                Err(crossbeam_channel::TryRecvError::Empty) => {}
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Receiver,
+                    });
+                }
+            }
+
+            let remaining = deadline.saturating_duration_since(self.clock.now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.clock.sleep(remaining.min(CANCEL_POLL));
+        }
+    }
+
+    /// [`recv_timeout`](Self::recv_timeout) with the
+    /// [`default_timeout`](Self::default_timeout), or
+    /// [`recv_blocking`](Self::recv_blocking) when there is none.
+    pub fn recv_default(&self) -> Result<Option<T>> {
+        match self.default_timeout() {
+            Some(timeout) => self.recv_timeout(timeout),
+            None => self.recv_blocking().map(Some),
+        }
+    }
+
+    /// Blocks for each message in turn and ends once every sender is gone.
+    /// Any other failure, such as a [deadlock](Error::Deadlock) reported by
+    /// the detector, is yielded as the last item, so it cannot pass for a
+    /// clean disconnect.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        let mut failed = false;
+        iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            match self.recv_blocking() {
+                Err(Error::Disconnected { .. }) => None,
+                result => {
+                    failed = result.is_err();
+                    Some(result)
+                }
+            }
+        })
+    }
+
+    #[inline]
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.receiver.try_iter()
+    }
+
+    pub fn recv_batch(&self, max: usize) -> Result<Vec<T>> {
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+
+        let first = self.recv_blocking()?;
+
+        let mut batch = Vec::with_capacity(max.min(self.receiver.len() + 1));
+        batch.push(first);
+        batch.extend(self.receiver.try_iter().take(max - 1));
+
+        Ok(batch)
+    }
+
+    /// Collects messages until `max`, or the [drain limit](Self::drain_limit)
+    /// if lower, arrived or `timeout` elapsed on the receiver's clock,
+    /// whichever comes first, and returns what it got. Returns early, possibly
+    /// empty, once every sender is gone and the queue is drained.
+    pub fn receive_bounded(&self, max: usize, timeout: Duration) -> Vec<T> {
+        let max = max.min(self.drain_limit().unwrap_or(usize::MAX));
+        let deadline = self.clock.now().checked_add(timeout);
+        let mut batch = Vec::with_capacity(max.min(self.receiver.len()));
+
+        while batch.len() < max {
+            match self.recv_deadline(deadline) {
+                Ok(Some(msg)) => batch.push(msg),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        batch
+    }
+
+    /// Collects messages until one matches `pred`, which is included as the
+    /// last one, or `timeout` elapsed on the receiver's clock. Returns early
+    /// once every sender is gone and the queue is drained. Messages after the
+    /// match stay queued.
+    pub fn receive_until(&self, pred: impl Fn(&T) -> bool, timeout: Duration) -> Vec<T> {
+        let deadline = self.clock.now().checked_add(timeout);
+        let mut batch = Vec::new();
+
+        while let Ok(Some(msg)) = self.recv_deadline(deadline) {
+            let done = pred(&msg);
+            batch.push(msg);
+            if done {
+                break;
+            }
+        }
+        batch
+    }
+
+    pub fn window(&self, dur: Duration) -> Vec<T> {
+        self.clock.sleep(dur);
+        self.receive()
+    }
+
+    pub fn dedup_by_key<K, F>(self, f: F, window: Duration) -> DedupReceiver<T, K, F>
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        DedupReceiver::new(self, f, window)
+    }
+
+    /// Converts every message on the way out, e.g. from the wire format to the
+    /// agent's own representation.
+    pub fn map<U>(self, f: impl Fn(T) -> U + Send + 'static) -> MappedReceiver<U>
+    where
+        T: Send + 'static,
+    {
+        MappedReceiver::new(self, f)
+    }
+
+    pub fn route_by<K, F>(self, classify: F) -> Router<T, K, F>
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        Router::new(self, classify)
+    }
+
+    /// Busy-polls the queue for up to `max_spin` before parking the thread in
+    /// a blocking receive. This keeps a whole core at 100% while spinning, so
+    /// reserve it for latency-critical loops that own a dedicated core.
+    pub fn spin_receive(&self, max_spin: Duration) -> Vec<T> {
+        let deadline = Instant::now() + max_spin;
+
+        while self.receiver.is_empty() && Instant::now() < deadline {
+            hint::spin_loop();
+        }
+
+        self.drain_blocking()
+    }
+
+    /// Yields the thread up to [`Self::YIELD_ATTEMPTS`] times while the queue
+    /// is empty before parking in a blocking receive. Unlike
+    /// [`Self::spin_receive`] this lets other agents sharing the core run.
+    /// Each yield goes through the receiver's [clock](Clock::yield_now).
+    pub fn receive_yield(&self) -> Vec<T> {
+        for _ in 0..Self::YIELD_ATTEMPTS {
+            if !self.receiver.is_empty() {
+                break;
+            }
+            self.clock.yield_now();
+        }
+
+        self.drain_blocking()
+    }
+
+    /// Waits for at least one message, escalating from spinning to yielding to
+    /// parking as the queue stays empty according to the configured
+    /// [`Backoff`], then drains the queue. Returns an empty batch once
+    /// disconnected.
+    pub fn receive_backoff(&self) -> Vec<T> {
+        'wait: {
+            for step in 0..=self.backoff.spin_limit() {
+                for _ in 0..1_u32 << step {
+                    if !self.receiver.is_empty() {
+                        break 'wait;
+                    }
+                    hint::spin_loop();
+                }
+            }
+            for _ in 0..self.backoff.yield_limit() {
+                if !self.receiver.is_empty() {
+                    break 'wait;
+                }
+                self.clock.yield_now();
+            }
+        }
+
+        self.drain_blocking()
+    }
+
+    /// Waits for at least one message using [`receive_strategy`](Self::receive_strategy)
+    /// and drains the queue. Returns an empty batch once disconnected.
+    pub fn wait(&self) -> Vec<T> {
+        match self.receive_strategy() {
+            ReceiveStrategy::Park => self.drain_blocking(),
+            ReceiveStrategy::Spin { max } => self.spin_receive(max),
+            ReceiveStrategy::Yield => self.receive_yield(),
+        }
+    }
+
+    /// A blocking receive that polls at schedule points instead of parking, so
+    /// the scheduler hook can run the sender in the meantime.
+    #[cfg(feature = "scheduler-hook")]
+    fn recv_scheduled(&self) -> Result<T> {
+        loop {
+            crate::scheduler_hook::yield_point(crate::SchedulePoint::Receive);
+
+            match self.receiver.try_recv() {
+                Ok(msg) => return Ok(msg),
+                Err(crossbeam_channel::TryRecvError::Empty) => crate::scheduler_hook::idle(),
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Receiver,
+                    });
+                }
+            }
+        }
+    }
+
+    fn drain_blocking(&self) -> Vec<T> {
+        match self.recv_blocking() {
+            Ok(first) => {
+                let limit = self.drain_limit().unwrap_or(usize::MAX);
+                let mut batch = vec![first];
+                batch.extend(self.receiver.try_iter().take(limit.saturating_sub(1)));
+                batch
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl<T> Receiver<Deadline<T>> {
+    /// Drains the queue earliest-deadline-first. Messages whose deadline has
+    /// already passed are dropped and added to [`Receiver::expired_count`].
+    pub fn receive_by_deadline(&self) -> Vec<Deadline<T>> {
+        let now = self.clock.now();
+
+        let (mut pending, expired): (Vec<_>, Vec<_>) = self
+            .receiver
+            .try_iter()
+            .partition(|message| !message.is_expired(now));
+        self.expired
+            .fetch_add(expired.len() as u64, Ordering::Relaxed);
+
+        pending.sort_by_key(Deadline::due);
+        pending
+    }
+
+    #[inline]
+    pub fn expired_count(&self) -> u64 {
+        self.expired.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // Every clone stores, the last one dropped leaves the final count.
+        self.depth_at_drop
+            .store(self.receiver.len(), Ordering::Release);
     }
 }
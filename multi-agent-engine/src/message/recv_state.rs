@@ -0,0 +1,27 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// What [`Receiver::receive_state`](super::Receiver::receive_state) found on
+/// the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecvState<T> {
+    /// Messages that were queued, possibly sent before every sender dropped.
+    Messages(Vec<T>),
+    /// Nothing is queued, but a sender is still connected.
+    Empty,
+    /// Nothing is queued and every sender has dropped, so nothing ever will be.
+    Disconnected,
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::time::Duration;
+
+/// The awaiting half of a [`OneShot`](super::OneShot) reply channel.
+#[derive(Debug)]
+pub struct Reply<R> {
+    receiver: crossbeam_channel::Receiver<R>,
+}
+
+impl<R> Reply<R> {
+    #[inline]
+    pub(super) fn new(receiver: crossbeam_channel::Receiver<R>) -> Self {
+        Self { receiver }
+    }
+
+    /// Blocks until the responder answers. Fails if it was dropped unanswered.
+    pub fn wait(self) -> Result<R> {
+        self.receiver.recv().map_err(|_| Error::Disconnected {
+            endpoint: Endpoint::Receiver,
+        })
+    }
+
+    pub fn wait_timeout(self, timeout: Duration) -> Result<R> {
+        self.receiver
+            .recv_timeout(timeout)
+            .map_err(|err| match err {
+                crossbeam_channel::RecvTimeoutError::Timeout => {
+                    Error::Timeout { duration: timeout }
+                }
+                crossbeam_channel::RecvTimeoutError::Disconnected => Error::Disconnected {
+                    endpoint: Endpoint::Receiver,
+                },
+            })
+    }
+
+    #[inline]
+    pub fn try_take(&self) -> Option<R> {
+        self.receiver.try_recv().ok()
+    }
+}
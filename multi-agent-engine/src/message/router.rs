@@ -0,0 +1,111 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Receiver;
+use crossbeam_channel::unbounded;
+use multi_agent_engine_core::Result;
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Splits one receiver into per-key sub-queues using a classifier.
+///
+/// Messages whose key has no route, or whose sub-queue receiver was dropped,
+/// are discarded and counted in [`unrouted`](Self::unrouted).
+pub struct Router<T, K, F> {
+    receiver: Receiver<T>,
+    classify: F,
+    routes: HashMap<K, crossbeam_channel::Sender<T>>,
+    unrouted: AtomicU64,
+}
+
+impl<T, K, F> Router<T, K, F>
+where
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    pub(super) fn new(receiver: Receiver<T>, classify: F) -> Self {
+        Self {
+            receiver,
+            classify,
+            routes: HashMap::new(),
+            unrouted: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers a sub-queue for `key` and returns its receiving end.
+    /// Registering the same key again replaces the previous sub-queue.
+    pub fn route(&mut self, key: K) -> Receiver<T> {
+        let (sender, receiver) = unbounded();
+        self.routes.insert(key, sender);
+
+        Receiver::new(receiver)
+    }
+
+    /// Moves every pending message into its sub-queue without blocking and
+    /// returns how many were delivered.
+    pub fn dispatch(&self) -> usize {
+        self.receiver
+            .try_iter()
+            .map(|msg| self.forward(msg))
+            .filter(|delivered| *delivered)
+            .count()
+    }
+
+    /// Forwards messages until every sender of the source queue is gone, or
+    /// fails with the error, such as a deadlock, that stopped the source.
+    pub fn run(self) -> Result<()> {
+        for msg in self.receiver.iter() {
+            self.forward(msg?);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn unrouted(&self) -> u64 {
+        self.unrouted.load(Ordering::Relaxed)
+    }
+
+    fn forward(&self, msg: T) -> bool {
+        let delivered = self
+            .routes
+            .get(&(self.classify)(&msg))
+            .is_some_and(|route| route.send(msg).is_ok());
+
+        if !delivered {
+            self.unrouted.fetch_add(1, Ordering::Relaxed);
+        }
+        delivered
+    }
+}
+
+impl<T, K, F> Debug for Router<T, K, F>
+where
+    T: Debug,
+    K: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("receiver", &self.receiver)
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .field("unrouted", &self.unrouted)
+            .finish_non_exhaustive()
+    }
+}
@@ -14,22 +14,465 @@
  * limitations under the License.
  */
 
-use multi_agent_engine_core::{Error, Result};
-use std::fmt::Debug;
+use super::{DeadLetterQueue, MessageSampler, MessageTap, Receiver, WeakSender};
+#[cfg(feature = "metrics")]
+use super::{MessageSize, QueueHistogram};
+use crate::{AgentContext, CancellationToken, Metrics, observer::SendOrigin};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    iter,
+    sync::{
+        Arc, Mutex, PoisonError, Weak,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const FLUSH_POLL: Duration = Duration::from_micros(100);
+/// How often a send blocked on a full queue checks for shutdown.
+const SHUTDOWN_POLL: Duration = Duration::from_millis(1);
+
+type Format<T> = fn(&T) -> String;
+type Duplicate<T> = fn(&T) -> T;
+type Deferred<T> = Arc<Mutex<VecDeque<(T, Option<SendOrigin>)>>>;
+#[cfg(feature = "metrics")]
+type Size<T> = fn(&T) -> usize;
 
 #[derive(Debug, Clone)]
 pub struct Sender<T> {
-    sender: crossbeam_channel::Sender<T>,
+    sender: Arc<crossbeam_channel::Sender<T>>,
+    config: SenderConfig<T>,
+}
+
+/// Everything a [`Sender`] carries besides its channel, so a
+/// [`WeakSender`] can upgrade to a sender that behaves like the original.
+#[derive(Debug)]
+pub(super) struct SenderConfig<T> {
+    metrics: Option<Metrics>,
+    tap: Option<(MessageTap, Duplicate<T>, Format<T>)>,
+    sampler: Option<(MessageSampler<T>, Duplicate<T>)>,
+    deferred: Option<Deferred<T>>,
+    evict: Option<Weak<crossbeam_channel::Receiver<T>>>,
+    peer: Option<(Weak<crossbeam_channel::Receiver<T>>, Arc<AtomicUsize>)>,
+    dead_letters: Option<(CancellationToken, DeadLetterQueue<T>)>,
+    dropped: Arc<AtomicU64>,
+    #[cfg(feature = "metrics")]
+    histogram: Option<(QueueHistogram, Size<T>)>,
+}
+
+impl<T> Default for SenderConfig<T> {
+    fn default() -> Self {
+        Self {
+            metrics: None,
+            tap: None,
+            sampler: None,
+            deferred: None,
+            evict: None,
+            peer: None,
+            dead_letters: None,
+            dropped: Arc::default(),
+            #[cfg(feature = "metrics")]
+            histogram: None,
+        }
+    }
+}
+
+impl<T> Clone for SenderConfig<T> {
+    fn clone(&self) -> Self {
+        Self {
+            metrics: self.metrics.clone(),
+            tap: self.tap.clone(),
+            sampler: self.sampler.clone(),
+            deferred: self.deferred.clone(),
+            evict: self.evict.clone(),
+            peer: self.peer.clone(),
+            dead_letters: self.dead_letters.clone(),
+            dropped: Arc::clone(&self.dropped),
+            #[cfg(feature = "metrics")]
+            histogram: self.histogram.clone(),
+        }
+    }
 }
 
 impl<T> Sender<T> {
     #[inline]
     pub(super) fn new(sender: crossbeam_channel::Sender<T>) -> Self {
-        Self { sender }
+        Self::from_parts(Arc::new(sender), SenderConfig::default())
+    }
+
+    #[inline]
+    pub(super) fn from_parts(
+        sender: Arc<crossbeam_channel::Sender<T>>,
+        config: SenderConfig<T>,
+    ) -> Self {
+        Self { sender, config }
+    }
+
+    /// Lets [`flush_and_wait`](Self::flush_and_wait) tell a dropped
+    /// `receiver` apart from a slow one.
+    pub(super) fn with_peer(mut self, receiver: &Receiver<T>) -> Self {
+        self.config.peer = Some((receiver.downgrade(), receiver.depth_at_drop()));
+        self
+    }
+
+    /// Makes a full queue discard its oldest message instead of blocking,
+    /// counting each discard in `dropped`.
+    pub(super) fn with_drop_oldest(
+        mut self,
+        receiver: Weak<crossbeam_channel::Receiver<T>>,
+        dropped: Arc<AtomicU64>,
+    ) -> Self {
+        self.config.evict = Some(receiver);
+        self.config.dropped = dropped;
+        self
+    }
+
+    /// Keeps sends from hanging once the agent of `ctx` shuts down: a send
+    /// blocked on a full queue, or into one whose receiver is gone, puts the
+    /// message into `dead_letters` and returns `Ok(())` as soon as shutdown
+    /// was requested.
+    pub fn with_dead_letters(
+        mut self,
+        ctx: &AgentContext,
+        dead_letters: &DeadLetterQueue<T>,
+    ) -> Self {
+        self.config.dead_letters = Some((ctx.cancellation_token().clone(), dead_letters.clone()));
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.config.metrics = Some(metrics);
+        self
+    }
+
+    pub(super) fn with_tap(mut self, tap: &MessageTap) -> Self
+    where
+        T: Clone + Debug,
+    {
+        self.config.tap = Some((tap.clone(), T::clone, |msg| format!("{msg:?}")));
+        self
+    }
+
+    pub(super) fn with_sampler(mut self, sampler: &MessageSampler<T>) -> Self
+    where
+        T: Clone,
+    {
+        self.config.sampler = Some((sampler.clone(), T::clone));
+        self
+    }
+
+    /// Records the size and per-frame count of every message sent through
+    /// this handle into `histogram`.
+    #[cfg(feature = "metrics")]
+    pub fn with_histogram(mut self, histogram: &QueueHistogram) -> Self
+    where
+        T: MessageSize,
+    {
+        self.config.histogram = Some((histogram.clone(), T::size_hint));
+        self
+    }
+
+    /// Runs `record` on this sender's metrics or, failing that, on the
+    /// engine metrics of the agent sending.
+    #[inline]
+    pub(super) fn record_metrics(&self, record: impl FnOnce(&Metrics)) {
+        match &self.config.metrics {
+            Some(metrics) => record(metrics),
+            None => crate::metrics::with_agent_metrics(record),
+        }
     }
 
     #[inline]
     pub fn send(&self, msg: T) -> Result<()> {
-        self.sender.send(msg).map_err(|_| Error::MessageSender)
+        self.send_with(msg, crate::observer::message_sent::<T>, |msg| {
+            match (&self.config.evict, &self.config.dead_letters) {
+                (Some(evict), _) => self.send_evicting(evict, msg).map(|()| true),
+                (None, Some((token, dead_letters))) => {
+                    self.send_until_shutdown(token, dead_letters, msg)
+                }
+                (None, None) => {
+                    self.sender
+                        .send(msg)
+                        .map(|()| true)
+                        .map_err(|_| Error::Disconnected {
+                            endpoint: Endpoint::Sender,
+                        })
+                }
+            }
+        })?;
+
+        Ok(())
+    }
+
+    /// Sends the messages of `msgs` in order without blocking until one does
+    /// not fit, then returns it together with all messages after it, so the
+    /// caller can retry them later. Returns an empty `Vec` once everything
+    /// was sent. Messages are only ever handed back, never dropped or
+    /// dead-lettered, whatever the queue's overflow behaviour; a disconnected
+    /// queue hands back the whole rest of the batch.
+    pub fn send_batch_partial(&self, msgs: impl IntoIterator<Item = T>) -> Vec<T> {
+        let mut msgs = msgs.into_iter();
+
+        while let Some(msg) = msgs.next() {
+            let sent = self.send_with(msg, crate::observer::message_sent::<T>, |msg| {
+                self.sender
+                    .try_send(msg)
+                    .map(|()| true)
+                    .map_err(crossbeam_channel::TrySendError::into_inner)
+            });
+            if let Err(rejected) = sent {
+                return iter::once(rejected).chain(msgs).collect();
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Calls `report` and hands `msg` to `deliver` and, if it reports the
+    /// message as delivered, records it in this sender's metrics, tap,
+    /// sampler and histogram.
+    fn send_with<E>(
+        &self,
+        msg: T,
+        report: impl FnOnce(),
+        deliver: impl FnOnce(T) -> std::result::Result<bool, E>,
+    ) -> std::result::Result<bool, E> {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Send);
+        let tapped = self
+            .config
+            .tap
+            .as_ref()
+            .map(|(tap, duplicate, format)| (tap, duplicate(&msg), format));
+        let sampled = self
+            .config
+            .sampler
+            .as_ref()
+            .filter(|(sampler, _)| sampler.tick())
+            .map(|(sampler, duplicate)| (sampler, duplicate(&msg)));
+        #[cfg(feature = "metrics")]
+        let sized = self
+            .config
+            .histogram
+            .as_ref()
+            .map(|(histogram, size)| (histogram, size(&msg)));
+        // Before delivering, so the receiver cannot react first.
+        report();
+
+        if !deliver(msg)? {
+            return Ok(false);
+        }
+
+        self.record_metrics(|metrics| metrics.record_sent(self.sender.len()));
+        if let Some((tap, msg, format)) = tapped {
+            tap.push(format(&msg));
+        }
+        if let Some((sampler, msg)) = sampled {
+            sampler.push(msg);
+        }
+        crate::rendezvous::message_sent();
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::message_sent();
+        #[cfg(feature = "metrics")]
+        if let Some((histogram, size)) = sized {
+            histogram.record(size);
+        }
+
+        Ok(true)
+    }
+
+    fn send_evicting(
+        &self,
+        evict: &Weak<crossbeam_channel::Receiver<T>>,
+        mut msg: T,
+    ) -> Result<()> {
+        loop {
+            match self.sender.try_send(msg) {
+                Ok(()) => return Ok(()),
+                Err(crossbeam_channel::TrySendError::Full(rejected)) => {
+                    if let Some(receiver) = evict.upgrade()
+                        && receiver.try_recv().is_ok()
+                    {
+                        self.config.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.record_metrics(Metrics::record_dropped);
+                    }
+                    msg = rejected;
+                }
+                Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Sender,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns whether `msg` was delivered rather than dead-lettered.
+    fn send_until_shutdown(
+        &self,
+        token: &CancellationToken,
+        dead_letters: &DeadLetterQueue<T>,
+        mut msg: T,
+    ) -> Result<bool> {
+        loop {
+            match self.sender.send_timeout(msg, SHUTDOWN_POLL) {
+                Ok(()) => return Ok(true),
+                Err(err) if token.is_cancelled() => {
+                    dead_letters.push(err.into_inner());
+                    return Ok(false);
+                }
+                Err(crossbeam_channel::SendTimeoutError::Timeout(rejected)) => msg = rejected,
+                Err(crossbeam_channel::SendTimeoutError::Disconnected(_)) => {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Sender,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Messages this queue discarded to make room for newer ones.
+    #[inline]
+    pub fn dropped_count(&self) -> u64 {
+        self.config.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Sends `msg` as the last message and drops this sender, so the
+    /// receiver drains it and then observes disconnection.
+    ///
+    /// Fails with [`Error::SenderInUse`] without sending anything while other
+    /// clones of this sender are alive, since those would keep the queue
+    /// open and could still send after `msg`.
+    pub fn send_and_close(self, msg: T) -> Result<()> {
+        let clones = Arc::strong_count(&self.sender) - 1;
+        if clones > 0 {
+            return Err(Error::SenderInUse { clones });
+        }
+
+        self.send(msg)
+    }
+
+    /// Ties this sender to the frame counter of `ctx`, enabling
+    /// [`send_at_next_frame`](Self::send_at_next_frame). Deferred messages are
+    /// recorded like sent ones, in the metrics, tap, sampler and histogram the
+    /// sender has at this point.
+    pub fn with_frame_boundary(mut self, ctx: &AgentContext) -> Self
+    where
+        T: Send + 'static,
+    {
+        let deferred = Deferred::default();
+        let held = Arc::downgrade(&deferred);
+        let channel = Arc::downgrade(&self.sender);
+        let config = SenderConfig {
+            deferred: None,
+            ..self.config.clone()
+        };
+
+        ctx.on_frame(move || {
+            let (Some(held), Some(channel)) = (held.upgrade(), channel.upgrade()) else {
+                return false;
+            };
+            let sender = Sender::from_parts(channel, config.clone());
+            let mut held = held.lock().unwrap_or_else(PoisonError::into_inner);
+            while let Some((msg, origin)) = held.pop_front() {
+                // Reported on delivery rather than up front, so a message
+                // retried at later frames is reported once.
+                let sent = sender.send_with(
+                    msg,
+                    || (),
+                    |msg| {
+                        let deliver = || sender.sender.try_send(msg);
+                        match &origin {
+                            Some(origin) => origin.deliver::<T, _>(deliver),
+                            None => deliver(),
+                        }
+                        .map(|()| true)
+                    },
+                );
+                if let Err(err) = sent {
+                    if err.is_disconnected() {
+                        return false;
+                    }
+                    held.push_front((err.into_inner(), origin));
+                    break;
+                }
+            }
+            true
+        });
+
+        self.config.deferred = Some(deferred);
+        self
+    }
+
+    /// Holds `msg` until the next [`AgentContext::advance_frame`], so every
+    /// agent first sees it in the same logical frame. Messages that do not
+    /// fit in a full bounded queue at that boundary wait for the next one.
+    /// Fails with [`Error::NoFrameBoundary`] unless the sender was set up with
+    /// [`with_frame_boundary`](Self::with_frame_boundary).
+    pub fn send_at_next_frame(&self, msg: T) -> Result<()> {
+        self.config
+            .deferred
+            .as_ref()
+            .ok_or(Error::NoFrameBoundary)?
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back((msg, crate::observer::send_origin()));
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.sender.capacity()
+    }
+
+    /// Fraction of the queue capacity currently in use, from `0.0` to `1.0`.
+    /// Unbounded and zero-capacity queues always report `0.0`.
+    #[inline]
+    pub fn fullness(&self) -> f32 {
+        match self.sender.capacity() {
+            Some(capacity) if capacity > 0 => self.sender.len() as f32 / capacity as f32,
+            _ => 0.0,
+        }
+    }
+
+    /// Blocks until the peer has taken every queued message off the queue, or
+    /// fails with [`Error::Timeout`] once `timeout` elapses, and with
+    /// [`Error::Disconnected`] once the receiver is dropped with messages
+    /// left. An empty queue means messages were received, not that the peer
+    /// finished handling them.
+    pub fn flush_and_wait(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            // Before checking the receiver, which discards what is left when
+            // dropped, so a queue emptied that way is not taken as received.
+            let empty = self.sender.is_empty();
+            if let Some((receiver, left)) = &self.config.peer
+                && receiver.strong_count() == 0
+            {
+                if left.load(Ordering::Acquire) > 0 {
+                    return Err(Error::Disconnected {
+                        endpoint: Endpoint::Sender,
+                    });
+                }
+                return Ok(());
+            }
+            if empty {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout { duration: timeout });
+            }
+            thread::sleep(FLUSH_POLL);
+        }
+    }
+
+    #[inline]
+    pub fn downgrade(&self) -> WeakSender<T> {
+        WeakSender::new(Arc::downgrade(&self.sender), self.config.clone())
     }
 }
@@ -0,0 +1,75 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Receiver;
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// Merges queues fed by one [`Sequencer`](super::Sequencer) and delivers
+/// their messages strictly in sequence order, buffering any that arrive
+/// ahead of a missing stamp.
+#[derive(Debug)]
+pub struct SequencedReceiver<T> {
+    receivers: Vec<Receiver<(u64, T)>>,
+    state: Mutex<State<T>>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    next: u64,
+    buffered: BTreeMap<u64, T>,
+}
+
+impl<T> SequencedReceiver<T> {
+    pub fn new(receivers: impl IntoIterator<Item = Receiver<(u64, T)>>) -> Self {
+        Self {
+            receivers: receivers.into_iter().collect(),
+            state: Mutex::new(State {
+                next: 0,
+                buffered: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Drains every queue and returns the contiguous run of messages starting
+    /// at the next expected sequence number, with their stamps.
+    pub fn receive(&self) -> Vec<(u64, T)> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for receiver in &self.receivers {
+            state.buffered.extend(receiver.try_iter());
+        }
+
+        let State { next, buffered } = &mut *state;
+        let mut batch = Vec::new();
+        while let Some(msg) = buffered.remove(next) {
+            batch.push((*next, msg));
+            *next += 1;
+        }
+        batch
+    }
+
+    /// Messages received but waiting for an earlier stamp.
+    pub fn pending(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .buffered
+            .len()
+    }
+}
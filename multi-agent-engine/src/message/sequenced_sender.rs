@@ -0,0 +1,39 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Sender, Sequencer};
+use multi_agent_engine_core::Result;
+
+#[derive(Debug, Clone)]
+pub struct SequencedSender<T> {
+    sender: Sender<(u64, T)>,
+    sequencer: Sequencer,
+}
+
+impl<T> SequencedSender<T> {
+    #[inline]
+    pub(super) fn new(sender: Sender<(u64, T)>, sequencer: Sequencer) -> Self {
+        Self { sender, sequencer }
+    }
+
+    /// Stamps `msg` with the next global sequence number and sends it,
+    /// returning the stamp.
+    pub fn send(&self, msg: T) -> Result<u64> {
+        let seq = self.sequencer.next();
+        self.sender.send((seq, msg))?;
+        Ok(seq)
+    }
+}
@@ -0,0 +1,52 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Queue, Receiver, SequencedSender};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// A global monotonic counter that stamps sends across any number of queues,
+/// so a [`SequencedReceiver`](super::SequencedReceiver) merging them can
+/// deliver in one total order.
+///
+/// Every send pays for an atomic increment on a counter shared by all
+/// senders, and the merging receiver holds back every message behind the
+/// slowest in-flight one. Each stamp must reach a merged queue: a stamp lost
+/// to a failed send leaves a gap that stalls delivery for good.
+#[derive(Debug, Clone, Default)]
+pub struct Sequencer {
+    next: Arc<AtomicU64>,
+}
+
+impl Sequencer {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub(super) fn next(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::AcqRel)
+    }
+
+    pub fn channel<T>(&self) -> (SequencedSender<T>, Receiver<(u64, T)>) {
+        let (sender, receiver) = Queue::channel();
+
+        (SequencedSender::new(sender, self.clone()), receiver)
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::SeededRng;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A queue for fuzz tests that delivers messages out of order.
+///
+/// Each [`receive`](Self::receive) hands out the pending messages with each
+/// one at most `window` positions away from where it was sent, driven by a
+/// seeded RNG so a failing ordering can be replayed. A window of `0` preserves
+/// the send order.
+#[derive(Debug)]
+pub struct ShuffleQueue<T> {
+    state: Arc<Mutex<Shuffle<T>>>,
+    window: usize,
+}
+
+#[derive(Debug)]
+struct Shuffle<T> {
+    messages: Vec<T>,
+    rng: SeededRng,
+}
+
+impl<T> ShuffleQueue<T> {
+    pub fn new(seed: u64, window: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(Shuffle {
+                messages: Vec::new(),
+                rng: SeededRng::new(seed),
+            })),
+            window,
+        }
+    }
+
+    pub fn send(&self, msg: T) {
+        self.lock().messages.push(msg);
+    }
+
+    pub fn receive(&self) -> Vec<T> {
+        let mut state = self.lock();
+        let Shuffle { messages, rng } = &mut *state;
+
+        // Every message is sorted by its send position pushed back by up to
+        // `window`, so none passes one sent more than `window` after it.
+        let reach = self.window as u64 + 1;
+        let mut keyed: Vec<(usize, T)> = messages
+            .drain(..)
+            .enumerate()
+            .map(|(i, msg)| (i + rng.below(reach) as usize, msg))
+            .collect();
+        keyed.sort_by_key(|(key, _)| *key);
+
+        keyed.into_iter().map(|(_, msg)| msg).collect()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().messages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shuffle<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for ShuffleQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            window: self.window,
+        }
+    }
+}
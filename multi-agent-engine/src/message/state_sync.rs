@@ -0,0 +1,78 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use arc_swap::ArcSwapOption;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Hands the most recent snapshot of an authoritative state to a reader.
+///
+/// The owner [`publish`](Self::publish)es a copy of its state every tick and
+/// the reader picks up whichever snapshot is newest with
+/// [`latest`](Self::latest). Older snapshots are simply replaced, so a slow
+/// reader never builds up a backlog and never blocks the publisher.
+#[derive(Debug)]
+pub struct StateSync<S> {
+    latest: Arc<ArcSwapOption<S>>,
+    version: Arc<AtomicU64>,
+}
+
+impl<S> StateSync<S> {
+    pub fn new() -> Self {
+        Self {
+            latest: Arc::new(ArcSwapOption::empty()),
+            version: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn publish(&self, state: &S)
+    where
+        S: Clone,
+    {
+        self.latest.store(Some(Arc::new(state.clone())));
+        self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// The newest published snapshot, `None` before the first publish.
+    pub fn latest(&self) -> Option<S>
+    where
+        S: Clone,
+    {
+        self.latest.load().as_deref().cloned()
+    }
+
+    /// How many snapshots have been published so far.
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+}
+
+impl<S> Clone for StateSync<S> {
+    fn clone(&self) -> Self {
+        Self {
+            latest: Arc::clone(&self.latest),
+            version: Arc::clone(&self.version),
+        }
+    }
+}
+
+impl<S> Default for StateSync<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
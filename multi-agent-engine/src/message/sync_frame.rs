@@ -0,0 +1,46 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Diff;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One state update produced by a [`DeltaEncoder`](super::DeltaEncoder).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SyncFrame<S: Diff> {
+    /// The full state, from which a receiver can always resync.
+    Keyframe { version: u64, state: S },
+    /// The change from the snapshot `base` the receiver acknowledged earlier.
+    Delta {
+        base: u64,
+        version: u64,
+        delta: S::Delta,
+    },
+}
+
+impl<S: Diff> SyncFrame<S> {
+    pub fn version(&self) -> u64 {
+        match self {
+            Self::Keyframe { version, .. } | Self::Delta { version, .. } => *version,
+        }
+    }
+
+    pub fn is_keyframe(&self) -> bool {
+        matches!(self, Self::Keyframe { .. })
+    }
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::SenderId;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct TaggedReceiver<T> {
+    receiver: crossbeam_channel::Receiver<(SenderId, T)>,
+}
+
+impl<T> TaggedReceiver<T> {
+    #[inline]
+    pub(super) fn new(receiver: crossbeam_channel::Receiver<(SenderId, T)>) -> Self {
+        Self { receiver }
+    }
+
+    #[inline]
+    pub fn receive(&self) -> Vec<T> {
+        self.receiver.try_iter().map(|(_, msg)| msg).collect()
+    }
+
+    /// Drains pending messages along with the id of the
+    /// [`TaggedSender`](super::TaggedSender) that sent each one.
+    #[inline]
+    pub fn receive_tagged(&self) -> Vec<(SenderId, T)> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Drains pending messages and interleaves them one per sender, in the
+    /// order senders were first seen, so a chatty sender cannot push a quiet
+    /// sender's messages to the back of the batch. Order within a sender is
+    /// preserved.
+    pub fn receive_fair(&self) -> Vec<T> {
+        let mut sources: Vec<(SenderId, VecDeque<T>)> = Vec::new();
+        let mut total = 0;
+
+        for (id, msg) in self.receiver.try_iter() {
+            match sources.iter_mut().find(|(source, _)| *source == id) {
+                Some((_, pending)) => pending.push_back(msg),
+                None => sources.push((id, VecDeque::from([msg]))),
+            }
+            total += 1;
+        }
+
+        let mut batch = Vec::with_capacity(total);
+        while batch.len() < total {
+            for (_, pending) in &mut sources {
+                batch.extend(pending.pop_front());
+            }
+        }
+
+        batch
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::SenderId;
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+#[derive(Debug)]
+pub struct TaggedSender<T> {
+    sender: crossbeam_channel::Sender<(SenderId, T)>,
+    id: SenderId,
+    next_id: Arc<AtomicU64>,
+}
+
+impl<T> TaggedSender<T> {
+    #[inline]
+    pub(super) fn new(sender: crossbeam_channel::Sender<(SenderId, T)>) -> Self {
+        Self {
+            sender,
+            id: SenderId(0),
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> SenderId {
+        self.id
+    }
+
+    #[inline]
+    pub fn send(&self, msg: T) -> Result<()> {
+        self.sender
+            .send((self.id, msg))
+            .map_err(|_| Error::Disconnected {
+                endpoint: Endpoint::Sender,
+            })
+    }
+}
+
+impl<T> Clone for TaggedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            id: SenderId(self.next_id.fetch_add(1, Ordering::Relaxed)),
+            next_id: Arc::clone(&self.next_id),
+        }
+    }
+}
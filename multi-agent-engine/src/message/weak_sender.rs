@@ -0,0 +1,39 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Sender, sender::SenderConfig};
+use std::sync::Weak;
+
+/// Upgrades to a sender with the same overflow behaviour, drop counter,
+/// metrics and recording as the one it was downgraded from.
+#[derive(Debug, Clone)]
+pub struct WeakSender<T> {
+    sender: Weak<crossbeam_channel::Sender<T>>,
+    config: SenderConfig<T>,
+}
+
+impl<T> WeakSender<T> {
+    #[inline]
+    pub(super) fn new(sender: Weak<crossbeam_channel::Sender<T>>, config: SenderConfig<T>) -> Self {
+        Self { sender, config }
+    }
+
+    #[inline]
+    pub fn upgrade(&self) -> Option<Sender<T>> {
+        let sender = self.sender.upgrade()?;
+        Some(Sender::from_parts(sender, self.config.clone()))
+    }
+}
@@ -0,0 +1,180 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    Clock, SystemClock,
+    message::{MessageKind, MessageStats},
+};
+use std::{
+    cell::RefCell,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static AGENT_METRICS: RefCell<Option<Metrics>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    messages_sent: AtomicU64,
+    messages_dropped: AtomicU64,
+    queue_depth: AtomicU64,
+    frames: AtomicU64,
+    frame_time_nanos: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    counters: Arc<Counters>,
+    message_stats: MessageStats,
+    clock: Arc<dyn Clock>,
+    started: Instant,
+    warmup: Duration,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            counters: Arc::default(),
+            message_stats: MessageStats::new(),
+            started: clock.now(),
+            clock,
+            warmup: Duration::ZERO,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.started = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Ignores every sample recorded within `warmup` from now, so start-up
+    /// transients such as cold caches and thread scheduling don't skew the
+    /// steady-state numbers.
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.started = self.clock.now();
+        self.warmup = warmup;
+        self
+    }
+
+    pub fn is_warming_up(&self) -> bool {
+        self.clock.now().saturating_duration_since(self.started) < self.warmup
+    }
+
+    pub fn record_sent(&self, queue_depth: usize) {
+        if self.is_warming_up() {
+            return;
+        }
+
+        self.counters.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.counters
+            .queue_depth
+            .store(queue_depth as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped(&self) {
+        if self.is_warming_up() {
+            return;
+        }
+        self.counters
+            .messages_dropped
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_frame(&self, frame_time: Duration) {
+        if self.is_warming_up() {
+            return;
+        }
+        self.counters.frames.fetch_add(1, Ordering::Relaxed);
+        self.counters
+            .frame_time_nanos
+            .store(frame_time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Records the delivery latency of `msg` under its
+    /// [kind](MessageKind::kind) in the [per-kind stats](Self::message_stats).
+    pub fn record_latency<T: MessageKind>(&self, msg: &T, latency: Duration) {
+        if self.is_warming_up() {
+            return;
+        }
+        self.message_stats.record(msg, latency);
+    }
+
+    pub fn messages_sent(&self) -> u64 {
+        self.counters.messages_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn messages_dropped(&self) -> u64 {
+        self.counters.messages_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn queue_depth(&self) -> u64 {
+        self.counters.queue_depth.load(Ordering::Relaxed)
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.counters.frames.load(Ordering::Relaxed)
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        Duration::from_nanos(self.counters.frame_time_nanos.load(Ordering::Relaxed))
+    }
+
+    /// The latencies given to [`record_latency`](Self::record_latency), which
+    /// can be exported with [`MessageStats::write_csv`].
+    pub fn message_stats(&self) -> &MessageStats {
+        &self.message_stats
+    }
+
+    #[cfg(feature = "prometheus")]
+    pub fn registry(&self) -> prometheus::Registry {
+        let registry = prometheus::Registry::new();
+        registry
+            .register(Box::new(crate::metrics_collector::MetricsCollector::new(
+                self.clone(),
+            )))
+            .expect("engine metric descriptors are unique and valid");
+        registry
+    }
+}
+
+/// Makes `metrics` record the sends of the calling agent thread through
+/// senders without metrics of their own.
+pub(crate) fn record_agent_sends(metrics: Metrics) {
+    AGENT_METRICS.set(Some(metrics));
+}
+
+/// Runs `record` on the metrics of the calling agent thread, if any.
+pub(crate) fn with_agent_metrics(record: impl FnOnce(&Metrics)) {
+    AGENT_METRICS.with_borrow(|metrics| {
+        if let Some(metrics) = metrics {
+            record(metrics);
+        }
+    });
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
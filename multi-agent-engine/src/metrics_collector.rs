@@ -0,0 +1,116 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Metrics;
+use prometheus::{
+    Gauge, IntCounter, IntGauge,
+    core::{Collector, Desc},
+    proto::MetricFamily,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug)]
+pub(crate) struct MetricsCollector {
+    metrics: Metrics,
+    messages_sent: ExportedCounter,
+    messages_dropped: ExportedCounter,
+    queue_depth: IntGauge,
+    frames: ExportedCounter,
+    frame_time: Gauge,
+}
+
+/// A Prometheus counter following one of the [`Metrics`] totals.
+///
+/// Collecting adds only what the total grew by since the highest value
+/// exported so far, swapped in atomically, so concurrent scrapes neither
+/// reset the counter in between nor count the same increments twice.
+#[derive(Debug)]
+struct ExportedCounter {
+    counter: IntCounter,
+    exported: AtomicU64,
+}
+
+impl ExportedCounter {
+    fn new(name: &str, help: &str) -> Self {
+        Self {
+            counter: IntCounter::new(name, help).unwrap(),
+            exported: AtomicU64::new(0),
+        }
+    }
+
+    fn update(&self, total: u64) {
+        let exported = self.exported.fetch_max(total, Ordering::AcqRel);
+        self.counter.inc_by(total.saturating_sub(exported));
+    }
+}
+
+impl MetricsCollector {
+    pub(crate) fn new(metrics: Metrics) -> Self {
+        Self {
+            metrics,
+            messages_sent: ExportedCounter::new(
+                "multi_agent_engine_messages_sent_total",
+                "Messages delivered to agent queues.",
+            ),
+            messages_dropped: ExportedCounter::new(
+                "multi_agent_engine_messages_dropped_total",
+                "Messages dropped before reaching an agent queue.",
+            ),
+            queue_depth: IntGauge::new(
+                "multi_agent_engine_queue_depth",
+                "Messages waiting in the most recently used queue.",
+            )
+            .unwrap(),
+            frames: ExportedCounter::new("multi_agent_engine_frames_total", "Frames completed."),
+            frame_time: Gauge::new(
+                "multi_agent_engine_frame_time_seconds",
+                "Duration of the most recent frame.",
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl Collector for MetricsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [
+            self.messages_sent.counter.desc(),
+            self.messages_dropped.counter.desc(),
+            self.queue_depth.desc(),
+            self.frames.counter.desc(),
+            self.frame_time.desc(),
+        ]
+        .concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        self.messages_sent.update(self.metrics.messages_sent());
+        self.messages_dropped
+            .update(self.metrics.messages_dropped());
+        self.queue_depth.set(self.metrics.queue_depth() as i64);
+        self.frames.update(self.metrics.frames());
+        self.frame_time.set(self.metrics.frame_time().as_secs_f64());
+
+        [
+            self.messages_sent.counter.collect(),
+            self.messages_dropped.counter.collect(),
+            self.queue_depth.collect(),
+            self.frames.counter.collect(),
+            self.frame_time.collect(),
+        ]
+        .concat()
+    }
+}
@@ -14,9 +14,39 @@
  * limitations under the License.
  */
 
-use crate::{Controller, Simulator};
-use multi_agent_engine_core::{Error, Result};
-use std::thread;
+#[cfg(feature = "thread-priority")]
+use crate::ThreadPriority;
+use crate::{
+    AgentContext, AgentOutcomes, CancellationToken, Clock, Controller, EngineHandle, Heartbeat,
+    LogLevel, LogSink, Metrics, NoAgent, NoLog, Observer, PanicPolicy, SeedLog, SeededRng, Shared,
+    ShutdownOrder, ShutdownReason, SimClock, Simulator, StartupReport, SystemClock, TeardownReport,
+    Warning, WarningMonitor,
+    agent_context::{FrameHooks, Observers},
+    agent_thread::AgentThread,
+    convergence::ConvergenceObserver,
+    engine_handle::{AgentSlot, BeforeJoin, ShutdownFlush},
+    message::{self, ReceiveStrategy},
+    record::Transcript,
+    rendezvous::Rendezvous,
+    thread_config::ThreadConfig,
+};
+use crossbeam_channel::Receiver;
+use multi_agent_engine_core::{AgentId, Error, Result};
+use std::{
+    mem,
+    sync::{
+        Arc, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use std::{fs, io, path::Path};
+
+const STARTUP_POLL: Duration = Duration::from_millis(1);
 
 pub struct MultiAgentEngine<C, S>
 where
@@ -25,8 +55,40 @@ where
 {
     controller: C,
     simulator: S,
+    cancellation: CancellationToken,
+    seed: Option<u64>,
+    seed_log: SeedLog,
+    metrics: Metrics,
+    controller_heartbeat: Heartbeat,
+    simulator_heartbeat: Heartbeat,
+    heartbeat_timeout: Option<Duration>,
+    controller_thread: ThreadConfig,
+    simulator_thread: ThreadConfig,
+    shutdown_order: ShutdownOrder,
+    clock: Arc<dyn Clock>,
+    observers: Observers,
+    log_sink: Arc<dyn LogSink>,
+    startup_timeout: Option<Duration>,
+    before_join: Option<BeforeJoin>,
+    shutdown_flush: Vec<ShutdownFlush>,
+    shutdown_grace: Duration,
+    teardown: TeardownReport,
+    startup: StartupReport,
+    slow_startup: Option<Duration>,
+    monitor: WarningMonitor,
+    start_barrier: bool,
+    stop_barrier: bool,
+    ordered_start: bool,
+    frame_limit: Option<u64>,
+    max_message_size: Option<usize>,
+    message_types: Vec<MessageType>,
 }
 
+/// The size of a [registered queue](MultiAgentEngine::with_queue)'s messages,
+/// checked against [`with_max_message_size`](MultiAgentEngine::with_max_message_size)
+/// on spawn.
+type MessageType = fn(usize) -> Option<Warning>;
+
 impl<C, S> MultiAgentEngine<C, S>
 where
     C: Controller + Send + 'static,
@@ -36,21 +98,727 @@ where
         Self {
             controller,
             simulator,
+            cancellation: CancellationToken::new(),
+            seed: None,
+            seed_log: SeedLog::default(),
+            metrics: Metrics::new(),
+            controller_heartbeat: Heartbeat::new(),
+            simulator_heartbeat: Heartbeat::new(),
+            heartbeat_timeout: None,
+            controller_thread: ThreadConfig::default(),
+            simulator_thread: ThreadConfig::default(),
+            shutdown_order: ShutdownOrder::default(),
+            clock: Arc::new(SystemClock),
+            observers: Observers::default(),
+            log_sink: Arc::new(NoLog),
+            startup_timeout: None,
+            before_join: None,
+            shutdown_flush: Vec::new(),
+            shutdown_grace: Duration::ZERO,
+            teardown: TeardownReport::default(),
+            startup: StartupReport::default(),
+            slow_startup: None,
+            monitor: WarningMonitor::new(),
+            start_barrier: false,
+            stop_barrier: false,
+            ordered_start: false,
+            frame_limit: None,
+            max_message_size: None,
+            message_types: Vec::new(),
         }
     }
 
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Filled in on spawn when the engine has a [seed](Self::with_seed),
+    /// with what it takes to reproduce the run.
+    pub fn seed_log(&self) -> SeedLog {
+        self.seed_log.clone()
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    pub fn with_controller_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.controller_heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_simulator_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.simulator_heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    #[cfg(feature = "prometheus")]
+    pub fn metrics_registry(&self) -> prometheus::Registry {
+        self.metrics.registry()
+    }
+
+    /// Registers a queue for the [teardown report](Self::teardown_report).
+    /// The queue is not kept open: once every receiver is dropped, the report
+    /// counts the messages it held at that point.
+    pub fn with_queue<T>(mut self, name: impl Into<String>, receiver: &message::Receiver<T>) -> Self
+    where
+        T: Send + 'static,
+    {
+        self.teardown
+            .register(name.into(), Box::new(receiver.depth_probe()));
+        self.message_types.push(message::check_message_size::<T>);
+        self
+    }
+
+    /// Reports a [`Warning::MessageSize`] on spawn for every
+    /// [registered queue](Self::with_queue) whose message type takes more
+    /// than `bytes`.
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
+    /// Filled in with the undrained [registered queues](Self::with_queue) once
+    /// the engine is joined, or once a timed out join detaches its agents.
+    pub fn teardown_report(&self) -> TeardownReport {
+        self.teardown.clone()
+    }
+
+    /// Filled in with each agent's startup latency as the engine runs.
+    pub fn startup_report(&self) -> StartupReport {
+        self.startup.clone()
+    }
+
+    /// Reports [`EngineEvent::SlowStartup`](crate::EngineEvent::SlowStartup)
+    /// to the observers for every agent whose first frame came more than
+    /// `threshold` after spawning.
+    pub fn with_slow_startup_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_startup = Some(threshold);
+        self
+    }
+
+    /// Collects the warnings agents [report](AgentContext::report) while
+    /// running.
+    pub fn warning_monitor(&self) -> WarningMonitor {
+        self.monitor.clone()
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn with_observer(self, observer: impl Observer + 'static) -> Self {
+        self.observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(observer));
+        self
+    }
+
+    /// Sends the engine's lifecycle messages to `sink` instead of discarding
+    /// them.
+    pub fn with_log_sink(mut self, sink: impl LogSink + 'static) -> Self {
+        self.log_sink = Arc::new(sink);
+        self
+    }
+
+    /// Makes [`spawn`](Self::spawn) wait until both agents have called
+    /// [`AgentContext::mark_ready`]. If an agent exits before marking itself
+    /// ready, or `timeout` elapses first, the engine is cancelled and joining
+    /// it fails with [`Error::StartupFailed`], together with the errors the
+    /// agents returned.
+    ///
+    /// Only agents overriding `run_with_context` can mark themselves ready:
+    /// with a startup timeout, an agent implementing just `run` always fails
+    /// to start.
+    pub fn with_startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the [`ReceiveStrategy`] inherited by receivers used on both agent
+    /// threads that do not set their own.
+    pub fn with_receive_strategy(mut self, strategy: ReceiveStrategy) -> Self {
+        self.controller_thread.receive = strategy;
+        self.simulator_thread.receive = strategy;
+        self
+    }
+
+    /// Caps how many messages a single `receive` or `wait` returns on both
+    /// agent threads, for receivers without their own
+    /// [`with_drain_at_most`](crate::message::Receiver::with_drain_at_most).
+    pub fn with_max_drain_per_frame(mut self, max: usize) -> Self {
+        self.controller_thread.max_drain = Some(max);
+        self.simulator_thread.max_drain = Some(max);
+        self
+    }
+
+    /// Sets how long [`Receiver::recv_default`](crate::message::Receiver::recv_default)
+    /// waits on the controller thread, for receivers without their own
+    /// [`with_default_timeout`](crate::message::Receiver::with_default_timeout).
+    /// The timeout belongs to the controller thread only: on threads the
+    /// controller spawns, `recv_default` blocks without a timeout.
+    pub fn with_controller_recv_timeout(mut self, timeout: Duration) -> Self {
+        self.controller_thread.recv_timeout = Some(timeout);
+        self
+    }
+
+    /// Like [`with_controller_recv_timeout`](Self::with_controller_recv_timeout),
+    /// for the simulator thread.
+    pub fn with_simulator_recv_timeout(mut self, timeout: Duration) -> Self {
+        self.simulator_thread.recv_timeout = Some(timeout);
+        self
+    }
+
+    /// Runs `hook` once both agent threads have finished, before
+    /// [`EngineHandle::join`] returns. Shared state captured by the hook holds
+    /// the agents' final outputs. The hook does not run if joining times out.
+    pub fn with_before_join(mut self, hook: impl FnOnce() + Send + 'static) -> Self {
+        self.before_join = Some(Box::new(hook));
+        self
+    }
+
+    /// Runs `flush` when the engine stops, typically to flush a
+    /// [`JsonLinesRecorder`](crate::record::JsonLinesRecorder) or sync an
+    /// [`EventLog`](crate::record::EventLog). Unlike
+    /// [`with_before_join`](Self::with_before_join) it also runs when joining
+    /// or shutting down times out, so a recording is complete up to that point
+    /// even when agents have to be detached. A failing flush fails the join.
+    pub fn with_shutdown_flush(
+        mut self,
+        flush: impl FnOnce() -> Result<()> + Send + 'static,
+    ) -> Self {
+        self.shutdown_flush.push(Box::new(flush));
+        self
+    }
+
+    /// Runs both agents under `hook`, which decides the order of their
+    /// queue and [`Shared`](crate::Shared) operations. Meant for reproducing
+    /// races in tests, for example with a [`PctScheduler`](crate::PctScheduler).
+    #[cfg(feature = "scheduler-hook")]
+    pub fn with_scheduler_hook(mut self, hook: Arc<dyn crate::SchedulerHook>) -> Self {
+        hook.register(AgentId::CONTROLLER);
+        hook.register(AgentId::SIMULATOR);
+        self.controller_thread.scheduler = Some(Arc::clone(&hook));
+        self.simulator_thread.scheduler = Some(hook);
+        self
+    }
+
+    /// Time [`EngineHandle::shutdown`] gives the shutdown stages to exit
+    /// cooperatively before it cancels every agent and its own timeout starts
+    /// counting. [`EngineHandle::join_timeout`] also cancels agents still
+    /// running after its timeout, and waits this long before detaching them.
+    pub fn with_shutdown_grace(mut self, grace: Duration) -> Self {
+        self.shutdown_grace = grace;
+        self
+    }
+
+    /// Makes [`AgentContext::wait_for_start`] block until both agents called
+    /// it. Both agents must call it, or the first one waits until shutdown.
+    pub fn with_start_barrier(mut self) -> Self {
+        self.start_barrier = true;
+        self
+    }
+
+    /// Like [`with_start_barrier`](Self::with_start_barrier), but then holds
+    /// the simulator back until the controller ends its startup phase with
+    /// [`AgentContext::release_start`], so the controller's startup messages
+    /// are always sent before the simulator's.
+    ///
+    /// The startup phase also ends at the controller's first checkpoint,
+    /// frame advance or sent message, and when it returns. A controller that
+    /// only implements [`Controller::run`] can never call `release_start`, so
+    /// only its first message is guaranteed to come before the simulator's.
+    pub fn with_ordered_start(mut self) -> Self {
+        self.start_barrier = true;
+        self.ordered_start = true;
+        self
+    }
+
+    /// Makes [`AgentContext::wait_for_stop`] block until both agents called
+    /// it. Both agents must call it, or the first one waits until shutdown.
+    pub fn with_stop_barrier(mut self) -> Self {
+        self.stop_barrier = true;
+        self
+    }
+
+    /// Cancels the engine once an agent advanced the shared frame counter to
+    /// `frames`, so agents stopping at their next
+    /// [`checkpoint`](AgentContext::checkpoint) run exactly that many frames.
+    pub fn with_frame_limit(mut self, frames: u64) -> Self {
+        self.frame_limit = Some(frames);
+        self
+    }
+
+    pub fn with_shutdown_order(mut self, order: ShutdownOrder) -> Self {
+        self.shutdown_order = order;
+        self
+    }
+
+    pub fn with_controller_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.controller_thread.panic = policy;
+        self
+    }
+
+    pub fn with_simulator_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.simulator_thread.panic = policy;
+        self
+    }
+
+    /// Pins the controller thread to the given core before `run` is called.
+    ///
+    /// Pinning is best-effort: it is supported on Linux, Android, Windows and
+    /// FreeBSD. On platforms such as macOS that do not expose thread affinity,
+    /// or when `core` is not one of the available cores, the agent still runs
+    /// and a [`Warning::Affinity`](crate::Warning::Affinity) is reported on the [`EngineHandle`].
+    #[cfg(feature = "core_affinity")]
+    pub fn pin_controller_to(mut self, core: usize) -> Self {
+        self.controller_thread.core = Some(core);
+        self
+    }
+
+    /// Pins the simulator thread to the given core before `run` is called.
+    ///
+    /// See [`pin_controller_to`](Self::pin_controller_to) for platform support.
+    #[cfg(feature = "core_affinity")]
+    pub fn pin_simulator_to(mut self, core: usize) -> Self {
+        self.simulator_thread.core = Some(core);
+        self
+    }
+
+    /// Requests the given scheduling priority for the controller thread before
+    /// `run` is called.
+    ///
+    /// Elevated priorities usually need extra privileges (for example
+    /// `CAP_SYS_NICE` on Linux). When the request is refused the agent still
+    /// runs and a [`Warning::Priority`](crate::Warning::Priority) is reported on the [`EngineHandle`].
+    #[cfg(feature = "thread-priority")]
+    pub fn with_controller_priority(mut self, priority: ThreadPriority) -> Self {
+        self.controller_thread.priority = Some(priority);
+        self
+    }
+
+    /// Requests the given scheduling priority for the simulator thread before
+    /// `run` is called.
+    ///
+    /// See [`with_controller_priority`](Self::with_controller_priority).
+    #[cfg(feature = "thread-priority")]
+    pub fn with_simulator_priority(mut self, priority: ThreadPriority) -> Self {
+        self.simulator_thread.priority = Some(priority);
+        self
+    }
+
+    /// Cancels the engine's [cancellation token](Self::cancellation_token)
+    /// with [`ShutdownReason::Interrupt`] on Ctrl-C, so the agents return and
+    /// the [`with_before_join`](Self::with_before_join) and
+    /// [`with_shutdown_flush`](Self::with_shutdown_flush) hooks run as on any
+    /// other shutdown. Fails with [`Error::SignalHandler`] if the process
+    /// already has a handler.
+    #[cfg(feature = "ctrlc")]
+    pub fn install_ctrlc_handler(&self) -> Result<()> {
+        let token = self.cancellation.clone();
+
+        ctrlc::set_handler(move || token.cancel_with(ShutdownReason::Interrupt))
+            .map_err(|err| Error::SignalHandler(Box::new(err)))
+    }
+
     pub fn run(self) -> Result<()> {
+        self.spawn().join()
+    }
+
+    /// Runs the engine to completion and returns the controller's and the
+    /// simulator's results separately, see [`EngineHandle::join_detailed`].
+    pub fn run_detailed(self) -> (Result<()>, Result<()>) {
+        self.spawn().join_detailed()
+    }
+
+    /// Runs the engine for at most `dur` and reports what each agent returned.
+    ///
+    /// Once `dur` has elapsed the engine is cancelled and agents still running
+    /// are detached, while the results of agents that already finished are
+    /// kept in the returned [`AgentOutcomes`].
+    pub fn run_for(self, dur: Duration) -> AgentOutcomes {
+        let cancellation = self.cancellation.clone();
+        let outcomes = self.spawn().join_timeout_outcomes(dur);
+        if !outcomes.is_complete() {
+            cancellation.cancel();
+        }
+
+        outcomes
+    }
+
+    /// Runs the engine for `frames` frames as with
+    /// [`with_frame_limit`](Self::with_frame_limit), then writes the final
+    /// value of `state` to `path` as JSON. Meant for headless batch jobs.
+    #[cfg(feature = "serde")]
+    pub fn run_frames_then_snapshot<T>(
+        self,
+        frames: u64,
+        state: &Shared<T>,
+        path: impl AsRef<Path>,
+    ) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.with_frame_limit(frames).run()?;
+        let json = serde_json::to_vec_pretty(&**state.load()).map_err(io::Error::from)?;
+        fs::write(path, json)?;
+
+        Ok(())
+    }
+
+    /// Runs the engine until `is_converged` holds for `state`, checked
+    /// whenever an agent [advances the frame](AgentContext::advance_frame),
+    /// or until `max_frames` frames as with
+    /// [`with_frame_limit`](Self::with_frame_limit). Returns whether the
+    /// state converged.
+    pub fn run_until_converged<T>(
+        self,
+        state: &Shared<T>,
+        is_converged: impl Fn(&T) -> bool + Send + 'static,
+        max_frames: u64,
+    ) -> Result<bool>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let (observer, converged) =
+            ConvergenceObserver::new(state.clone(), is_converged, self.cancellation.clone());
+        self.with_observer(observer)
+            .with_frame_limit(max_frames)
+            .run()?;
+
+        Ok(converged.load(Ordering::Acquire))
+    }
+
+    /// Runs the engine like [`run`](Self::run) and returns, in order, every
+    /// frame boundary, the type of every message the agents sent, and
+    /// everything they reported through their [`AgentContext`]. Agents that
+    /// exchange messages in lock-step produce the same transcript every run.
+    pub fn run_with_transcript(self) -> (Result<()>, Transcript) {
+        let (observer, transcript) = Transcript::observer();
+        let result = self.with_observer(observer).run();
+        let transcript = mem::take(&mut *transcript.lock().unwrap_or_else(PoisonError::into_inner));
+
+        (result, transcript)
+    }
+
+    pub fn spawn(self) -> EngineHandle {
         let Self {
-            controller,
-            simulator,
+            mut controller,
+            mut simulator,
+            seed,
+            seed_log,
+            cancellation,
+            controller_heartbeat,
+            simulator_heartbeat,
+            heartbeat_timeout,
+            controller_thread,
+            simulator_thread,
+            shutdown_order,
+            clock,
+            observers,
+            log_sink,
+            startup_timeout,
+            before_join,
+            shutdown_flush,
+            shutdown_grace,
+            teardown,
+            startup,
+            slow_startup,
+            monitor,
+            start_barrier,
+            stop_barrier,
+            ordered_start,
+            frame_limit,
+            max_message_size,
+            message_types,
+            metrics,
         } = self;
 
-        let controller_handle = thread::spawn(move || controller.run());
-        let simulator_handle = thread::spawn(move || simulator.run());
+        if let Some(seed) = seed {
+            controller.seed(SeededRng::for_agent(seed, AgentId::CONTROLLER));
+            simulator.seed(SeededRng::for_agent(seed, AgentId::SIMULATOR));
+        }
+        let frame = Arc::new(AtomicU64::new(0));
+        if let Some(seed) = seed {
+            seed_log.begin(seed, Arc::clone(&frame));
+        }
+        let frame_hooks = FrameHooks::default();
+        let (ready, readiness) = crossbeam_channel::unbounded();
+        let barrier = |enabled: bool| enabled.then(|| Arc::new(Rendezvous::new(2)));
+        let (start, stop) = (barrier(start_barrier), barrier(stop_barrier));
+        let release = barrier(ordered_start);
+        let clock = SimClock::from_arc(clock);
+        startup.begin(clock.now());
+        let context = |agent| {
+            AgentContext::for_engine(
+                agent,
+                cancellation.child_token(),
+                Arc::clone(&frame),
+                Arc::new(clock.clone()),
+                Arc::clone(&observers),
+                Arc::clone(&frame_hooks),
+                ready.clone(),
+            )
+            .with_barriers(start.clone(), stop.clone())
+            .with_ordered_start(release.clone())
+            .with_startup_report(startup.clone(), slow_startup)
+            .with_warnings(monitor.sender())
+            .with_pause(clock.clone())
+            .with_metrics(metrics.clone())
+        };
+        let controller_context =
+            context(AgentId::CONTROLLER).with_heartbeat(controller_heartbeat.clone());
+        let simulator_context =
+            context(AgentId::SIMULATOR).with_heartbeat(simulator_heartbeat.clone());
+        controller_context.on_frame({
+            let (metrics, clock) = (metrics.clone(), clock.clone());
+            let mut last = clock.now();
+            move || {
+                let now = clock.now();
+                metrics.record_frame(now.saturating_duration_since(last));
+                last = now;
+                true
+            }
+        });
+        if let Some(limit) = frame_limit {
+            let token = cancellation.clone();
+            let reached = move |frame: u64| {
+                let reached = frame >= limit;
+                if reached {
+                    token.cancel();
+                }
+                reached
+            };
+            if !reached(0) {
+                let frame = Arc::clone(&frame);
+                controller_context.on_frame(move || !reached(frame.load(Ordering::Acquire)));
+            }
+        }
+        let controller_linked =
+            controller.link_cancellation(controller_context.cancellation_token());
+        let simulator_linked = simulator.link_cancellation(simulator_context.cancellation_token());
+
+        let errored = Arc::new(AtomicBool::new(false));
 
-        controller_handle.join().map_err(Error::Thread)??;
-        simulator_handle.join().map_err(Error::Thread)??;
+        let (controller_agent, controller_warnings) = AgentThread::spawn(
+            AgentId::CONTROLLER,
+            controller_heartbeat.clone(),
+            controller_thread.clone(),
+            Arc::clone(&errored),
+            cancellation.clone(),
+            {
+                let context = controller_context.clone();
+                move || {
+                    context.observe_sends();
+                    context.release_start_on_send();
+                    let result = controller_linked.and_then(|()| {
+                        controller
+                            .run_with_context(&context)
+                            .map_err(|err| Error::from_agent(AgentId::CONTROLLER, err))
+                    });
+                    context.release_start();
+                    result
+                }
+            },
+        );
+        let (simulator_agent, simulator_warnings) = AgentThread::spawn(
+            AgentId::SIMULATOR,
+            simulator_heartbeat.clone(),
+            simulator_thread.clone(),
+            Arc::clone(&errored),
+            cancellation.clone(),
+            {
+                let context = simulator_context.clone();
+                move || {
+                    context.observe_sends();
+                    simulator_linked?;
+                    simulator
+                        .run_with_context(&context)
+                        .map_err(|err| Error::from_agent(AgentId::SIMULATOR, err))
+                }
+            },
+        );
 
-        Ok(())
+        let mut warnings: Vec<Warning> = [controller_warnings, simulator_warnings]
+            .iter()
+            .filter_map(|setup| setup.recv().ok())
+            .flatten()
+            .collect();
+        if let Some(max) = max_message_size {
+            warnings.extend(message_types.iter().filter_map(|check| check(max)));
+        }
+
+        let startup_failure = startup_timeout.and_then(|timeout| {
+            await_startup(
+                &readiness,
+                timeout,
+                [
+                    (AgentId::CONTROLLER, &controller_agent),
+                    (AgentId::SIMULATOR, &simulator_agent),
+                ],
+            )
+        });
+        log_sink.log(LogLevel::Info, "engine started");
+        for warning in &warnings {
+            log_sink.log(LogLevel::Warn, &warning.to_string());
+        }
+        if let Some((agent, reason)) = startup_failure {
+            log_sink.log(LogLevel::Error, &format!("{agent} failed to start"));
+            cancellation.cancel_with(reason);
+        }
+
+        let handle = EngineHandle::new(
+            AgentSlot {
+                thread: controller_agent,
+                heartbeat: controller_heartbeat,
+                config: controller_thread,
+                context: controller_context,
+            },
+            AgentSlot {
+                thread: simulator_agent,
+                heartbeat: simulator_heartbeat,
+                config: simulator_thread,
+                context: simulator_context,
+            },
+            warnings,
+            heartbeat_timeout,
+            cancellation,
+            shutdown_order,
+            errored,
+        )
+        .with_before_join(before_join)
+        .with_shutdown_flush(shutdown_flush)
+        .with_shutdown_grace(shutdown_grace)
+        .with_teardown_report(teardown)
+        .with_clock(clock)
+        .with_log_sink(log_sink);
+
+        match startup_failure {
+            Some((agent, _)) => handle.with_startup_failure(agent),
+            None => handle,
+        }
+    }
+}
+
+impl<C> MultiAgentEngine<C, NoAgent>
+where
+    C: Controller + Send + 'static,
+{
+    /// An engine running only `controller`, with [`NoAgent`] as simulator.
+    pub fn controller_only(controller: C) -> Self {
+        Self::new(controller, NoAgent)
+    }
+}
+
+impl<S> MultiAgentEngine<NoAgent, S>
+where
+    S: Simulator + Send + 'static,
+{
+    /// An engine running only `simulator`, with [`NoAgent`] as controller.
+    pub fn simulator_only(simulator: S) -> Self {
+        Self::new(NoAgent, simulator)
+    }
+}
+
+/// Returns the first agent that exited or timed out before marking itself
+/// ready along with the matching shutdown reason, or `None` once both are
+/// ready.
+fn await_startup(
+    readiness: &Receiver<AgentId>,
+    timeout: Duration,
+    agents: [(AgentId, &AgentThread); 2],
+) -> Option<(AgentId, ShutdownReason)> {
+    let deadline = Instant::now() + timeout;
+    let mut pending = agents.to_vec();
+
+    loop {
+        // Agents mark themselves ready before exiting, so anything seen as
+        // finished here has already queued its ready signal if it sent one.
+        let finished: Vec<AgentId> = pending
+            .iter()
+            .filter(|(_, thread)| thread.is_finished())
+            .map(|(agent, _)| *agent)
+            .collect();
+        for agent in readiness.try_iter() {
+            pending.retain(|(id, _)| *id != agent);
+        }
+
+        let &(first, _) = pending.first()?;
+        if let Some(&(agent, _)) = pending.iter().find(|(id, _)| finished.contains(id)) {
+            return Some((agent, ShutdownReason::Error));
+        }
+        if Instant::now() >= deadline {
+            return Some((first, ShutdownReason::Timeout));
+        }
+
+        if let Ok(agent) = readiness.recv_timeout(STARTUP_POLL) {
+            pending.retain(|(id, _)| *id != agent);
+        }
+    }
+}
+
+impl<C, S> Controller for MultiAgentEngine<C, S>
+where
+    C: Controller + Send + 'static,
+    S: Simulator + Send + 'static,
+{
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        MultiAgentEngine::run(self)
+    }
+
+    fn seed(&mut self, mut rng: SeededRng) {
+        self.seed = Some(rng.next_u64());
+    }
+
+    fn link_cancellation(&mut self, parent: &CancellationToken) -> Result<()> {
+        self.cancellation.link_parent(parent)
+    }
+}
+
+impl<C, S> Simulator for MultiAgentEngine<C, S>
+where
+    C: Controller + Send + 'static,
+    S: Simulator + Send + 'static,
+{
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        MultiAgentEngine::run(self)
+    }
+
+    fn seed(&mut self, mut rng: SeededRng) {
+        self.seed = Some(rng.next_u64());
+    }
+
+    fn link_cancellation(&mut self, parent: &CancellationToken) -> Result<()> {
+        self.cancellation.link_parent(parent)
     }
 }
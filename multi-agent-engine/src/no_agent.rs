@@ -0,0 +1,56 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{AgentContext, Controller, Simulator};
+use multi_agent_engine_core::{Error, Result};
+
+/// Stands in for the missing agent of a
+/// [`controller_only`](crate::MultiAgentEngine::controller_only) or
+/// [`simulator_only`](crate::MultiAgentEngine::simulator_only) engine. It
+/// marks itself ready, passes the start and stop barriers and exits, holding
+/// no channel endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoAgent;
+
+impl Controller for NoAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        ctx.mark_ready();
+        let _ = ctx.wait_for_start();
+        let _ = ctx.wait_for_stop();
+        Ok(())
+    }
+}
+
+impl Simulator for NoAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        ctx.mark_ready();
+        let _ = ctx.wait_for_start();
+        let _ = ctx.wait_for_stop();
+        Ok(())
+    }
+}
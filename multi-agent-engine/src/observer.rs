@@ -0,0 +1,143 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::agent_context::Observers;
+use multi_agent_engine_core::AgentId;
+use std::{
+    any,
+    cell::RefCell,
+    fmt::{self, Debug, Formatter},
+    sync::PoisonError,
+    time::Duration,
+};
+
+thread_local! {
+    static SENDS: RefCell<Option<SendOrigin>> = const { RefCell::new(None) };
+}
+
+/// The agent sending from the current thread and the observers its sends
+/// are reported to, kept with a deferred message so it is reported for the
+/// agent that sent it rather than the one advancing the frame.
+#[derive(Clone)]
+pub(crate) struct SendOrigin {
+    agent: AgentId,
+    observers: Observers,
+}
+
+impl SendOrigin {
+    fn report<T>(&self, observers: &mut [Box<dyn Observer>]) {
+        let event = EngineEvent::Sent {
+            agent: self.agent,
+            message_type: any::type_name::<T>(),
+        };
+        for observer in observers {
+            observer.observe(event);
+        }
+    }
+
+    /// Runs `deliver`, which must not block, with the observers locked and
+    /// reports the message only once it was delivered, so no event of the
+    /// receiver gets in before it.
+    pub(crate) fn deliver<T, E>(&self, deliver: impl FnOnce() -> Result<(), E>) -> Result<(), E> {
+        let mut observers = self
+            .observers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        deliver()?;
+        self.report::<T>(&mut observers);
+        Ok(())
+    }
+}
+
+impl Debug for SendOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendOrigin")
+            .field("agent", &self.agent)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum EngineEvent<'a> {
+    Frame {
+        frame: u64,
+    },
+    Message {
+        agent: AgentId,
+        message: &'a dyn Debug,
+    },
+    State {
+        agent: AgentId,
+        state: &'a dyn Debug,
+    },
+    /// Reported by [`FixedTimestep`](crate::FixedTimestep) when a frame took
+    /// longer than its interval.
+    DeadlineMissed {
+        frame: u64,
+        overrun: Duration,
+    },
+    /// Reported by [`FrameWatchdog`](crate::FrameWatchdog) when a frame ran
+    /// over its budget; the time not spent in `receive` went to compute.
+    SlowFrame {
+        frame: u64,
+        elapsed: Duration,
+        receive: Duration,
+    },
+    /// Reported when an agent reached its first frame later than the
+    /// engine's [slow startup threshold](crate::MultiAgentEngine::with_slow_startup_threshold).
+    SlowStartup {
+        agent: AgentId,
+        latency: Duration,
+    },
+    /// Reported whenever an agent thread of the engine starts sending a
+    /// message, named by its type, even if the send then fails. A message
+    /// deferred to the next frame is reported once, for the agent that sent
+    /// it, at the frame it is delivered. Contents are only known when
+    /// reported as [`Message`](Self::Message).
+    Sent {
+        agent: AgentId,
+        message_type: &'static str,
+    },
+}
+
+pub trait Observer: Send {
+    fn observe(&mut self, event: EngineEvent<'_>);
+}
+
+/// Reports every message sent from the current thread to `observers` as
+/// [`EngineEvent::Sent`].
+pub(crate) fn observe_sends(agent: AgentId, observers: Observers) {
+    SENDS.set(Some(SendOrigin { agent, observers }));
+}
+
+/// The origin sends from the current thread are reported for, if any.
+pub(crate) fn send_origin() -> Option<SendOrigin> {
+    SENDS.with_borrow(Clone::clone)
+}
+
+pub(crate) fn message_sent<T>() {
+    SENDS.with_borrow(|sends| {
+        if let Some(origin) = sends {
+            origin.report::<T>(
+                &mut origin
+                    .observers
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            );
+        }
+    });
+}
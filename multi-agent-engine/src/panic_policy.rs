@@ -0,0 +1,35 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// What the engine does when one of its agents panics.
+///
+/// In both cases the panic is reported as [`Error::AgentPanic`] when the
+/// engine is joined. There is no restarting policy, since `run` consumes the
+/// agent and the engine has nothing to rebuild it from. To bring a panicked
+/// simulator back, isolate it and call [`EngineHandle::restart_simulator`]
+/// with a factory.
+///
+/// [`Error::AgentPanic`]: crate::Error::AgentPanic
+/// [`EngineHandle::restart_simulator`]: crate::EngineHandle::restart_simulator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PanicPolicy {
+    /// Cancels the engine token so every agent shuts down.
+    #[default]
+    Abort,
+    /// Stops only the panicking agent and lets its peers run to completion.
+    Isolate,
+}
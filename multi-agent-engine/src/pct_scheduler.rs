@@ -0,0 +1,225 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{SchedulePoint, SchedulerHook, SeededRng};
+use multi_agent_engine_core::AgentId;
+use std::{
+    collections::BTreeMap,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// A seeded [`SchedulerHook`] after probabilistic concurrency testing (PCT).
+///
+/// Only one agent runs between schedule points. Each agent gets a random
+/// priority from the seed and the highest-priority agent that can progress
+/// always runs. At a few random steps within the first
+/// [`with_horizon`](Self::with_horizon) steps, the running agent drops to
+/// the lowest priority, which forces the rare interleavings where ordering
+/// bugs hide. The same seed produces the same interleaving on every run.
+#[derive(Debug)]
+pub struct PctScheduler {
+    seed: u64,
+    state: Mutex<State>,
+    turn: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    rng: SeededRng,
+    agents: BTreeMap<AgentId, Agent>,
+    current: Option<AgentId>,
+    step: u64,
+    horizon: u64,
+    change_points: Vec<u64>,
+    lowest: i64,
+    trace: Vec<(AgentId, SchedulePoint)>,
+}
+
+#[derive(Debug)]
+struct Agent {
+    priority: i64,
+    status: Status,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Runnable,
+    Idle,
+    Done,
+}
+
+impl PctScheduler {
+    const DEFAULT_HORIZON: u64 = 100;
+
+    pub fn new(seed: u64) -> Self {
+        let mut state = State {
+            rng: SeededRng::new(seed),
+            agents: BTreeMap::new(),
+            current: None,
+            step: 0,
+            horizon: Self::DEFAULT_HORIZON,
+            change_points: Vec::new(),
+            lowest: 0,
+            trace: Vec::new(),
+        };
+        state.sample_change_points(1);
+
+        Self {
+            seed,
+            state: Mutex::new(state),
+            turn: Condvar::new(),
+        }
+    }
+
+    /// Number of priority change points, the bug depth PCT targets. Defaults
+    /// to one.
+    pub fn with_change_points(self, count: usize) -> Self {
+        self.lock().sample_change_points(count);
+        self
+    }
+
+    /// Steps within which change points are placed, ideally close to the
+    /// number of schedule points in one run.
+    pub fn with_horizon(self, steps: u64) -> Self {
+        {
+            let mut state = self.lock();
+            let count = state.change_points.len();
+            state.horizon = steps.max(1);
+            state.rng = SeededRng::new(self.seed);
+            state.sample_change_points(count);
+        }
+        self
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Every granted schedule point so far, in execution order.
+    pub fn trace(&self) -> Vec<(AgentId, SchedulePoint)> {
+        self.lock().trace.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SchedulerHook for PctScheduler {
+    fn register(&self, agent: AgentId) {
+        let mut state = self.lock();
+        let priority = (state.rng.next_u64() >> 2) as i64;
+
+        state.agents.insert(
+            agent,
+            Agent {
+                priority,
+                status: Status::Runnable,
+            },
+        );
+    }
+
+    fn yield_point(&self, agent: AgentId, point: SchedulePoint) {
+        let mut state = self.lock();
+        if state.current == Some(agent) {
+            state.current = None;
+        }
+        if state.agent(agent).status == Status::Done {
+            state.agent(agent).status = Status::Runnable;
+        }
+
+        loop {
+            if state.current.is_none() {
+                state.schedule();
+                self.turn.notify_all();
+            }
+            while state.current != Some(agent) {
+                state = self
+                    .turn
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+
+            state.step += 1;
+            if !state.change_points.contains(&state.step) {
+                break;
+            }
+            state.lowest -= 1;
+            let lowest = state.lowest;
+            state.agent(agent).priority = lowest;
+            state.current = None;
+        }
+
+        state.agent(agent).status = Status::Runnable;
+        state.trace.push((agent, point));
+    }
+
+    fn idle(&self, agent: AgentId) {
+        self.lock().agent(agent).status = Status::Idle;
+    }
+
+    fn message_sent(&self, _agent: AgentId) {
+        self.lock().wake_idle();
+    }
+
+    fn finish(&self, agent: AgentId) {
+        let mut state = self.lock();
+        state.agent(agent).status = Status::Done;
+        state.wake_idle();
+
+        if state.current == Some(agent) {
+            state.current = None;
+            state.schedule();
+            self.turn.notify_all();
+        }
+    }
+}
+
+impl State {
+    fn sample_change_points(&mut self, count: usize) {
+        let horizon = self.horizon;
+        self.change_points = (0..count).map(|_| 1 + self.rng.below(horizon)).collect();
+    }
+
+    /// Picks the highest-priority runnable agent, falling back to an idle
+    /// one so agents polling for something other than a message still run.
+    fn schedule(&mut self) {
+        let pick = |status| {
+            self.agents
+                .iter()
+                .filter(|(_, agent)| agent.status == status)
+                .max_by_key(|(_, agent)| agent.priority)
+                .map(|(id, _)| *id)
+        };
+
+        self.current = pick(Status::Runnable).or_else(|| pick(Status::Idle));
+    }
+
+    fn wake_idle(&mut self) {
+        for agent in self.agents.values_mut() {
+            if agent.status == Status::Idle {
+                agent.status = Status::Runnable;
+            }
+        }
+    }
+
+    fn agent(&mut self, agent: AgentId) -> &mut Agent {
+        self.agents
+            .get_mut(&agent)
+            .expect("agents are registered before they run")
+    }
+}
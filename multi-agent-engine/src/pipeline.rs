@@ -0,0 +1,146 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    CancellationToken,
+    message::{Queue, Receiver, Sender},
+};
+use multi_agent_engine_core::{Error, Result};
+use std::thread::{self, JoinHandle};
+
+type Source<T> = Box<dyn FnOnce(&Sender<T>) -> Result<()> + Send>;
+type Stages<In, Out> = Box<dyn FnOnce(Receiver<In>, &mut Threads) -> Receiver<Out> + Send>;
+
+/// A chain of stages, each running on its own thread and connected to the
+/// next by a bounded queue of `capacity` messages. A stage blocks on sending
+/// while the queue after it is full, so a slow stage rate-limits every stage
+/// upstream of it, all the way back to the source. The source produces `In`
+/// messages and every stage may change their type, so that the sink receives
+/// `Out` messages.
+///
+/// The chain shuts down front to back: once the source returns, each stage
+/// drains what is queued and then finishes. Cancelling the pipeline's
+/// [token](Self::with_cancellation_token) stops the stages and the sink
+/// without draining, and a source still sending then sees its queue
+/// disconnected.
+pub struct Pipeline<In, Out = In> {
+    capacity: usize,
+    cancellation: CancellationToken,
+    source: Source<In>,
+    stages: Stages<In, Out>,
+}
+
+/// What the stages are spawned with.
+struct Threads {
+    capacity: usize,
+    cancellation: CancellationToken,
+    handles: Vec<JoinHandle<Result<()>>>,
+}
+
+impl<In: Send + 'static> Pipeline<In> {
+    pub fn new(
+        capacity: usize,
+        source: impl FnOnce(&Sender<In>) -> Result<()> + Send + 'static,
+    ) -> Self {
+        Self {
+            capacity,
+            cancellation: CancellationToken::new(),
+            source: Box::new(source),
+            stages: Box::new(|input, _| input),
+        }
+    }
+}
+
+impl<In: Send + 'static, Out: Send + 'static> Pipeline<In, Out> {
+    /// Stops the pipeline once `token` is cancelled, for example with the
+    /// [engine's token](crate::MultiAgentEngine::cancellation_token).
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Appends a stage transforming every message on its way downstream.
+    pub fn with_stage<U: Send + 'static>(
+        self,
+        mut stage: impl FnMut(Out) -> U + Send + 'static,
+    ) -> Pipeline<In, U> {
+        let stages = self.stages;
+        Pipeline {
+            capacity: self.capacity,
+            cancellation: self.cancellation,
+            source: self.source,
+            stages: Box::new(move |input, threads| {
+                let input = stages(input, threads);
+                let (sender, receiver) = Queue::bounded_channel(threads.capacity);
+                threads.spawn(input, move |msg| sender.send(stage(msg)));
+                receiver
+            }),
+        }
+    }
+
+    /// Runs the source, the stages and `sink` to completion. Errors of all
+    /// stages are collected, a stage that panicked reports [`Error::Thread`].
+    /// Once cancelled, the disconnections that stopping causes are not
+    /// reported.
+    pub fn run(self, mut sink: impl FnMut(Out) + Send + 'static) -> Result<()> {
+        let (sender, input) = Queue::bounded_channel(self.capacity);
+        let source = self.source;
+        let mut threads = Threads {
+            capacity: self.capacity,
+            cancellation: self.cancellation.clone(),
+            handles: vec![thread::spawn(move || source(&sender))],
+        };
+
+        let output = (self.stages)(input, &mut threads);
+        threads.spawn(output, move |msg| {
+            sink(msg);
+            Ok(())
+        });
+
+        let mut errors: Vec<Error> = threads
+            .handles
+            .into_iter()
+            .filter_map(|handle| handle.join().map_err(Error::Thread).and_then(|r| r).err())
+            .filter(|err| {
+                !(self.cancellation.is_cancelled() && matches!(err, Error::Disconnected { .. }))
+            })
+            .collect();
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+}
+
+impl Threads {
+    /// Spawns a thread handing every message of `input` to `handle` until the
+    /// queue is drained and disconnected or the pipeline is cancelled.
+    fn spawn<T: Send + 'static>(
+        &mut self,
+        input: Receiver<T>,
+        mut handle: impl FnMut(T) -> Result<()> + Send + 'static,
+    ) {
+        let cancellation = self.cancellation.clone();
+        self.handles.push(thread::spawn(move || {
+            while let Ok(Some(msg)) = input.recv_cancellable(&cancellation) {
+                handle(msg)?;
+            }
+            Ok(())
+        }));
+    }
+}
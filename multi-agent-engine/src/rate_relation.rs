@@ -0,0 +1,147 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::AgentContext;
+use multi_agent_engine_core::AgentId;
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+/// How often a waiting agent checks its cancellation token.
+const CANCEL_POLL: Duration = Duration::from_millis(1);
+
+/// Runs one agent exactly once per `ratio` frames of the other.
+///
+/// Both agents call [`tick`](Self::tick) at the start of every frame. The
+/// slow agent's tick blocks until the fast one has started another `ratio`
+/// frames, and the fast one's blocks until the slow one has caught up with
+/// the frames it was granted, so the two stay in lock-step. Once the fast
+/// agent is done it calls [`close`](Self::close); the slow agent still runs
+/// the frames it was granted, then its ticks break.
+#[derive(Debug)]
+pub struct RateRelation {
+    fast: AgentId,
+    ratio: u32,
+    progress: Arc<(Mutex<Progress>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    fast: u64,
+    slow: u64,
+    closed: bool,
+}
+
+impl RateRelation {
+    /// The simulator runs once per `ratio` controller frames.
+    pub fn simulator_every(ratio: u32) -> Self {
+        Self::new(AgentId::CONTROLLER, ratio)
+    }
+
+    /// The controller runs once per `ratio` simulator frames.
+    pub fn controller_every(ratio: u32) -> Self {
+        Self::new(AgentId::SIMULATOR, ratio)
+    }
+
+    fn new(fast: AgentId, ratio: u32) -> Self {
+        Self {
+            fast,
+            ratio: ratio.max(1),
+            progress: Arc::default(),
+        }
+    }
+
+    /// The frame interval of `agent` when the fast agent runs every `base`.
+    pub fn interval(&self, agent: AgentId, base: Duration) -> Duration {
+        if agent == self.fast {
+            base
+        } else {
+            base.saturating_mul(self.ratio)
+        }
+    }
+
+    /// Frames `agent` has started so far.
+    pub fn frames(&self, agent: AgentId) -> u64 {
+        let progress = self.lock();
+
+        if agent == self.fast {
+            progress.fast
+        } else {
+            progress.slow
+        }
+    }
+
+    /// Waits until the agent of `ctx` may start its next frame. Breaks once
+    /// the engine is cancelled, or the relation is closed and the agent has
+    /// no frame left to run.
+    pub fn tick(&self, ctx: &AgentContext) -> ControlFlow<()> {
+        let fast = ctx.agent() == self.fast;
+        let mut progress = self.lock();
+
+        loop {
+            let granted = progress.fast / u64::from(self.ratio);
+
+            if fast && progress.closed {
+                return ControlFlow::Break(());
+            }
+            if fast == (progress.slow >= granted) {
+                break;
+            }
+            if progress.closed || ctx.is_cancelled() {
+                return ControlFlow::Break(());
+            }
+            progress = self
+                .progress
+                .1
+                .wait_timeout(progress, CANCEL_POLL)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        if fast {
+            progress.fast += 1;
+        } else {
+            progress.slow += 1;
+        }
+        self.progress.1.notify_all();
+        ControlFlow::Continue(())
+    }
+
+    /// Ends the relation; called by the fast agent once it stops.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.progress.1.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Progress> {
+        self.progress
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for RateRelation {
+    fn clone(&self) -> Self {
+        Self {
+            fast: self.fast,
+            ratio: self.ratio,
+            progress: Arc::clone(&self.progress),
+        }
+    }
+}
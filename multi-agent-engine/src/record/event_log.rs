@@ -0,0 +1,194 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::{Error, Result};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fmt::{self, Debug, Formatter},
+    fs::{File, OpenOptions},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+const LEN_PREFIX: usize = size_of::<u32>();
+
+type Migration<T> = Arc<dyn Fn(u8, serde_json::Value) -> Result<T> + Send + Sync>;
+
+/// An append-only file of serde-encoded messages.
+///
+/// Each record is a little-endian `u32` length followed by that many bytes:
+/// a schema version byte, then the message as JSON. A record cut short by a
+/// crash is dropped on [`open`](Self::open), so appends resume right after the
+/// last complete record.
+///
+/// Records written under another schema version fail to replay with
+/// [`Error::SchemaMismatch`] unless a migration is installed with
+/// [`with_migration`](Self::with_migration).
+pub struct EventLog<T> {
+    path: PathBuf,
+    file: Mutex<File>,
+    version: u8,
+    migration: Option<Migration<T>>,
+}
+
+impl<T> EventLog<T> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        let complete = complete_len(&mut file)?;
+        if complete < file.metadata()?.len() {
+            file.set_len(complete)?;
+        }
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            version: 1,
+            migration: None,
+        })
+    }
+
+    pub fn with_schema_version(mut self, version: u8) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn with_migration<F>(mut self, migrate: F) -> Self
+    where
+        F: Fn(u8, serde_json::Value) -> Result<T> + Send + Sync + 'static,
+    {
+        self.migration = Some(Arc::new(migrate));
+        self
+    }
+
+    #[inline]
+    pub fn schema_version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn append(&self, msg: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let payload = serde_json::to_vec(msg).map_err(io::Error::from)?;
+        let len = u32::try_from(payload.len()).map_err(io::Error::other)?;
+
+        let len = len
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("record too large"))?;
+
+        let mut record = Vec::with_capacity(LEN_PREFIX + 1 + payload.len());
+        record.extend_from_slice(&len.to_le_bytes());
+        record.push(self.version);
+        record.extend_from_slice(&payload);
+
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.write_all(&record)?;
+        Ok(())
+    }
+
+    pub fn sync(&self) -> Result<()> {
+        let file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(file.sync_data()?)
+    }
+
+    pub fn replay(&self) -> Result<impl Iterator<Item = Result<T>> + use<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let expected = self.version;
+        let migration = self.migration.clone();
+
+        Ok(std::iter::from_fn(move || {
+            let payload = read_record(&mut reader).transpose()?;
+            Some(payload.and_then(|payload| decode(&payload, expected, migration.as_ref())))
+        }))
+    }
+}
+
+impl<T> Debug for EventLog<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLog")
+            .field("path", &self.path)
+            .field("file", &self.file)
+            .field("version", &self.version)
+            .field("migration", &self.migration.is_some())
+            .finish()
+    }
+}
+
+fn decode<T>(payload: &[u8], expected: u8, migration: Option<&Migration<T>>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let Some((&found, json)) = payload.split_first() else {
+        return Err(
+            io::Error::new(io::ErrorKind::InvalidData, "record without schema version").into(),
+        );
+    };
+
+    if found == expected {
+        return serde_json::from_slice(json).map_err(|err| io::Error::from(err).into());
+    }
+
+    match migration {
+        Some(migrate) => {
+            let value = serde_json::from_slice(json).map_err(io::Error::from)?;
+            migrate(found, value)
+        }
+        None => Err(Error::SchemaMismatch { found, expected }),
+    }
+}
+
+fn read_record(reader: &mut impl Read) -> Result<Option<Vec<u8>>> {
+    let mut len = [0; LEN_PREFIX];
+    if !read_full(reader, &mut len)? {
+        return Ok(None);
+    }
+
+    let len = u32::from_le_bytes(len) as usize;
+    let mut payload = Vec::new();
+    reader.take(len as u64).read_to_end(&mut payload)?;
+
+    Ok((payload.len() == len).then_some(payload))
+}
+
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn complete_len(file: &mut File) -> Result<u64> {
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut reader = BufReader::new(&*file);
+    let mut complete = 0;
+    while let Some(payload) = read_record(&mut reader)? {
+        complete += (LEN_PREFIX + payload.len()) as u64;
+    }
+
+    Ok(complete)
+}
@@ -0,0 +1,81 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Clock, SystemClock};
+use multi_agent_engine_core::{AgentId, Result};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex, PoisonError},
+    time::Instant,
+};
+
+#[derive(Serialize)]
+struct Line<'a, T> {
+    timestamp_ns: u64,
+    agent: String,
+    message: &'a T,
+}
+
+#[derive(Debug)]
+pub struct JsonLinesRecorder<W> {
+    writer: Mutex<W>,
+    start: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl<W: Write> JsonLinesRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            writer: Mutex::new(writer),
+            start: clock.now(),
+            clock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.start = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn record<T: Serialize>(&self, agent: AgentId, message: &T) -> Result<()> {
+        let at = self.clock.now().saturating_duration_since(self.start);
+        let line = Line {
+            timestamp_ns: at.as_nanos() as u64,
+            agent: agent.to_string(),
+            message,
+        };
+
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        serde_json::to_writer(&mut *writer, &line).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
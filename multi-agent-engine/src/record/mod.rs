@@ -0,0 +1,39 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(feature = "serde")]
+mod event_log;
+#[cfg(feature = "serde")]
+mod json_lines_recorder;
+mod recorded;
+mod recorder;
+mod recording_sender;
+mod replay_sender;
+mod session;
+mod transcript;
+mod transcript_entry;
+
+#[cfg(feature = "serde")]
+pub use event_log::EventLog;
+#[cfg(feature = "serde")]
+pub use json_lines_recorder::JsonLinesRecorder;
+pub use recorded::Recorded;
+pub use recorder::Recorder;
+pub use recording_sender::RecordingSender;
+pub use replay_sender::ReplaySender;
+pub use session::Session;
+pub use transcript::Transcript;
+pub use transcript_entry::TranscriptEntry;
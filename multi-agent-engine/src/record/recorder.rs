@@ -0,0 +1,98 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Recorded, RecordingSender};
+use crate::{Clock, SystemClock, message::Sender};
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
+
+#[derive(Debug)]
+pub struct Recorder<T> {
+    entries: Arc<Mutex<Vec<Recorded<T>>>>,
+    start: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl<T> Recorder<T> {
+    pub fn new() -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            entries: Arc::new(Mutex::new(Vec::new())),
+            start: clock.now(),
+            clock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.start = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn record(&self, message: T) {
+        let at = self.clock.now().saturating_duration_since(self.start);
+        self.lock().push(Recorded { at, message });
+    }
+
+    pub fn sender(&self, sender: Sender<T>) -> RecordingSender<T>
+    where
+        T: Clone,
+    {
+        RecordingSender::new(sender, self.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn entries(&self) -> Vec<Recorded<T>>
+    where
+        T: Clone,
+    {
+        self.lock().clone()
+    }
+
+    pub fn take(&self) -> Vec<Recorded<T>> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Recorded<T>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for Recorder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: Arc::clone(&self.entries),
+            start: self.start,
+            clock: Arc::clone(&self.clock),
+        }
+    }
+}
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
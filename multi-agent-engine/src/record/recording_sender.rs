@@ -0,0 +1,44 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Recorder;
+use crate::message::Sender;
+use multi_agent_engine_core::Result;
+
+#[derive(Debug, Clone)]
+pub struct RecordingSender<T> {
+    sender: Sender<T>,
+    recorder: Recorder<T>,
+}
+
+impl<T> RecordingSender<T>
+where
+    T: Clone,
+{
+    pub(super) fn new(sender: Sender<T>, recorder: Recorder<T>) -> Self {
+        Self { sender, recorder }
+    }
+
+    pub fn send(&self, msg: T) -> Result<()> {
+        self.sender.send(msg.clone())?;
+        self.recorder.record(msg);
+        Ok(())
+    }
+
+    pub fn recorder(&self) -> &Recorder<T> {
+        &self.recorder
+    }
+}
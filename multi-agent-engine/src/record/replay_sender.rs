@@ -0,0 +1,119 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Recorded;
+use crate::{Clock, SystemClock, message::Sender};
+use multi_agent_engine_core::Result;
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+#[derive(Debug)]
+pub struct ReplaySender<T> {
+    pending: VecDeque<Recorded<T>>,
+    sender: Sender<T>,
+    clock: Arc<dyn Clock>,
+    speed: f64,
+    last_at: Duration,
+}
+
+impl<T> ReplaySender<T> {
+    pub fn new(recorded: impl IntoIterator<Item = Recorded<T>>, sender: Sender<T>) -> Self {
+        Self {
+            pending: recorded.into_iter().collect(),
+            sender,
+            clock: Arc::new(SystemClock),
+            speed: 1.0,
+            last_at: Duration::ZERO,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Replays only the messages matching `keep`, discarding the rest, to
+    /// isolate a single message stream. Kept messages retain their recorded
+    /// timestamps.
+    pub fn with_filter(mut self, mut keep: impl FnMut(&T) -> bool) -> Self {
+        self.pending.retain(|recorded| keep(&recorded.message));
+        self
+    }
+
+    /// Scales the recorded gaps between messages for timed replay: `1.0` is
+    /// realtime, `2.0` twice as fast and `0.5` half speed.
+    pub fn set_speed(&mut self, speed: f64) {
+        assert!(
+            speed.is_finite() && speed > 0.0,
+            "speed must be positive and finite"
+        );
+        self.speed = speed;
+    }
+
+    #[inline]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Waits out the scaled gap to the next recorded message, then sends it.
+    /// Returns `false` once nothing is left to replay.
+    pub fn replay_next(&mut self) -> Result<bool> {
+        let Some(next) = self.pending.front() else {
+            return Ok(false);
+        };
+
+        let gap = next.at.saturating_sub(self.last_at);
+        self.clock.sleep(gap.div_f64(self.speed));
+        self.step_one()
+    }
+
+    /// Replays every remaining message while honoring the recorded timing.
+    pub fn play(&mut self) -> Result<usize> {
+        let mut replayed = 0;
+
+        while self.replay_next()? {
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+
+    /// Sends the next recorded message immediately, for frame-by-frame replay.
+    /// Returns `false` once nothing is left to replay.
+    pub fn step_one(&mut self) -> Result<bool> {
+        let Some(recorded) = self.pending.pop_front() else {
+            return Ok(false);
+        };
+
+        self.last_at = recorded.at;
+        self.sender.send(recorded.message)?;
+        Ok(true)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn replay_all(&mut self) -> Result<usize> {
+        let mut replayed = 0;
+
+        while let Some(recorded) = self.pending.pop_front() {
+            self.sender.send(recorded.message)?;
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Recorded, ReplaySender};
+use crate::message::Sender;
+use multi_agent_engine_core::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session<M, S> {
+    messages: Vec<Recorded<M>>,
+    final_state: S,
+}
+
+impl<M, S> Session<M, S> {
+    pub fn new(messages: Vec<Recorded<M>>, final_state: S) -> Self {
+        Self {
+            messages,
+            final_state,
+        }
+    }
+
+    pub fn messages(&self) -> &[Recorded<M>] {
+        &self.messages
+    }
+
+    pub fn final_state(&self) -> &S {
+        &self.final_state
+    }
+
+    pub fn replay(&self, sender: Sender<M>) -> Result<usize>
+    where
+        M: Clone,
+    {
+        ReplaySender::new(self.messages.iter().cloned(), sender).replay_all()
+    }
+
+    pub fn matches(&self, state: &S) -> bool
+    where
+        S: PartialEq,
+    {
+        self.final_state == *state
+    }
+}
@@ -0,0 +1,146 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::TranscriptEntry;
+use crate::{EngineEvent, Observer};
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The ordered events of one engine run: every message and state reported
+/// through [`AgentContext::emit`](crate::AgentContext::emit), the type of every
+/// message sent, and every frame boundary from
+/// [`AgentContext::advance_frame`](crate::AgentContext::advance_frame) or a
+/// [`FrameBatch`](crate::FrameBatch).
+///
+/// Its [`Display`] form has one line per entry and is meant to be compared
+/// against a golden file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transcript {
+    entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    #[inline]
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn observer() -> (TranscriptObserver, Arc<Mutex<Transcript>>) {
+        let transcript = Arc::new(Mutex::new(Self::default()));
+
+        (
+            TranscriptObserver {
+                transcript: Arc::clone(&transcript),
+            },
+            transcript,
+        )
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct TranscriptObserver {
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl Observer for TranscriptObserver {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        let entry = match event {
+            EngineEvent::Frame { frame } => TranscriptEntry::Frame { frame },
+            EngineEvent::Message { agent, message } => TranscriptEntry::Message {
+                agent: agent.to_string(),
+                message: format!("{message:?}"),
+            },
+            EngineEvent::State { agent, state } => TranscriptEntry::State {
+                agent: agent.to_string(),
+                state: format!("{state:?}"),
+            },
+            EngineEvent::DeadlineMissed { frame, overrun } => {
+                TranscriptEntry::DeadlineMissed { frame, overrun }
+            }
+            EngineEvent::SlowFrame {
+                frame,
+                elapsed,
+                receive,
+            } => TranscriptEntry::SlowFrame {
+                frame,
+                elapsed,
+                receive,
+            },
+            EngineEvent::SlowStartup { agent, latency } => TranscriptEntry::SlowStartup {
+                agent: agent.to_string(),
+                latency,
+            },
+            EngineEvent::Sent {
+                agent,
+                message_type,
+            } => TranscriptEntry::Sent {
+                agent: agent.to_string(),
+                message_type: short_type_name(message_type),
+            },
+        };
+
+        self.transcript
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .push(entry);
+    }
+}
+
+/// Drops the module paths from a type name, so that golden transcripts do
+/// not change when a type moves: `alloc::vec::Vec<app::Move>` is `Vec<Move>`.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut path = 0;
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            short.truncate(path);
+        } else {
+            short.push(c);
+            if !(c.is_alphanumeric() || c == '_') {
+                path = short.len();
+            }
+        }
+    }
+    short
+}
@@ -0,0 +1,85 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum TranscriptEntry {
+    Frame {
+        frame: u64,
+    },
+    Message {
+        agent: String,
+        message: String,
+    },
+    State {
+        agent: String,
+        state: String,
+    },
+    DeadlineMissed {
+        frame: u64,
+        overrun: Duration,
+    },
+    SlowFrame {
+        frame: u64,
+        elapsed: Duration,
+        receive: Duration,
+    },
+    SlowStartup {
+        agent: String,
+        latency: Duration,
+    },
+    Sent {
+        agent: String,
+        message_type: String,
+    },
+}
+
+impl Display for TranscriptEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Frame { frame } => write!(f, "-- frame {frame}"),
+            Self::Message { agent, message } => write!(f, "{agent}: {message}"),
+            Self::State { agent, state } => write!(f, "{agent} state: {state}"),
+            Self::DeadlineMissed { frame, overrun } => {
+                write!(f, "-- frame {frame} missed deadline by {overrun:?}")
+            }
+            Self::SlowFrame {
+                frame,
+                elapsed,
+                receive,
+            } => write!(
+                f,
+                "-- frame {frame} took {elapsed:?} ({receive:?} receiving)"
+            ),
+            Self::SlowStartup { agent, latency } => {
+                write!(f, "-- {agent} took {latency:?} to start")
+            }
+            Self::Sent {
+                agent,
+                message_type,
+            } => write!(f, "{agent} sent {message_type}"),
+        }
+    }
+}
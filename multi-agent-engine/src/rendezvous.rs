@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::CancellationToken;
+use std::{
+    cell::RefCell,
+    ops::ControlFlow,
+    sync::{Condvar, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// How often a waiting agent checks its cancellation token.
+const CANCEL_POLL: Duration = Duration::from_millis(1);
+
+thread_local! {
+    static RELEASE_ON_SEND: RefCell<Option<Box<dyn FnOnce()>>> = const { RefCell::new(None) };
+}
+
+/// Runs `release` after the first message sent from the current thread.
+pub(crate) fn release_on_send(release: impl FnOnce() + 'static) {
+    RELEASE_ON_SEND.set(Some(Box::new(release)));
+}
+
+pub(crate) fn message_sent() {
+    if let Some(release) = RELEASE_ON_SEND.take() {
+        release();
+    }
+}
+
+/// A barrier that opens once every party arrived and then stays open, so an
+/// agent restarted later passes straight through instead of waiting for a
+/// partner that already moved on.
+#[derive(Debug)]
+pub(crate) struct Rendezvous {
+    parties: usize,
+    arrived: Mutex<usize>,
+    opened: Condvar,
+}
+
+impl Rendezvous {
+    pub(crate) fn new(parties: usize) -> Self {
+        Self {
+            parties,
+            arrived: Mutex::new(0),
+            opened: Condvar::new(),
+        }
+    }
+
+    /// Counts one party as arrived without waiting for the others. Arrivals
+    /// past the last party are ignored, the rendezvous is open by then.
+    pub(crate) fn arrive(&self) {
+        let mut arrived = self.arrived.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.count(&mut arrived) {
+            self.opened.notify_all();
+        }
+    }
+
+    /// Blocks until every party arrived, or breaks once `token` is cancelled.
+    pub(crate) fn wait(&self, token: &CancellationToken) -> ControlFlow<()> {
+        let mut arrived = self.arrived.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.count(&mut arrived) {
+            self.opened.notify_all();
+            return ControlFlow::Continue(());
+        }
+
+        while *arrived < self.parties {
+            if token.is_cancelled() {
+                return ControlFlow::Break(());
+            }
+            arrived = self
+                .opened
+                .wait_timeout(arrived, CANCEL_POLL)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Counts an arrival and returns whether every party arrived.
+    fn count(&self, arrived: &mut usize) -> bool {
+        *arrived = (*arrived + 1).min(self.parties);
+        *arrived == self.parties
+    }
+}
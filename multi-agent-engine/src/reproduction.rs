@@ -0,0 +1,38 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+/// What it takes to replay a seeded run: the engine seed, the seeds derived
+/// from it for each agent, and the last frame the run reached. Its
+/// [`Display`] form is a one-line hint meant for failing test output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reproduction {
+    pub seed: u64,
+    pub controller_seed: u64,
+    pub simulator_seed: u64,
+    pub frames: u64,
+}
+
+impl Display for Reproduction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reproduce with seed {}, frames {} (controller seed {}, simulator seed {})",
+            self.seed, self.frames, self.controller_seed, self.simulator_seed
+        )
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{EngineEvent, Observer};
+use rerun::{RecordingStream, RecordingStreamResult, TextLog};
+
+/// Forwards engine events to a rerun viewer.
+///
+/// Logging is best-effort telemetry: failures to reach the viewer are ignored
+/// so that visualization never interrupts the simulation.
+#[derive(Debug, Clone)]
+pub struct RerunSink {
+    stream: RecordingStream,
+}
+
+impl RerunSink {
+    pub fn new(stream: RecordingStream) -> Self {
+        Self { stream }
+    }
+
+    pub fn spawn(application_id: impl Into<rerun::ApplicationId>) -> RecordingStreamResult<Self> {
+        rerun::RecordingStreamBuilder::new(application_id)
+            .spawn()
+            .map(Self::new)
+    }
+
+    pub fn buffered(
+        application_id: impl Into<rerun::ApplicationId>,
+    ) -> RecordingStreamResult<Self> {
+        rerun::RecordingStreamBuilder::new(application_id)
+            .buffered()
+            .map(Self::new)
+    }
+
+    pub fn stream(&self) -> &RecordingStream {
+        &self.stream
+    }
+}
+
+impl Observer for RerunSink {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        match event {
+            EngineEvent::Frame { frame } => {
+                self.stream.set_time_sequence("frame", frame as i64);
+            }
+            EngineEvent::Message { agent, message } => {
+                let path = format!("messages/{agent}");
+                let _ = self.stream.log(path, &TextLog::new(format!("{message:?}")));
+            }
+            EngineEvent::State { agent, state } => {
+                let path = format!("state/{agent}");
+                let _ = self.stream.log(path, &TextLog::new(format!("{state:?}")));
+            }
+            EngineEvent::DeadlineMissed { frame, overrun } => {
+                let text = format!("frame {frame} overran by {overrun:?}");
+                let _ = self.stream.log("deadlines", &TextLog::new(text));
+            }
+            EngineEvent::SlowFrame {
+                frame,
+                elapsed,
+                receive,
+            } => {
+                let text = format!("frame {frame} took {elapsed:?}, {receive:?} receiving");
+                let _ = self.stream.log("slow_frames", &TextLog::new(text));
+            }
+            EngineEvent::SlowStartup { agent, latency } => {
+                let text = format!("{agent} took {latency:?} to start");
+                let _ = self.stream.log("startup", &TextLog::new(text));
+            }
+            EngineEvent::Sent {
+                agent,
+                message_type,
+            } => {
+                let path = format!("sent/{agent}");
+                let _ = self.stream.log(path, &TextLog::new(message_type));
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{CancellationToken, Controller, EngineHandle, MultiAgentEngine, Simulator};
+use multi_agent_engine_core::{Error, Result};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Default)]
+pub struct Runtime {
+    engines: Mutex<Vec<(CancellationToken, EngineHandle)>>,
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn<C, S>(&self, engine: MultiAgentEngine<C, S>)
+    where
+        C: Controller + Send + 'static,
+        S: Simulator + Send + 'static,
+    {
+        let token = engine.cancellation_token();
+        let handle = engine.spawn();
+
+        self.lock().push((token, handle));
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn shutdown_all(&self) {
+        for (token, _) in self.lock().iter() {
+            token.cancel();
+        }
+    }
+
+    pub fn join_all(&self) -> Result<()> {
+        let engines = std::mem::take(&mut *self.lock());
+
+        let mut errors: Vec<Error> = engines
+            .into_iter()
+            .filter_map(|(_, handle)| handle.join().err())
+            .collect();
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(CancellationToken, EngineHandle)>> {
+        self.engines.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
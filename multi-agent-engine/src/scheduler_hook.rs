@@ -0,0 +1,93 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::AgentId;
+use std::{cell::RefCell, fmt::Debug, sync::Arc};
+
+thread_local! {
+    static HOOK: RefCell<Option<(AgentId, Arc<dyn SchedulerHook>)>> = const { RefCell::new(None) };
+}
+
+/// An operation at which an agent thread hands control to the
+/// [`SchedulerHook`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchedulePoint {
+    /// The agent thread is about to run the agent.
+    #[default]
+    Start,
+    /// A [`Sender::send`](crate::message::Sender::send).
+    Send,
+    /// A receive on a [`Receiver`](crate::message::Receiver).
+    Receive,
+    /// A load, store or write on a [`Shared`](crate::Shared).
+    Shared,
+}
+
+/// Orders the operations of concurrently running agents.
+///
+/// Installed with [`MultiAgentEngine::with_scheduler_hook`](crate::MultiAgentEngine::with_scheduler_hook),
+/// the hook is called on the agent threads at every [`SchedulePoint`] and may
+/// block there to let another agent run first. Interleavings are only
+/// controlled at those points, so agents must interact exclusively through
+/// engine queues and [`Shared`](crate::Shared) for a schedule to be
+/// reproducible.
+pub trait SchedulerHook: Debug + Send + Sync {
+    /// Called on the spawning thread for every agent before any agent runs.
+    fn register(&self, _agent: AgentId) {}
+
+    fn yield_point(&self, agent: AgentId, point: SchedulePoint);
+
+    /// The agent found its queue empty and cannot progress until a message
+    /// is sent.
+    fn idle(&self, _agent: AgentId) {}
+
+    fn message_sent(&self, _agent: AgentId) {}
+
+    /// The agent returned from its run, dropping its endpoints.
+    fn finish(&self, _agent: AgentId) {}
+}
+
+pub(crate) fn install(agent: AgentId, hook: Arc<dyn SchedulerHook>) {
+    HOOK.set(Some((agent, hook)));
+}
+
+pub(crate) fn is_active() -> bool {
+    HOOK.with_borrow(Option::is_some)
+}
+
+pub(crate) fn yield_point(point: SchedulePoint) {
+    with_hook(|agent, hook| hook.yield_point(agent, point));
+}
+
+pub(crate) fn idle() {
+    with_hook(|agent, hook| hook.idle(agent));
+}
+
+pub(crate) fn message_sent() {
+    with_hook(|agent, hook| hook.message_sent(agent));
+}
+
+pub(crate) fn finish() {
+    with_hook(|agent, hook| hook.finish(agent));
+    HOOK.set(None);
+}
+
+fn with_hook(f: impl FnOnce(AgentId, &dyn SchedulerHook)) {
+    if let Some((agent, hook)) = HOOK.with_borrow(Clone::clone) {
+        f(agent, hook.as_ref());
+    }
+}
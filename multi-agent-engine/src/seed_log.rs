@@ -0,0 +1,50 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{Reproduction, SeededRng};
+use multi_agent_engine_core::AgentId;
+use std::sync::{
+    Arc, OnceLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Records the seed of a run started [`with_seed`](crate::MultiAgentEngine::with_seed)
+/// along with the shared frame counter, so a failing run can print how to
+/// replay it. Obtained from
+/// [`MultiAgentEngine::seed_log`](crate::MultiAgentEngine::seed_log).
+#[derive(Debug, Clone, Default)]
+pub struct SeedLog {
+    run: Arc<OnceLock<(u64, Arc<AtomicU64>)>>,
+}
+
+impl SeedLog {
+    pub(crate) fn begin(&self, seed: u64, frame: Arc<AtomicU64>) {
+        let _ = self.run.set((seed, frame));
+    }
+
+    /// `None` until a seeded engine was spawned. The frame count is live
+    /// while the engine runs and final once it was joined.
+    pub fn reproduction(&self) -> Option<Reproduction> {
+        let (seed, frame) = self.run.get()?;
+
+        Some(Reproduction {
+            seed: *seed,
+            controller_seed: SeededRng::agent_seed(*seed, AgentId::CONTROLLER),
+            simulator_seed: SeededRng::agent_seed(*seed, AgentId::SIMULATOR),
+            frames: frame.load(Ordering::Acquire),
+        })
+    }
+}
@@ -14,26 +14,171 @@
  * limitations under the License.
  */
 
+use crate::{ContentionStats, Diff, message::Receiver};
 use arc_swap::{ArcSwap, Guard};
-use std::sync::Arc;
+use crossbeam_channel::TrySendError;
+use multi_agent_engine_core::{Error, Result};
+use std::{
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError, TryLockError,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often a writer waiting with a timeout retries the lock.
+const WRITER_POLL: Duration = Duration::from_micros(100);
 
 #[derive(Debug, Clone)]
 pub struct Shared<T> {
     data: Arc<ArcSwap<T>>,
+    version: Arc<AtomicU64>,
+    subscribers: Arc<Mutex<Vec<crossbeam_channel::Sender<()>>>>,
+    writer: Arc<Mutex<ContentionStats>>,
 }
 
 impl<T> Shared<T> {
     pub fn new(data: T) -> Self {
         Self {
             data: Arc::new(ArcSwap::from_pointee(data)),
+            version: Arc::new(AtomicU64::new(0)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            writer: Arc::new(Mutex::new(ContentionStats::default())),
         }
     }
 
     pub fn load(&self) -> Guard<Arc<T>> {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Shared);
+
         self.data.load()
     }
 
+    /// Replaces the value. Serialized with [`update`](Self::update), so a
+    /// store never lands in the middle of an update and gets overwritten.
     pub fn store(&self, data: T) {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Shared);
+
+        let stats = self.lock_writer();
+        self.publish(data);
+        drop(stats);
+    }
+
+    /// Applies `f` to a copy of the current value and publishes the result,
+    /// bumping the version and notifying subscribers before the next writer
+    /// may start. This is the way to read-modify-write shared state.
+    ///
+    /// Readers never block a write, but concurrent writes are serialized so
+    /// none of their updates is lost. Time spent waiting for another writer is
+    /// reported by [`contention_stats`](Self::contention_stats).
+    pub fn update(&self, f: impl FnOnce(&mut T))
+    where
+        T: Clone,
+    {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Shared);
+
+        let stats = self.lock_writer();
+        let mut data = T::clone(&self.data.load());
+        f(&mut data);
+        self.publish(data);
+        drop(stats);
+    }
+
+    /// Like [`update`](Self::update), but gives up with [`Error::Timeout`]
+    /// without applying `f` if another writer holds on for longer than
+    /// `timeout`, so a starved writer can be detected.
+    pub fn update_timeout(&self, timeout: Duration, f: impl FnOnce(&mut T)) -> Result<()>
+    where
+        T: Clone,
+    {
+        #[cfg(feature = "scheduler-hook")]
+        crate::scheduler_hook::yield_point(crate::SchedulePoint::Shared);
+
+        let stats = self
+            .lock_writer_timeout(timeout)
+            .ok_or(Error::Timeout { duration: timeout })?;
+        let mut data = T::clone(&self.data.load());
+        f(&mut data);
+        self.publish(data);
+        drop(stats);
+        Ok(())
+    }
+
+    pub fn contention_stats(&self) -> ContentionStats {
+        *self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(sender);
+
+        Receiver::new(receiver)
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    pub fn diff_since(&self, prev: &T) -> T::Delta
+    where
+        T: Diff,
+    {
+        prev.diff(&self.load())
+    }
+
+    fn lock_writer(&self) -> MutexGuard<'_, ContentionStats> {
+        match self.writer.try_lock() {
+            Ok(stats) => stats,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                let start = Instant::now();
+                let mut stats = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+                stats.record(start.elapsed());
+                stats
+            }
+        }
+    }
+
+    fn lock_writer_timeout(&self, timeout: Duration) -> Option<MutexGuard<'_, ContentionStats>> {
+        let start = Instant::now();
+        let mut waited = false;
+        loop {
+            match self.writer.try_lock() {
+                Ok(mut stats) => {
+                    if waited {
+                        stats.record(start.elapsed());
+                    }
+                    return Some(stats);
+                }
+                Err(TryLockError::Poisoned(err)) => return Some(err.into_inner()),
+                Err(TryLockError::WouldBlock) if start.elapsed() >= timeout => return None,
+                Err(TryLockError::WouldBlock) => {
+                    waited = true;
+                    thread::sleep(WRITER_POLL);
+                }
+            }
+        }
+    }
+
+    fn publish(&self, data: T) {
         self.data.store(Arc::new(data));
+        self.version.fetch_add(1, Ordering::Release);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|subscriber| {
+                !matches!(subscriber.try_send(()), Err(TrySendError::Disconnected(())))
+            });
     }
 }
@@ -0,0 +1,84 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::AtomicBits;
+use std::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// A lock-free counterpart of [`Shared`](crate::Shared) for tiny `Copy` state
+/// such as a frame count, a flag set or a position, stored in one atomic word.
+pub struct SharedAtomic<T> {
+    bits: Arc<AtomicU64>,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T: AtomicBits> SharedAtomic<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            bits: Arc::new(AtomicU64::new(value.into_bits())),
+            value: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn load(&self) -> T {
+        T::from_bits(self.bits.load(Ordering::Acquire))
+    }
+
+    #[inline]
+    pub fn store(&self, value: T) {
+        self.bits.store(value.into_bits(), Ordering::Release);
+    }
+
+    /// Applies `f` until it wins against concurrent writers, as
+    /// [`AtomicU64::fetch_update`] does. Returns the previous value, or the
+    /// current one as `Err` if `f` returned `None`.
+    pub fn fetch_update(&self, mut f: impl FnMut(T) -> Option<T>) -> Result<T, T> {
+        self.bits
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+                f(T::from_bits(bits)).map(T::into_bits)
+            })
+            .map(T::from_bits)
+            .map_err(T::from_bits)
+    }
+}
+
+impl<T> Clone for SharedAtomic<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bits: Arc::clone(&self.bits),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T: AtomicBits + Debug> Debug for SharedAtomic<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedAtomic").field(&self.load()).finish()
+    }
+}
+
+impl<T: AtomicBits + Default> Default for SharedAtomic<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
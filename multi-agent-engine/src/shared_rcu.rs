@@ -0,0 +1,63 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use arc_swap::ArcSwap;
+use std::sync::Arc;
+
+/// Read-copy-update state for many readers and a rare writer, without the
+/// version counter, subscribers and writer lock of [`Shared`](crate::Shared).
+///
+/// [`load`](Self::load) hands out the current version as an `Arc` without
+/// ever blocking, and [`store`](Self::store) publishes a whole new version by
+/// swapping the pointer. A reader therefore always sees a complete version,
+/// but may keep working on the old one for a while after a store, until it
+/// loads again. Old versions are freed once their last reader drops them.
+#[derive(Debug)]
+pub struct SharedRcu<T> {
+    data: Arc<ArcSwap<T>>,
+}
+
+impl<T> SharedRcu<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data: Arc::new(ArcSwap::from_pointee(data)),
+        }
+    }
+
+    #[inline]
+    pub fn load(&self) -> Arc<T> {
+        self.data.load_full()
+    }
+
+    #[inline]
+    pub fn store(&self, data: T) {
+        self.data.store(Arc::new(data));
+    }
+}
+
+impl<T> Clone for SharedRcu<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: Arc::clone(&self.data),
+        }
+    }
+}
+
+impl<T: Default> Default for SharedRcu<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
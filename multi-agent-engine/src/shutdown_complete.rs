@@ -0,0 +1,92 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::EngineHandle;
+use multi_agent_engine_core::Result;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+/// A future resolving with the engine's [`join`](EngineHandle::join) result
+/// once both agents have finished, returned by
+/// [`EngineHandle::shutdown_complete`].
+///
+/// Joining happens on a helper thread, so awaiting never blocks the
+/// executor. The future works with any async runtime. After it has resolved
+/// once, polling it again returns `Ok(())`.
+#[derive(Debug)]
+pub struct ShutdownComplete {
+    completion: Arc<Mutex<Completion>>,
+}
+
+#[derive(Debug, Default)]
+struct Completion {
+    result: Option<Result<()>>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl ShutdownComplete {
+    pub(crate) fn new(handle: EngineHandle) -> Self {
+        let completion = Arc::new(Mutex::new(Completion::default()));
+        let published = Arc::clone(&completion);
+
+        thread::spawn(move || {
+            let result = handle.join();
+            let waker = {
+                let mut completion = published.lock().unwrap_or_else(PoisonError::into_inner);
+                completion.result = Some(result);
+                completion.finished = true;
+                completion.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+
+        Self { completion }
+    }
+
+    /// Whether both agents have finished and the result is ready.
+    pub fn is_complete(&self) -> bool {
+        self.lock().finished
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Completion> {
+        self.completion
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Future for ShutdownComplete {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut completion = self.lock();
+
+        if completion.finished {
+            Poll::Ready(completion.result.take().unwrap_or(Ok(())))
+        } else {
+            completion.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
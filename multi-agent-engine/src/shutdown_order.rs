@@ -0,0 +1,32 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// The sequence in which [`EngineHandle::shutdown`] stops the agents.
+///
+/// [`EngineHandle::shutdown`]: crate::EngineHandle::shutdown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShutdownOrder {
+    /// Cancels both agents at once.
+    #[default]
+    Simultaneous,
+    /// Cancels the controller and waits for it to exit before cancelling the
+    /// simulator, so the simulator can drain the controller's last messages.
+    ControllerFirst,
+    /// Cancels the simulator and waits for it to exit before cancelling the
+    /// controller.
+    SimulatorFirst,
+}
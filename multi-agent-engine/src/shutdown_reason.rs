@@ -0,0 +1,30 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// Why a [`CancellationToken`](crate::CancellationToken) was cancelled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ShutdownReason {
+    /// A regular, requested shutdown.
+    #[default]
+    Normal,
+    /// Another agent failed or panicked.
+    Error,
+    /// A deadline such as the startup timeout expired.
+    Timeout,
+    /// The process received an interrupt signal such as Ctrl-C.
+    Interrupt,
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::AgentId;
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Default)]
+struct Startup {
+    spawned: OnceLock<Instant>,
+    controller: OnceLock<Duration>,
+    simulator: OnceLock<Duration>,
+}
+
+/// How long each agent took from the engine spawning it to its first frame,
+/// that is its first [`checkpoint`](crate::AgentContext::checkpoint),
+/// [`advance_frame`](crate::AgentContext::advance_frame),
+/// [`complete_frame`](crate::AgentContext::complete_frame) or
+/// [`mark_ready`](crate::AgentContext::mark_ready). Obtained from
+/// [`MultiAgentEngine::startup_report`](crate::MultiAgentEngine::startup_report)
+/// and filled in while the engine runs.
+///
+/// A restarted agent keeps the latency of its first start.
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+    startup: Arc<Startup>,
+}
+
+impl StartupReport {
+    pub(crate) fn begin(&self, now: Instant) {
+        let _ = self.startup.spawned.set(now);
+    }
+
+    /// Records the first frame of `agent`, returning its latency the first
+    /// time only.
+    pub(crate) fn record(&self, agent: AgentId, now: Instant) -> Option<Duration> {
+        let slot = self.slot(agent)?;
+        if slot.get().is_some() {
+            return None;
+        }
+
+        let spawned = self.startup.spawned.get()?;
+        let latency = now.saturating_duration_since(*spawned);
+        slot.set(latency).ok().map(|()| latency)
+    }
+
+    /// `None` until `agent` reached its first frame.
+    pub fn startup_latency(&self, agent: AgentId) -> Option<Duration> {
+        self.slot(agent)?.get().copied()
+    }
+
+    fn slot(&self, agent: AgentId) -> Option<&OnceLock<Duration>> {
+        match agent {
+            AgentId::CONTROLLER => Some(&self.startup.controller),
+            AgentId::SIMULATOR => Some(&self.startup.simulator),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::Result;
+use std::ops::ControlFlow;
+
+pub trait Step {
+    fn step(&mut self) -> Result<ControlFlow<()>>;
+
+    /// Like [`step`](Self::step), but doing at most `quantum` units of work,
+    /// such as messages handled, and leaving the rest for later steps. Called
+    /// by a [`SteppedEngine`](crate::SteppedEngine) built
+    /// [`with_work_quantum`](crate::SteppedEngine::with_work_quantum).
+    /// Defaults to [`step`](Self::step).
+    fn step_bounded(&mut self, quantum: usize) -> Result<ControlFlow<()>> {
+        let _ = quantum;
+        self.step()
+    }
+}
@@ -0,0 +1,160 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{EngineEvent, Metrics, Observer, Step};
+use multi_agent_engine_core::Result;
+use std::{ops::ControlFlow, time::Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepOutcome {
+    pub frame: u64,
+    pub controller_finished: bool,
+    pub simulator_finished: bool,
+}
+
+impl StepOutcome {
+    pub fn is_finished(&self) -> bool {
+        self.controller_finished || self.simulator_finished
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.controller_finished && self.simulator_finished
+    }
+}
+
+/// Runs both agents on the calling thread, one step each per frame in
+/// round-robin order, so an agent that always has work cannot starve the
+/// other. A [work quantum](Self::with_work_quantum) also bounds how long one
+/// step may take.
+pub struct SteppedEngine<C, S>
+where
+    C: Step,
+    S: Step,
+{
+    controller: C,
+    simulator: S,
+    frame: u64,
+    controller_finished: bool,
+    simulator_finished: bool,
+    metrics: Option<Metrics>,
+    observers: Vec<Box<dyn Observer>>,
+    quantum: Option<usize>,
+}
+
+impl<C, S> SteppedEngine<C, S>
+where
+    C: Step,
+    S: Step,
+{
+    pub fn new(controller: C, simulator: S) -> Self {
+        Self {
+            controller,
+            simulator,
+            frame: 0,
+            controller_finished: false,
+            simulator_finished: false,
+            metrics: None,
+            observers: Vec::new(),
+            quantum: None,
+        }
+    }
+
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Steps both agents through [`Step::step_bounded`] with at most
+    /// `quantum` units of work each per frame.
+    pub fn with_work_quantum(mut self, quantum: usize) -> Self {
+        self.quantum = Some(quantum);
+        self
+    }
+
+    /// Runs one frame: steps each agent that is still running once.
+    ///
+    /// This is also how an embedder drives the engine from its own loop,
+    /// such as a game loop: once both agents finished, a step does nothing
+    /// and returns the final outcome, so it can be called every iteration
+    /// without tracking the agents' state.
+    pub fn step(&mut self) -> Result<StepOutcome> {
+        if self.outcome().is_complete() {
+            return Ok(self.outcome());
+        }
+        let started = Instant::now();
+
+        if !self.controller_finished {
+            self.controller_finished = step_one(&mut self.controller, self.quantum)?.is_break();
+        }
+        if !self.simulator_finished {
+            self.simulator_finished = step_one(&mut self.simulator, self.quantum)?.is_break();
+        }
+        self.frame += 1;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_frame(started.elapsed());
+        }
+        self.notify(EngineEvent::Frame { frame: self.frame });
+
+        Ok(self.outcome())
+    }
+
+    pub fn run(mut self) -> Result<u64> {
+        while !self.outcome().is_complete() {
+            self.step()?;
+        }
+
+        Ok(self.frame)
+    }
+
+    pub fn notify(&mut self, event: EngineEvent<'_>) {
+        for observer in &mut self.observers {
+            observer.observe(event);
+        }
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn controller(&self) -> &C {
+        &self.controller
+    }
+
+    pub fn simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    fn outcome(&self) -> StepOutcome {
+        StepOutcome {
+            frame: self.frame,
+            controller_finished: self.controller_finished,
+            simulator_finished: self.simulator_finished,
+        }
+    }
+}
+
+fn step_one(agent: &mut impl Step, quantum: Option<usize>) -> Result<ControlFlow<()>> {
+    match quantum {
+        Some(quantum) => agent.step_bounded(quantum),
+        None => agent.step(),
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+type Depth = Box<dyn Fn() -> usize + Send>;
+
+/// A queue that still held messages when the engine was torn down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndrainedQueue {
+    pub name: String,
+    pub remaining: usize,
+}
+
+#[derive(Default)]
+struct State {
+    queues: Vec<(String, Depth)>,
+    undrained: Option<Vec<UndrainedQueue>>,
+}
+
+/// Per-queue count of the messages left behind once both agents exited,
+/// filled in when the engine is joined. Queues are registered with
+/// [`MultiAgentEngine::with_queue`](crate::MultiAgentEngine::with_queue).
+///
+/// Leftover messages usually mean one side stopped reading before the other
+/// stopped writing.
+#[derive(Clone, Default)]
+pub struct TeardownReport {
+    state: Arc<Mutex<State>>,
+}
+
+impl TeardownReport {
+    pub(crate) fn register(&self, name: String, depth: Depth) {
+        self.lock().queues.push((name, depth));
+    }
+
+    /// Counts the messages still queued and releases the registered queues.
+    /// Only the first call records, later joins keep its counts.
+    pub(crate) fn record(&self) {
+        let mut state = self.lock();
+        if state.undrained.is_some() {
+            return;
+        }
+
+        let undrained = state
+            .queues
+            .drain(..)
+            .map(|(name, depth)| (name, depth()))
+            .filter(|&(_, remaining)| remaining > 0)
+            .map(|(name, remaining)| UndrainedQueue { name, remaining })
+            .collect();
+
+        state.undrained = Some(undrained);
+    }
+
+    /// Whether the engine was joined and the counts are available.
+    pub fn is_recorded(&self) -> bool {
+        self.lock().undrained.is_some()
+    }
+
+    /// The registered queues that still held messages, in registration order.
+    /// Empty until the engine was joined.
+    pub fn undrained(&self) -> Vec<UndrainedQueue> {
+        self.lock().undrained.clone().unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Debug for TeardownReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+
+        f.debug_struct("TeardownReport")
+            .field("queues", &state.queues.len())
+            .field("undrained", &state.undrained)
+            .finish()
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    PanicPolicy, Warning,
+    message::{self, ReceiveStrategy},
+};
+use multi_agent_engine_core::AgentId;
+use std::time::Duration;
+
+#[cfg(feature = "scheduler-hook")]
+use crate::SchedulerHook;
+#[cfg(feature = "scheduler-hook")]
+use std::sync::Arc;
+#[cfg(feature = "thread-priority")]
+use thread_priority::ThreadPriority;
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ThreadConfig {
+    pub(crate) panic: PanicPolicy,
+    pub(crate) receive: ReceiveStrategy,
+    pub(crate) max_drain: Option<usize>,
+    pub(crate) recv_timeout: Option<Duration>,
+    #[cfg(feature = "core_affinity")]
+    pub(crate) core: Option<usize>,
+    #[cfg(feature = "thread-priority")]
+    pub(crate) priority: Option<ThreadPriority>,
+    #[cfg(feature = "scheduler-hook")]
+    pub(crate) scheduler: Option<Arc<dyn SchedulerHook>>,
+}
+
+impl ThreadConfig {
+    #[cfg_attr(
+        not(any(feature = "core_affinity", feature = "thread-priority")),
+        allow(unused_mut, unused_variables)
+    )]
+    pub(crate) fn apply(&self, agent: AgentId) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        self.receive.set_thread_default();
+        message::set_thread_drain_limit(self.max_drain);
+        message::set_thread_recv_timeout(self.recv_timeout);
+
+        #[cfg(feature = "scheduler-hook")]
+        if let Some(scheduler) = &self.scheduler {
+            crate::scheduler_hook::install(agent, Arc::clone(scheduler));
+        }
+
+        #[cfg(feature = "core_affinity")]
+        if let Some(core) = self.core {
+            let id = core_affinity::CoreId { id: core };
+            let pinned = core_affinity::get_core_ids().is_some_and(|cores| cores.contains(&id))
+                && core_affinity::set_for_current(id);
+
+            if !pinned {
+                warnings.push(Warning::Affinity { agent, core });
+            }
+        }
+
+        #[cfg(feature = "thread-priority")]
+        if let Some(priority) = self.priority
+            && let Err(err) = thread_priority::set_current_thread_priority(priority)
+        {
+            warnings.push(Warning::Priority {
+                agent,
+                reason: err.to_string(),
+            });
+        }
+
+        warnings
+    }
+}
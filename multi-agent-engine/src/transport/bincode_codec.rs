@@ -0,0 +1,39 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Codec;
+use multi_agent_engine_core::Result;
+use serde::{Serialize, de::DeserializeOwned};
+use std::io;
+
+/// The compact binary [`Codec`] of the `bincode` crate, in its standard
+/// configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BincodeCodec;
+
+impl Codec for BincodeCodec {
+    fn encode<T: Serialize>(&self, msg: &T) -> Result<Vec<u8>> {
+        let bytes = bincode::serde::encode_to_vec(msg, bincode::config::standard())
+            .map_err(io::Error::other)?;
+        Ok(bytes)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        let (msg, _) = bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(io::Error::other)?;
+        Ok(msg)
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Transport;
+use crate::{Clock, SystemClock};
+use multi_agent_engine_core::{Error, Result};
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker<Tr> {
+    transport: Tr,
+    failure_threshold: u32,
+    cooldown: Duration,
+    breaker: Mutex<Breaker>,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    failures: u32,
+    opened_at: Instant,
+    probing: bool,
+}
+
+impl<Tr> CircuitBreaker<Tr>
+where
+    Tr: Transport,
+{
+    pub fn new(transport: Tr, failure_threshold: u32, cooldown: Duration) -> Self {
+        let clock = Arc::new(SystemClock);
+
+        Self {
+            transport,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            breaker: Mutex::new(Breaker {
+                state: CircuitState::Closed,
+                failures: 0,
+                opened_at: clock.now(),
+                probing: false,
+            }),
+            clock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn inner(&self) -> &Tr {
+        &self.transport
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let mut breaker = self.lock();
+        self.refresh(&mut breaker);
+        breaker.state
+    }
+
+    fn refresh(&self, breaker: &mut Breaker) {
+        if breaker.state == CircuitState::Open
+            && self.clock.now() >= breaker.opened_at + self.cooldown
+        {
+            breaker.state = CircuitState::HalfOpen;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Breaker> {
+        self.breaker.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<Tr> Transport for CircuitBreaker<Tr>
+where
+    Tr: Transport,
+{
+    type Outgoing = Tr::Outgoing;
+    type Incoming = Tr::Incoming;
+
+    fn send(&self, msg: Self::Outgoing) -> Result<()> {
+        {
+            let mut breaker = self.lock();
+            self.refresh(&mut breaker);
+
+            match breaker.state {
+                CircuitState::Closed => {}
+                CircuitState::HalfOpen if !breaker.probing => breaker.probing = true,
+                CircuitState::HalfOpen | CircuitState::Open => return Err(Error::CircuitOpen),
+            }
+        }
+
+        let result = self.transport.send(msg);
+
+        let mut breaker = self.lock();
+        breaker.probing = false;
+
+        match result {
+            Ok(()) => {
+                breaker.state = CircuitState::Closed;
+                breaker.failures = 0;
+            }
+            Err(_) => {
+                breaker.failures += 1;
+
+                if breaker.state == CircuitState::HalfOpen
+                    || breaker.failures >= self.failure_threshold
+                {
+                    breaker.state = CircuitState::Open;
+                    breaker.opened_at = self.clock.now();
+                }
+            }
+        }
+
+        result
+    }
+
+    fn receive(&self) -> Result<Vec<Self::Incoming>> {
+        self.transport.receive()
+    }
+}
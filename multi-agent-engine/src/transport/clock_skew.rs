@@ -0,0 +1,71 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How far a peer's wall clock runs ahead of the local one, estimated from
+/// one request and reply.
+///
+/// The four timestamps are the NTP ones, in nanoseconds since the Unix epoch:
+/// `t0` when the local side sent the request, `t1` when the peer received it,
+/// `t2` when the peer replied and `t3` when the reply arrived. Network delay
+/// is assumed to be the same in both directions, so the estimate is off by
+/// up to half the difference between the two, and never by more than half
+/// the [`round_trip`](Self::round_trip).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClockSkew {
+    offset_ns: i64,
+    round_trip: Duration,
+}
+
+impl ClockSkew {
+    pub fn from_exchange(t0: u64, t1: u64, t2: u64, t3: u64) -> Self {
+        let there = i128::from(t1) - i128::from(t0);
+        let back = i128::from(t2) - i128::from(t3);
+        let offset = (there + back) / 2;
+        let round_trip = (t3.saturating_sub(t0)).saturating_sub(t2.saturating_sub(t1));
+
+        Self {
+            offset_ns: offset.clamp(i64::MIN.into(), i64::MAX.into()) as i64,
+            round_trip: Duration::from_nanos(round_trip),
+        }
+    }
+
+    /// Nanoseconds the peer's clock is ahead of the local one, negative when
+    /// it is behind.
+    #[inline]
+    pub fn offset_ns(&self) -> i64 {
+        self.offset_ns
+    }
+
+    #[inline]
+    pub fn round_trip(&self) -> Duration {
+        self.round_trip
+    }
+
+    /// Moves a timestamp taken on the peer's clock into the local domain.
+    pub fn to_local(&self, remote_ns: u64) -> u64 {
+        remote_ns.saturating_add_signed(self.offset_ns.saturating_neg())
+    }
+}
+
+/// The local wall clock in nanoseconds since the Unix epoch, the unit
+/// [`ClockSkew`] and [`Timestamped`](super::Timestamped) work in.
+pub fn unix_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos().try_into().unwrap_or(u64::MAX))
+}
@@ -0,0 +1,31 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::Result;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// A wire format for messages crossing a process boundary.
+pub trait Codec {
+    fn encode<T: Serialize>(&self, msg: &T) -> Result<Vec<u8>>;
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T>;
+
+    /// Whether encoded messages are UTF-8 text, which transports with a
+    /// distinct text frame, such as WebSocket, send as such.
+    fn is_text(&self) -> bool {
+        false
+    }
+}
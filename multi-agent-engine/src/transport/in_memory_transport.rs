@@ -0,0 +1,114 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Transport;
+use crate::{Clock, SystemClock};
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+type Stamped<T> = (Instant, T);
+
+/// One end of an in-process [`Transport`] for testing transport-generic code.
+///
+/// Messages travel over a channel to the peer created by [`pair`](Self::pair)
+/// and become visible to its `receive` once `latency` has elapsed on the
+/// sender's clock. Dropping one end disconnects the other.
+#[derive(Debug)]
+pub struct InMemoryTransport<O, I> {
+    outgoing: Sender<Stamped<O>>,
+    incoming: Receiver<Stamped<I>>,
+    in_flight: Mutex<VecDeque<Stamped<I>>>,
+    latency: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl<O, I> InMemoryTransport<O, I> {
+    pub fn pair() -> (Self, InMemoryTransport<I, O>) {
+        let (left_sender, right_receiver) = crossbeam_channel::unbounded();
+        let (right_sender, left_receiver) = crossbeam_channel::unbounded();
+
+        (
+            Self::new(left_sender, left_receiver),
+            InMemoryTransport::new(right_sender, right_receiver),
+        )
+    }
+
+    fn new(outgoing: Sender<Stamped<O>>, incoming: Receiver<Stamped<I>>) -> Self {
+        Self {
+            outgoing,
+            incoming,
+            in_flight: Mutex::new(VecDeque::new()),
+            latency: Duration::ZERO,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+}
+
+impl<O, I> Transport for InMemoryTransport<O, I> {
+    type Outgoing = O;
+    type Incoming = I;
+
+    fn send(&self, msg: O) -> Result<()> {
+        let due = self.clock.now() + self.latency;
+
+        self.outgoing
+            .send((due, msg))
+            .map_err(|_| Error::Disconnected {
+                endpoint: Endpoint::Sender,
+            })
+    }
+
+    fn receive(&self) -> Result<Vec<I>> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let disconnected = loop {
+            match self.incoming.try_recv() {
+                Ok(msg) => in_flight.push_back(msg),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+
+        if disconnected && in_flight.is_empty() {
+            return Err(Error::Disconnected {
+                endpoint: Endpoint::Receiver,
+            });
+        }
+
+        let now = self.clock.now();
+        let arrived = in_flight.iter().take_while(|(due, _)| *due <= now).count();
+
+        Ok(in_flight.drain(..arrived).map(|(_, msg)| msg).collect())
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Codec;
+use multi_agent_engine_core::Result;
+use serde::{Serialize, de::DeserializeOwned};
+use std::io;
+
+/// A [`Codec`] for MessagePack, compact like bincode but self-describing,
+/// with structs encoded as maps so fields can be added across versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessagePackCodec;
+
+impl Codec for MessagePackCodec {
+    fn encode<T: Serialize>(&self, msg: &T) -> Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec_named(msg).map_err(io::Error::other)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        Ok(rmp_serde::from_slice(bytes).map_err(io::Error::other)?)
+    }
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(feature = "bincode")]
+mod bincode_codec;
+mod circuit_breaker;
+mod clock_skew;
+#[cfg(feature = "serde")]
+mod codec;
+mod in_memory_transport;
+#[cfg(feature = "serde")]
+mod json_codec;
+#[cfg(feature = "msgpack")]
+mod message_pack_codec;
+mod reconnecting_transport;
+mod reliable_sender;
+mod retry_sender;
+mod skew_corrected_transport;
+mod timestamped;
+#[cfg(feature = "serde")]
+mod versioned_codec;
+#[cfg(feature = "websocket")]
+mod web_socket_transport;
+
+#[cfg(feature = "bincode")]
+pub use bincode_codec::BincodeCodec;
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use clock_skew::{ClockSkew, unix_nanos};
+#[cfg(feature = "serde")]
+pub use codec::Codec;
+pub use in_memory_transport::InMemoryTransport;
+#[cfg(feature = "serde")]
+pub use json_codec::JsonCodec;
+#[cfg(feature = "msgpack")]
+pub use message_pack_codec::MessagePackCodec;
+pub use reconnecting_transport::{ConnectionState, ReconnectingTransport};
+pub use reliable_sender::ReliableSender;
+pub use retry_sender::RetrySender;
+pub use skew_corrected_transport::SkewCorrectedTransport;
+pub use timestamped::Timestamped;
+#[cfg(feature = "serde")]
+pub use versioned_codec::VersionedCodec;
+#[cfg(feature = "websocket")]
+pub use web_socket_transport::WebSocketTransport;
+
+use multi_agent_engine_core::Result;
+
+pub trait Transport {
+    type Outgoing;
+    type Incoming;
+
+    fn send(&self, msg: Self::Outgoing) -> Result<()>;
+
+    fn receive(&self) -> Result<Vec<Self::Incoming>>;
+}
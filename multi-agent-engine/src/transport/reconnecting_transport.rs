@@ -0,0 +1,243 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Transport;
+use crate::{Clock, SystemClock};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+}
+
+/// Wraps a transport created by `connect` and survives it disconnecting.
+///
+/// While disconnected, sent messages are buffered up to
+/// [`with_buffer_capacity`](Self::with_buffer_capacity) and `connect` is
+/// retried with exponential backoff on each send or receive. Once it
+/// succeeds, the buffer is flushed in order before new messages go out.
+/// Only [`Error::Transport`] and [`Error::Disconnected`] count as a lost
+/// connection; other errors are returned as is.
+pub struct ReconnectingTransport<Tr, F>
+where
+    Tr: Transport,
+{
+    connect: Mutex<F>,
+    link: Mutex<Link<Tr>>,
+    capacity: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+struct Link<Tr: Transport> {
+    transport: Option<Tr>,
+    buffer: VecDeque<Tr::Outgoing>,
+    delay: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl<Tr, F> ReconnectingTransport<Tr, F>
+where
+    Tr: Transport,
+    Tr::Outgoing: Clone,
+    F: FnMut() -> Result<Tr>,
+{
+    pub const DEFAULT_BUFFER_CAPACITY: usize = 1024;
+
+    pub fn new(connect: F) -> Self {
+        let backoff = Duration::from_millis(10);
+
+        Self {
+            connect: Mutex::new(connect),
+            link: Mutex::new(Link {
+                transport: None,
+                buffer: VecDeque::new(),
+                delay: backoff,
+                retry_at: None,
+            }),
+            capacity: Self::DEFAULT_BUFFER_CAPACITY,
+            backoff,
+            max_backoff: Duration::from_secs(1),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Waits `initial` after the first failed attempt, doubling up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = initial;
+        self.max_backoff = max.max(initial);
+        self.link
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .delay = initial;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        let mut link = self.lock();
+        self.reconnect_if_due(&mut link);
+
+        if link.transport.is_some() {
+            ConnectionState::Connected
+        } else {
+            ConnectionState::Reconnecting
+        }
+    }
+
+    /// Messages waiting for the connection to come back.
+    pub fn buffered(&self) -> usize {
+        self.lock().buffer.len()
+    }
+
+    fn reconnect_if_due(&self, link: &mut Link<Tr>) {
+        if link.transport.is_some() {
+            return;
+        }
+
+        let now = self.clock.now();
+        if link.retry_at.is_some_and(|at| now < at) {
+            return;
+        }
+
+        let mut connect = self.connect.lock().unwrap_or_else(PoisonError::into_inner);
+        match connect() {
+            Ok(transport) => {
+                link.transport = Some(transport);
+                link.delay = self.backoff;
+                link.retry_at = None;
+            }
+            Err(_) => {
+                link.retry_at = Some(now + link.delay);
+                link.delay = link.delay.saturating_mul(2).min(self.max_backoff);
+            }
+        }
+    }
+
+    fn flush(&self, link: &mut Link<Tr>) -> Result<()> {
+        while let Some(transport) = &link.transport
+            && let Some(msg) = link.buffer.front()
+        {
+            match transport.send(msg.clone()) {
+                Ok(()) => {
+                    link.buffer.pop_front();
+                }
+                Err(err) if is_connection_loss(&err) => self.disconnect(link),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn disconnect(&self, link: &mut Link<Tr>) {
+        link.transport = None;
+        link.retry_at = Some(self.clock.now() + link.delay);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Link<Tr>> {
+        self.link.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<Tr, F> Transport for ReconnectingTransport<Tr, F>
+where
+    Tr: Transport,
+    Tr::Outgoing: Clone,
+    F: FnMut() -> Result<Tr>,
+{
+    type Outgoing = Tr::Outgoing;
+    type Incoming = Tr::Incoming;
+
+    /// Fails with [`Error::Disconnected`] only when disconnected with a full
+    /// buffer.
+    fn send(&self, msg: Self::Outgoing) -> Result<()> {
+        let mut link = self.lock();
+        self.reconnect_if_due(&mut link);
+        self.flush(&mut link)?;
+
+        if link.buffer.is_empty()
+            && let Some(transport) = &link.transport
+        {
+            match transport.send(msg.clone()) {
+                Err(err) if is_connection_loss(&err) => self.disconnect(&mut link),
+                result => return result,
+            }
+        }
+
+        if link.buffer.len() >= self.capacity {
+            return Err(Error::Disconnected {
+                endpoint: Endpoint::Sender,
+            });
+        }
+        link.buffer.push_back(msg);
+        Ok(())
+    }
+
+    /// Returns no messages while disconnected.
+    fn receive(&self) -> Result<Vec<Self::Incoming>> {
+        let mut link = self.lock();
+        self.reconnect_if_due(&mut link);
+        self.flush(&mut link)?;
+
+        let Some(transport) = &link.transport else {
+            return Ok(Vec::new());
+        };
+        match transport.receive() {
+            Err(err) if is_connection_loss(&err) => {
+                self.disconnect(&mut link);
+                Ok(Vec::new())
+            }
+            result => result,
+        }
+    }
+}
+
+impl<Tr, F> Debug for ReconnectingTransport<Tr, F>
+where
+    Tr: Transport,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let link = self.link.lock().unwrap_or_else(PoisonError::into_inner);
+
+        f.debug_struct("ReconnectingTransport")
+            .field("connected", &link.transport.is_some())
+            .field("buffered", &link.buffer.len())
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_connection_loss(err: &Error) -> bool {
+    matches!(err, Error::Transport(_) | Error::Disconnected { .. })
+}
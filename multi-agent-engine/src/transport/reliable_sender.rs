@@ -0,0 +1,148 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Transport;
+use multi_agent_engine_core::Result;
+use std::{
+    collections::BTreeMap,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// At-least-once delivery over a transport carrying `(sequence, message)`
+/// pairs.
+///
+/// Every message is numbered from 1 and kept in a retransmit buffer until
+/// the peer acknowledges it. Acknowledgements are cumulative: one
+/// [`ack`](Self::ack) clears every message up to the acknowledged sequence,
+/// so the peer does not need to answer each message individually. The peer
+/// must discard sequences it has already seen, since
+/// [`retransmit`](Self::retransmit) may deliver a message twice.
+///
+/// Without a [window](Self::with_window) the retransmit buffer grows for as
+/// long as the peer does not acknowledge; with one, sends block once that
+/// many messages are in flight, until an acknowledgement frees a slot.
+#[derive(Debug)]
+pub struct ReliableSender<Tr, T> {
+    transport: Tr,
+    state: Mutex<State<T>>,
+    acked: Condvar,
+    window: Option<usize>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    next: u64,
+    unacked: BTreeMap<u64, T>,
+}
+
+impl<Tr, T> ReliableSender<Tr, T>
+where
+    Tr: Transport<Outgoing = (u64, T)>,
+    T: Clone,
+{
+    pub fn new(transport: Tr) -> Self {
+        Self {
+            transport,
+            state: Mutex::new(State {
+                next: 1,
+                unacked: BTreeMap::new(),
+            }),
+            acked: Condvar::new(),
+            window: None,
+        }
+    }
+
+    /// Allows at most `window` unacknowledged messages in flight.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = Some(window.max(1));
+        self
+    }
+
+    pub fn inner(&self) -> &Tr {
+        &self.transport
+    }
+
+    /// Sends `msg` and returns its sequence number. The message stays
+    /// buffered even if this send fails, so a later retransmit covers it.
+    /// Blocks while the window is full.
+    pub fn send_reliable(&self, msg: T) -> Result<u64> {
+        let seq = {
+            let mut state = self.lock();
+            if let Some(window) = self.window {
+                state = self
+                    .acked
+                    .wait_while(state, |state| state.unacked.len() >= window)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            let seq = state.next;
+            state.next += 1;
+            state.unacked.insert(seq, msg.clone());
+            seq
+        };
+
+        self.transport.send((seq, msg))?;
+        Ok(seq)
+    }
+
+    /// Cumulatively acknowledges every message up to and including `up_to`.
+    pub fn ack(&self, up_to: u64) {
+        let mut state = self.lock();
+        state.unacked = state.unacked.split_off(&(up_to.saturating_add(1)));
+        self.acked.notify_all();
+    }
+
+    /// Resends every unacknowledged message in sequence order.
+    pub fn retransmit(&self) -> Result<usize> {
+        let pending: Vec<_> = self
+            .lock()
+            .unacked
+            .iter()
+            .map(|(seq, msg)| (*seq, msg.clone()))
+            .collect();
+        let count = pending.len();
+
+        for msg in pending {
+            self.transport.send(msg)?;
+        }
+        Ok(count)
+    }
+
+    /// Sequence numbers still waiting for an acknowledgement.
+    pub fn unacked(&self) -> Vec<u64> {
+        self.lock().unacked.keys().copied().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<Tr, T> Transport for ReliableSender<Tr, T>
+where
+    Tr: Transport<Outgoing = (u64, T)>,
+    T: Clone,
+{
+    type Outgoing = T;
+    type Incoming = Tr::Incoming;
+
+    fn send(&self, msg: T) -> Result<()> {
+        self.send_reliable(msg).map(|_| ())
+    }
+
+    fn receive(&self) -> Result<Vec<Self::Incoming>> {
+        self.transport.receive()
+    }
+}
@@ -0,0 +1,81 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Transport;
+use crate::{Clock, SystemClock};
+use multi_agent_engine_core::Result;
+use std::{sync::Arc, time::Duration};
+
+#[derive(Debug)]
+pub struct RetrySender<Tr> {
+    transport: Tr,
+    max_attempts: u32,
+    backoff: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl<Tr> RetrySender<Tr>
+where
+    Tr: Transport,
+    Tr::Outgoing: Clone,
+{
+    pub fn new(transport: Tr, max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            transport,
+            max_attempts: max_attempts.max(1),
+            backoff,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn inner(&self) -> &Tr {
+        &self.transport
+    }
+}
+
+impl<Tr> Transport for RetrySender<Tr>
+where
+    Tr: Transport,
+    Tr::Outgoing: Clone,
+{
+    type Outgoing = Tr::Outgoing;
+    type Incoming = Tr::Incoming;
+
+    fn send(&self, msg: Self::Outgoing) -> Result<()> {
+        let mut delay = self.backoff;
+
+        for _ in 1..self.max_attempts {
+            match self.transport.send(msg.clone()) {
+                Err(err) if err.is_retriable() => {
+                    self.clock.sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+
+        self.transport.send(msg)
+    }
+
+    fn receive(&self) -> Result<Vec<Self::Incoming>> {
+        self.transport.receive()
+    }
+}
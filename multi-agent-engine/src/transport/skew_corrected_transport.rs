@@ -0,0 +1,77 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{ClockSkew, Timestamped, Transport};
+use multi_agent_engine_core::Result;
+use std::sync::{Mutex, PoisonError};
+
+/// Wraps a transport of [`Timestamped`] messages and moves every incoming
+/// stamp from the peer's clock into the local one.
+///
+/// The correction is only as good as the [`ClockSkew`] estimate: stamps end
+/// up within half the measured round trip of the local clock, not exactly on
+/// it. Replace the estimate with [`set_skew`](Self::set_skew) as clocks drift
+/// apart during long runs.
+#[derive(Debug)]
+pub struct SkewCorrectedTransport<Tr> {
+    inner: Tr,
+    skew: Mutex<ClockSkew>,
+}
+
+impl<Tr> SkewCorrectedTransport<Tr> {
+    pub fn new(inner: Tr, skew: ClockSkew) -> Self {
+        Self {
+            inner,
+            skew: Mutex::new(skew),
+        }
+    }
+
+    pub fn skew(&self) -> ClockSkew {
+        *self.skew.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn set_skew(&self, skew: ClockSkew) {
+        *self.skew.lock().unwrap_or_else(PoisonError::into_inner) = skew;
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &Tr {
+        &self.inner
+    }
+}
+
+impl<Tr, I> Transport for SkewCorrectedTransport<Tr>
+where
+    Tr: Transport<Incoming = Timestamped<I>>,
+{
+    type Outgoing = Tr::Outgoing;
+    type Incoming = Timestamped<I>;
+
+    fn send(&self, msg: Self::Outgoing) -> Result<()> {
+        self.inner.send(msg)
+    }
+
+    fn receive(&self) -> Result<Vec<Timestamped<I>>> {
+        let skew = self.skew();
+
+        Ok(self
+            .inner
+            .receive()?
+            .into_iter()
+            .map(|msg| msg.corrected(&skew))
+            .collect())
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{ClockSkew, unix_nanos};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A message stamped with the sender's wall clock when it was sent.
+///
+/// The stamp is in nanoseconds since the Unix epoch on the sender's clock;
+/// once received over a [`SkewCorrectedTransport`](super::SkewCorrectedTransport)
+/// it has been moved into the receiver's clock domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Timestamped<T> {
+    pub sent_at_ns: u64,
+    pub message: T,
+}
+
+impl<T> Timestamped<T> {
+    pub fn now(message: T) -> Self {
+        Self {
+            sent_at_ns: unix_nanos(),
+            message,
+        }
+    }
+
+    pub fn corrected(self, skew: &ClockSkew) -> Self {
+        Self {
+            sent_at_ns: skew.to_local(self.sent_at_ns),
+            message: self.message,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::Codec;
+use multi_agent_engine_core::{Error, Result};
+use serde::{Serialize, de::DeserializeOwned};
+use std::io;
+
+/// A [`Codec`] prefixing every message encoded by `C` with a schema version
+/// byte, the wire counterpart of the version of an
+/// [`EventLog`](crate::record::EventLog) record.
+///
+/// Decoding a message written under another version fails with
+/// [`Error::SchemaMismatch`] instead of misreading it, so peers built from
+/// different message formats notice before acting on garbage. The prefix
+/// makes every message binary, even for text codecs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VersionedCodec<C> {
+    codec: C,
+    version: u8,
+}
+
+impl<C> VersionedCodec<C> {
+    pub const fn new(codec: C, version: u8) -> Self {
+        Self { codec, version }
+    }
+
+    #[inline]
+    pub fn schema_version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl<C: Codec> Codec for VersionedCodec<C> {
+    fn encode<T: Serialize>(&self, msg: &T) -> Result<Vec<u8>> {
+        let payload = self.codec.encode(msg)?;
+
+        let mut bytes = Vec::with_capacity(1 + payload.len());
+        bytes.push(self.version);
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        let Some((&found, payload)) = bytes.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "message without schema version",
+            )
+            .into());
+        };
+
+        if found != self.version {
+            return Err(Error::SchemaMismatch {
+                found,
+                expected: self.version,
+            });
+        }
+        self.codec.decode(payload)
+    }
+}
@@ -0,0 +1,401 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{BincodeCodec, ClockSkew, Codec, Transport, unix_nanos};
+use multi_agent_engine_core::{Endpoint, Error, Result};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    io,
+    marker::PhantomData,
+    net::TcpStream,
+    ops::RangeInclusive,
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
+use tungstenite::{Message, WebSocket, error::ProtocolError, http::Uri};
+
+/// A [`Transport`] speaking WebSocket (RFC 6455) over plain TCP.
+///
+/// The handshake is the standard HTTP/1.1 upgrade: [`connect`](Self::connect)
+/// sends the client request for a `ws://` URL and [`accept`](Self::accept)
+/// answers it on an already accepted stream. Once upgraded the socket is
+/// switched to non-blocking mode so that `receive` only drains what arrived.
+///
+/// Every outgoing message is encoded with the transport's [`Codec`],
+/// [`BincodeCodec`] unless replaced with [`with_codec`](Self::with_codec), and
+/// sent as one binary frame, or one text frame for text codecs such as
+/// [`JsonCodec`](super::JsonCodec). Incoming text and binary frames are both
+/// decoded as one message each, since browsers may send either; ping and pong
+/// frames are answered by the protocol layer and never surface. A frame that
+/// fails to decode ends the batch: `receive` returns the messages decoded
+/// before it and reports the error on its next call. `send` waits until the
+/// frame was handed to the socket. A close frame, sent with
+/// [`close`](Self::close), ends the transport; afterwards both directions
+/// report [`Error::Disconnected`].
+///
+/// To tag every message with a schema version, wrap the codec in a
+/// [`VersionedCodec`](super::VersionedCodec); a message of another version
+/// then fails to decode with [`Error::SchemaMismatch`].
+///
+/// Peers built from different message formats can agree on a protocol
+/// version first with [`connect_with_versions`](Self::connect_with_versions)
+/// and [`accept_with_versions`](Self::accept_with_versions). Both sides send
+/// the range of versions they support and settle on the highest one in
+/// common, or fail with [`Error::IncompatibleVersion`] before any message is
+/// exchanged. Once the agreed version is recent enough,
+/// [`connect_with_clock_sync`](Self::connect_with_clock_sync) and
+/// [`accept_with_clock_sync`](Self::accept_with_clock_sync) then trade
+/// wall-clock timestamps once to estimate the peer's
+/// [`clock_skew`](Self::clock_skew), which a
+/// [`SkewCorrectedTransport`](super::SkewCorrectedTransport) can use to move
+/// [`Timestamped`](super::Timestamped) messages into the local clock domain.
+#[derive(Debug)]
+pub struct WebSocketTransport<O, I, C = BincodeCodec> {
+    socket: Mutex<WebSocket<TcpStream>>,
+    undecoded: Mutex<Option<Error>>,
+    version: Option<u16>,
+    skew: Option<ClockSkew>,
+    codec: C,
+    _messages: PhantomData<fn(O) -> I>,
+}
+
+impl<O, I> WebSocketTransport<O, I> {
+    pub fn connect(url: &str) -> Result<Self> {
+        Self::from_socket(Self::client(url, None)?, None)
+    }
+
+    /// Like [`connect`](Self::connect), but tries to reach the listener up to
+    /// `attempts` times, for peers started before the other side is up. Waits
+    /// `backoff` after the first failure and twice as long after each next
+    /// one. Fails with [`Error::ConnectFailed`] carrying the last error; a
+    /// rejected WebSocket upgrade is not retried.
+    pub fn connect_with_retry(url: &str, attempts: u32, backoff: Duration) -> Result<Self> {
+        Self::from_socket(Self::client(url, Some((attempts.max(1), backoff)))?, None)
+    }
+
+    pub fn accept(stream: TcpStream) -> Result<Self> {
+        let socket = tungstenite::accept(stream).map_err(io::Error::other)?;
+
+        Self::from_socket(socket, None)
+    }
+
+    pub fn connect_with_versions(url: &str, versions: RangeInclusive<u16>) -> Result<Self> {
+        Self::versioned(Self::client(url, None)?, versions, None)
+    }
+
+    pub fn accept_with_versions(stream: TcpStream, versions: RangeInclusive<u16>) -> Result<Self> {
+        let socket = tungstenite::accept(stream).map_err(io::Error::other)?;
+
+        Self::versioned(socket, versions, None)
+    }
+
+    /// Like [`connect_with_versions`](Self::connect_with_versions), but when
+    /// the agreed version is `skew_since` or later also estimates the
+    /// [`clock_skew`](Self::clock_skew). Both peers must pass the same
+    /// `skew_since`, the version their protocol started exchanging clocks in.
+    pub fn connect_with_clock_sync(
+        url: &str,
+        versions: RangeInclusive<u16>,
+        skew_since: u16,
+    ) -> Result<Self> {
+        Self::versioned(Self::client(url, None)?, versions, Some(skew_since))
+    }
+
+    /// The accepting side of [`connect_with_clock_sync`](Self::connect_with_clock_sync).
+    pub fn accept_with_clock_sync(
+        stream: TcpStream,
+        versions: RangeInclusive<u16>,
+        skew_since: u16,
+    ) -> Result<Self> {
+        let socket = tungstenite::accept(stream).map_err(io::Error::other)?;
+
+        Self::versioned(socket, versions, Some(skew_since))
+    }
+
+    fn versioned(
+        mut socket: WebSocket<TcpStream>,
+        versions: RangeInclusive<u16>,
+        skew_since: Option<u16>,
+    ) -> Result<Self> {
+        let version = negotiate(&mut socket, versions)?;
+        // Peers on an older version do not take part in the exchange.
+        let skew = match skew_since {
+            Some(since) if version >= since => Some(estimate_skew(&mut socket)?),
+            _ => None,
+        };
+
+        Ok(Self {
+            skew,
+            ..Self::from_socket(socket, Some(version))?
+        })
+    }
+
+    fn client(url: &str, retry: Option<(u32, Duration)>) -> Result<WebSocket<TcpStream>> {
+        let uri: Uri = url.parse().map_err(io::Error::other)?;
+        let host = uri.host().unwrap_or("localhost");
+        let port = uri.port_u16().unwrap_or(80);
+
+        let stream = match retry {
+            None => TcpStream::connect((host, port))?,
+            Some((attempts, backoff)) => connect_retrying((host, port), attempts, backoff)?,
+        };
+        let (socket, _) = tungstenite::client(uri, stream).map_err(io::Error::other)?;
+
+        Ok(socket)
+    }
+
+    fn from_socket(socket: WebSocket<TcpStream>, version: Option<u16>) -> Result<Self> {
+        socket.get_ref().set_nodelay(true)?;
+        socket.get_ref().set_nonblocking(true)?;
+
+        Ok(Self {
+            socket: Mutex::new(socket),
+            undecoded: Mutex::new(None),
+            version,
+            skew: None,
+            codec: BincodeCodec,
+            _messages: PhantomData,
+        })
+    }
+}
+
+impl<O, I, C> WebSocketTransport<O, I, C> {
+    /// Switches the wire format. Both peers must use the same codec.
+    pub fn with_codec<D: Codec>(self, codec: D) -> WebSocketTransport<O, I, D> {
+        WebSocketTransport {
+            socket: self.socket,
+            undecoded: self.undecoded,
+            version: self.version,
+            skew: self.skew,
+            codec,
+            _messages: PhantomData,
+        }
+    }
+
+    /// The protocol version agreed on during the handshake, if one took place.
+    #[inline]
+    pub fn protocol_version(&self) -> Option<u16> {
+        self.version
+    }
+
+    /// How far the peer's clock was estimated to run ahead of the local one
+    /// during a [clock sync](Self::connect_with_clock_sync), if the agreed
+    /// version included one.
+    #[inline]
+    pub fn clock_skew(&self) -> Option<ClockSkew> {
+        self.skew
+    }
+
+    pub fn close(&self) -> Result<()> {
+        let mut socket = self.lock();
+
+        match socket.close(None).and_then(|()| socket.flush()) {
+            Ok(()) => Ok(()),
+            Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+            Err(err) => Err(map_error(err, Endpoint::Sender)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WebSocket<TcpStream>> {
+        self.socket.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<O, I, C> Transport for WebSocketTransport<O, I, C>
+where
+    O: Serialize,
+    I: DeserializeOwned,
+    C: Codec,
+{
+    type Outgoing = O;
+    type Incoming = I;
+
+    fn send(&self, msg: O) -> Result<()> {
+        let bytes = self.codec.encode(&msg)?;
+        let frame = if self.codec.is_text() {
+            Message::text(String::from_utf8(bytes).map_err(io::Error::other)?)
+        } else {
+            Message::binary(bytes)
+        };
+
+        let mut socket = self.lock();
+        let mut frame = frame;
+        loop {
+            match socket.write(frame) {
+                Ok(()) => break,
+                // The frame is buffered, the flush below writes it out.
+                Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                    break;
+                }
+                Err(tungstenite::Error::WriteBufferFull(rejected)) => {
+                    frame = *rejected;
+                    flush(&mut socket).map_err(|err| map_error(err, Endpoint::Sender))?;
+                }
+                Err(err) => return Err(map_error(err, Endpoint::Sender)),
+            }
+        }
+
+        flush(&mut socket).map_err(|err| map_error(err, Endpoint::Sender))
+    }
+
+    fn receive(&self) -> Result<Vec<I>> {
+        let mut socket = self.lock();
+        if let Some(err) = self
+            .undecoded
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        {
+            return Err(err);
+        }
+        let mut messages = Vec::new();
+
+        loop {
+            let payload = match socket.read() {
+                Ok(Message::Text(text)) => self.codec.decode(text.as_bytes()),
+                Ok(Message::Binary(bytes)) => self.codec.decode(&bytes),
+                Ok(Message::Close(_)) if !messages.is_empty() => return Ok(messages),
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                    return Ok(messages);
+                }
+                Err(err) if !messages.is_empty() && is_closed(&err) => return Ok(messages),
+                Err(err) => return Err(map_error(err, Endpoint::Receiver)),
+            };
+
+            match payload {
+                Ok(msg) => messages.push(msg),
+                Err(err) if !messages.is_empty() => {
+                    *self
+                        .undecoded
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner) = Some(err);
+                    return Ok(messages);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Flushes the socket, waiting while it would block.
+fn flush(socket: &mut WebSocket<TcpStream>) -> tungstenite::Result<()> {
+    loop {
+        match socket.flush() {
+            Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::yield_now();
+            }
+            flushed => return flushed,
+        }
+    }
+}
+
+fn connect_retrying(addr: (&str, u16), attempts: u32, backoff: Duration) -> Result<TcpStream> {
+    let mut delay = backoff;
+
+    for _ in 1..attempts {
+        if let Ok(stream) = TcpStream::connect(addr) {
+            return Ok(stream);
+        }
+        thread::sleep(delay);
+        delay = delay.saturating_mul(2);
+    }
+
+    TcpStream::connect(addr).map_err(|source| Error::ConnectFailed { attempts, source })
+}
+
+/// Runs while the socket is still blocking: sends the local range, waits for
+/// the remote one and picks the highest version both support.
+fn negotiate(socket: &mut WebSocket<TcpStream>, local: RangeInclusive<u16>) -> Result<u16> {
+    let offer = serde_json::to_string(&[*local.start(), *local.end()]).map_err(io::Error::from)?;
+    socket
+        .send(Message::text(offer))
+        .map_err(|err| map_error(err, Endpoint::Sender))?;
+
+    let [start, end]: [u16; 2] = loop {
+        let payload = match socket.read() {
+            Ok(Message::Text(text)) => serde_json::from_str(&text),
+            Ok(Message::Binary(bytes)) => serde_json::from_slice(&bytes),
+            Ok(_) => continue,
+            Err(err) => return Err(map_error(err, Endpoint::Receiver)),
+        };
+        break payload.map_err(io::Error::from)?;
+    };
+
+    let remote = start..=end;
+    let highest = (*local.end()).min(*remote.end());
+    if local.contains(&highest) && remote.contains(&highest) {
+        Ok(highest)
+    } else {
+        Err(Error::IncompatibleVersion { local, remote })
+    }
+}
+
+/// Runs right after [`negotiate`]: both peers send a ping stamped with their
+/// clock, answer the other's ping with the receive and reply times, and
+/// estimate the skew once their own ping is answered.
+fn estimate_skew(socket: &mut WebSocket<TcpStream>) -> Result<ClockSkew> {
+    let ping = serde_json::to_string(&[unix_nanos()]).map_err(io::Error::from)?;
+    socket
+        .send(Message::text(ping))
+        .map_err(|err| map_error(err, Endpoint::Sender))?;
+
+    let mut answered = false;
+    let mut skew = None;
+    while !answered || skew.is_none() {
+        let stamps: Vec<u64> = match socket.read() {
+            Ok(Message::Text(text)) => serde_json::from_str(&text),
+            Ok(Message::Binary(bytes)) => serde_json::from_slice(&bytes),
+            Ok(_) => continue,
+            Err(err) => return Err(map_error(err, Endpoint::Receiver)),
+        }
+        .map_err(io::Error::from)?;
+        let received = unix_nanos();
+
+        match stamps[..] {
+            [sent] => {
+                let pong = serde_json::to_string(&[sent, received, unix_nanos()])
+                    .map_err(io::Error::from)?;
+                socket
+                    .send(Message::text(pong))
+                    .map_err(|err| map_error(err, Endpoint::Sender))?;
+                answered = true;
+            }
+            [t0, t1, t2] => skew = Some(ClockSkew::from_exchange(t0, t1, t2, received)),
+            _ => return Err(io::Error::from(io::ErrorKind::InvalidData).into()),
+        }
+    }
+
+    Ok(skew.unwrap_or_default())
+}
+
+fn is_closed(err: &tungstenite::Error) -> bool {
+    matches!(
+        err,
+        tungstenite::Error::ConnectionClosed
+            | tungstenite::Error::AlreadyClosed
+            | tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+    )
+}
+
+fn map_error(err: tungstenite::Error, endpoint: Endpoint) -> Error {
+    match err {
+        err if is_closed(&err) => Error::Disconnected { endpoint },
+        tungstenite::Error::Io(err) => Error::Transport(err),
+        err => Error::Transport(io::Error::other(err)),
+    }
+}
@@ -0,0 +1,148 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{CommitOutcome, Shared};
+use multi_agent_engine_core::{AgentId, Error, Result};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+/// Changes a [`Shared`] value only once every participating agent agreed.
+///
+/// A proposer [`prepare`](Self::prepare)s the new state, each of the `voters`
+/// peers inspects it with [`proposal`](Self::proposal) and casts one
+/// [`vote`](Self::vote) under its own [`AgentId`]. The state is stored as soon as the last approval
+/// arrives and dropped at the first rejection, so no agent ever observes a
+/// transition the others refused. One round runs at a time.
+#[derive(Debug, Clone)]
+pub struct TwoPhaseCommit<T> {
+    shared: Shared<T>,
+    voters: usize,
+    round: Arc<(Mutex<Round<T>>, Condvar)>,
+}
+
+#[derive(Debug)]
+struct Round<T> {
+    proposal: Option<T>,
+    approvals: HashSet<AgentId>,
+    outcome: Option<CommitOutcome>,
+}
+
+impl<T> TwoPhaseCommit<T> {
+    pub fn new(shared: Shared<T>, voters: usize) -> Self {
+        Self {
+            shared,
+            voters,
+            round: Arc::new((
+                Mutex::new(Round {
+                    proposal: None,
+                    approvals: HashSet::new(),
+                    outcome: None,
+                }),
+                Condvar::new(),
+            )),
+        }
+    }
+
+    /// Opens a round for `state`, which commits at once when there are no
+    /// voters. Returns `false`, leaving the running round untouched, while
+    /// another proposal is still being voted on.
+    pub fn prepare(&self, state: T) -> bool {
+        let mut round = self.lock();
+        if round.proposal.is_some() {
+            return false;
+        }
+
+        round.proposal = Some(state);
+        round.approvals.clear();
+        round.outcome = None;
+        self.resolve(&mut round);
+        true
+    }
+
+    /// A copy of the proposal being voted on, for peers to validate.
+    pub fn proposal(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.lock().proposal.clone()
+    }
+
+    /// Casts `voter`'s vote on the running round. Ignored when no round is
+    /// running or when `voter` already approved it.
+    pub fn vote(&self, voter: AgentId, approve: bool) {
+        let mut round = self.lock();
+        if round.proposal.is_none() || round.approvals.contains(&voter) {
+            return;
+        }
+
+        if approve {
+            round.approvals.insert(voter);
+            self.resolve(&mut round);
+        } else {
+            round.proposal = None;
+            self.finish(&mut round, CommitOutcome::Aborted);
+        }
+    }
+
+    /// The outcome of the last finished round, `None` while one is running or
+    /// before the first.
+    pub fn outcome(&self) -> Option<CommitOutcome> {
+        self.lock().outcome
+    }
+
+    /// Blocks until the running round finished and returns its outcome.
+    pub fn wait(&self) -> Option<CommitOutcome> {
+        let (_, finished) = &*self.round;
+        let round = finished
+            .wait_while(self.lock(), |round| round.proposal.is_some())
+            .unwrap_or_else(PoisonError::into_inner);
+        round.outcome
+    }
+
+    /// Like [`wait`](Self::wait), but fails with [`Error::Timeout`] when the
+    /// round is still running after `timeout`.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<Option<CommitOutcome>> {
+        let (_, finished) = &*self.round;
+        let (round, waited) = finished
+            .wait_timeout_while(self.lock(), timeout, |round| round.proposal.is_some())
+            .unwrap_or_else(PoisonError::into_inner);
+        if waited.timed_out() {
+            return Err(Error::Timeout { duration: timeout });
+        }
+        Ok(round.outcome)
+    }
+
+    fn resolve(&self, round: &mut Round<T>) {
+        if round.approvals.len() >= self.voters
+            && let Some(state) = round.proposal.take()
+        {
+            self.shared.store(state);
+            self.finish(round, CommitOutcome::Committed);
+        }
+    }
+
+    fn finish(&self, round: &mut Round<T>, outcome: CommitOutcome) {
+        round.outcome = Some(outcome);
+        self.round.1.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Round<T>> {
+        self.round.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
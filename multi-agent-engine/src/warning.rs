@@ -0,0 +1,62 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine_core::AgentId;
+use std::fmt::{self, Display, Formatter};
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    Affinity {
+        agent: AgentId,
+        core: usize,
+    },
+    Priority {
+        agent: AgentId,
+        reason: String,
+    },
+    MessageSize {
+        type_name: &'static str,
+        size: usize,
+        max: usize,
+    },
+    /// Reported by an agent through [`AgentContext::report`](crate::AgentContext::report).
+    Reported {
+        agent: AgentId,
+        message: String,
+    },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Affinity { agent, core } => write!(f, "failed to pin {agent} to core {core}"),
+            Self::Priority { agent, reason } => {
+                write!(f, "failed to raise {agent} thread priority: {reason}")
+            }
+            Self::MessageSize {
+                type_name,
+                size,
+                max,
+            } => write!(
+                f,
+                "{type_name} takes {size} bytes, over the {max} byte message limit; \
+                 consider boxing its large variants"
+            ),
+            Self::Reported { agent, message } => write!(f, "{agent}: {message}"),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::Warning;
+use crossbeam_channel::{Receiver, Sender};
+use std::time::Duration;
+
+/// Collects the non-fatal warnings agents [report](crate::AgentContext::report)
+/// while they keep running. Obtained from
+/// [`MultiAgentEngine::warning_monitor`](crate::MultiAgentEngine::warning_monitor).
+///
+/// Clones share one channel, so each warning is collected by one of them.
+#[derive(Debug, Clone)]
+pub struct WarningMonitor {
+    sender: Sender<Warning>,
+    receiver: Receiver<Warning>,
+}
+
+impl WarningMonitor {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        Self { sender, receiver }
+    }
+
+    pub(crate) fn sender(&self) -> Sender<Warning> {
+        self.sender.clone()
+    }
+
+    /// Takes every warning reported so far, in order.
+    pub fn drain(&self) -> Vec<Warning> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Waits up to `timeout` for the next warning.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Warning> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+}
@@ -0,0 +1,233 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Agents shared by several test targets; each target uses only a subset.
+
+#![allow(dead_code)]
+
+use multi_agent_engine::{
+    AgentContext, AgentId, CancellationToken, Controller, Endpoint, Error, MultiAgentEngine,
+    Result, Shared, Simulator, message,
+};
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+pub struct PingController {
+    pub sender: message::Sender<u32>,
+}
+
+impl Controller for PingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.sender.send(42)
+    }
+}
+
+pub struct PongSimulator {
+    pub receiver: message::Receiver<u32>,
+}
+
+impl Simulator for PongSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        assert_eq!(self.receiver.recv_batch(1)?, vec![42]);
+        Ok(())
+    }
+}
+
+pub struct SpinningController {
+    pub stop: Arc<AtomicBool>,
+}
+
+impl Controller for SpinningController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while !self.stop.load(Ordering::Relaxed) {
+            thread::yield_now();
+        }
+        Ok(())
+    }
+}
+
+pub struct IdleSimulator;
+
+impl Simulator for IdleSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct PanickingSimulator;
+
+impl Simulator for PanickingSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        panic!("simulation diverged");
+    }
+}
+
+pub struct CancellableController {
+    pub token: CancellationToken,
+}
+
+impl Controller for CancellableController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+pub struct CancellableSimulator {
+    pub token: CancellationToken,
+}
+
+impl Simulator for CancellableSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+pub fn cancellable_engine() -> MultiAgentEngine<CancellableController, CancellableSimulator> {
+    let token = CancellationToken::new();
+
+    let controller = CancellableController {
+        token: token.clone(),
+    };
+    let simulator = CancellableSimulator {
+        token: token.clone(),
+    };
+
+    MultiAgentEngine::new(controller, simulator).with_cancellation_token(token)
+}
+
+pub struct FailingController;
+
+impl Controller for FailingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Err(Error::Disconnected {
+            endpoint: Endpoint::Sender,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PhysicsError {
+    pub step: u32,
+}
+
+impl Display for PhysicsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "physics diverged at step {}", self.step)
+    }
+}
+
+impl error::Error for PhysicsError {}
+
+pub struct IdleController;
+
+impl Controller for IdleController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct AccumulatingSimulator {
+    pub receiver: message::Receiver<u32>,
+    pub total: Shared<u32>,
+    pub fail_on: Option<u32>,
+}
+
+impl Simulator for AccumulatingSimulator {
+    type Error = PhysicsError;
+
+    fn run(self) -> std::result::Result<(), PhysicsError> {
+        while let Ok(value) = self.receiver.recv_blocking() {
+            if self.fail_on == Some(value) {
+                return Err(PhysicsError { step: value });
+            }
+            self.total.store(**self.total.load() + value);
+        }
+        Ok(())
+    }
+}
+
+/// An agent running `f` with its context, for tests whose agent has no state
+/// beyond what the closure captures.
+pub struct WithContext<F>(F);
+
+pub fn with_context<F>(f: F) -> WithContext<F>
+where
+    F: FnOnce(&AgentContext) -> Result<()>,
+{
+    WithContext(f)
+}
+
+impl<F> Controller for WithContext<F>
+where
+    F: FnOnce(&AgentContext) -> Result<()>,
+{
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        (self.0)(&AgentContext::new(AgentId::CONTROLLER))
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        (self.0)(ctx)
+    }
+}
+
+impl<F> Simulator for WithContext<F>
+where
+    F: FnOnce(&AgentContext) -> Result<()>,
+{
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        (self.0)(&AgentContext::new(AgentId::SIMULATOR))
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        (self.0)(ctx)
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{AdaptiveRate, MockClock};
+use std::time::Duration;
+
+#[test]
+fn sustained_overruns_lower_rate_to_floor() {
+    let mut rate = AdaptiveRate::new(10.0, 60.0).with_window(4);
+    let mut previous = rate.rate();
+
+    for _ in 0..10 {
+        for _ in 0..4 {
+            rate.record_frame(Duration::from_millis(250));
+        }
+        assert!(rate.rate() <= previous);
+        previous = rate.rate();
+    }
+
+    assert_eq!(rate.rate(), 10.0);
+}
+
+#[test]
+fn occasional_overrun_keeps_rate() {
+    let mut rate = AdaptiveRate::new(10.0, 60.0).with_window(4);
+
+    for frame in [20, 5, 20, 20, 5, 20] {
+        rate.record_frame(Duration::from_millis(frame));
+    }
+
+    assert_eq!(rate.rate(), 60.0);
+}
+
+#[test]
+fn headroom_raises_rate_back_to_ceiling() {
+    let mut rate = AdaptiveRate::new(10.0, 60.0).with_window(2);
+    for _ in 0..40 {
+        rate.record_frame(Duration::from_millis(500));
+    }
+    assert_eq!(rate.rate(), 10.0);
+
+    for _ in 0..100 {
+        rate.record_frame(Duration::from_millis(1));
+    }
+
+    assert_eq!(rate.rate(), 60.0);
+}
+
+#[test]
+fn tick_measures_frames_with_injected_clock() {
+    let clock = MockClock::new();
+    let mut rate = AdaptiveRate::new(5.0, 50.0)
+        .with_window(3)
+        .with_clock(clock.clone());
+
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(100));
+        rate.tick();
+    }
+
+    assert!(rate.rate() < 50.0);
+    assert_eq!(rate.interval(), Duration::from_secs_f64(1.0 / rate.rate()));
+}
@@ -0,0 +1,655 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{
+    FailingController, IdleController, IdleSimulator, PingController, WithContext, with_context,
+};
+use multi_agent_engine::{
+    AgentContext, AgentId, Clock, Controller, Endpoint, EngineEvent, Error, MockClock,
+    MultiAgentEngine, RateRelation, Result, Shared, Simulator, Warning, message,
+};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+fn context_agent(
+    frames: u64,
+    wait_for: u64,
+    seen: Arc<Mutex<Vec<(AgentId, u64)>>>,
+) -> WithContext<impl FnOnce(&AgentContext) -> Result<()>> {
+    with_context(move |ctx| {
+        for _ in 0..frames {
+            ctx.advance_frame();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while ctx.frame() < wait_for && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        seen.lock().unwrap().push((ctx.agent(), ctx.frame()));
+        Ok(())
+    })
+}
+
+#[test]
+fn agents_read_their_id_and_frame_from_the_context() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let controller = context_agent(3, 0, Arc::clone(&seen));
+    let simulator = context_agent(0, 3, Arc::clone(&seen));
+
+    MultiAgentEngine::new(controller, simulator).run().unwrap();
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, [(AgentId::CONTROLLER, 3), (AgentId::SIMULATOR, 3)]);
+}
+
+fn startup_agent(
+    ready: bool,
+    wait_for_cancel: bool,
+) -> WithContext<impl FnOnce(&AgentContext) -> Result<()>> {
+    with_context(move |ctx| {
+        if ready {
+            ctx.mark_ready();
+        }
+        while wait_for_cancel && !ctx.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn agent_exiting_before_ready_cancels_its_peer() {
+    let result = MultiAgentEngine::new(startup_agent(false, false), startup_agent(true, true))
+        .with_startup_timeout(Duration::from_secs(5))
+        .spawn()
+        .join_timeout(Duration::from_secs(1));
+
+    assert!(matches!(
+        result,
+        Err(Error::StartupFailed {
+            agent: AgentId::CONTROLLER
+        })
+    ));
+}
+
+#[test]
+fn agent_not_ready_within_startup_timeout_fails_the_run() {
+    let result = MultiAgentEngine::new(startup_agent(true, true), startup_agent(false, true))
+        .with_startup_timeout(Duration::from_millis(20))
+        .spawn()
+        .join_timeout(Duration::from_secs(5));
+
+    assert!(matches!(
+        result,
+        Err(Error::StartupFailed {
+            agent: AgentId::SIMULATOR
+        })
+    ));
+}
+
+#[test]
+fn startup_failure_keeps_the_error_the_agent_returned() {
+    let result = MultiAgentEngine::new(FailingController, startup_agent(true, true))
+        .with_startup_timeout(Duration::from_secs(5))
+        .spawn()
+        .join();
+
+    let Err(Error::Multiple(errors)) = result else {
+        panic!("expected the startup failure and the agent error, got {result:?}");
+    };
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            Error::StartupFailed {
+                agent: AgentId::CONTROLLER
+            },
+            Error::Disconnected {
+                endpoint: Endpoint::Sender
+            }
+        ]
+    ));
+}
+
+#[test]
+fn ready_agents_start_normally() {
+    let result = MultiAgentEngine::new(startup_agent(true, false), startup_agent(true, false))
+        .with_startup_timeout(Duration::from_secs(5))
+        .run();
+
+    assert!(result.is_ok());
+}
+
+struct StrategySimulator {
+    receiver: message::Receiver<u32>,
+    observed: Shared<Option<message::ReceiveStrategy>>,
+}
+
+impl Simulator for StrategySimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.observed.store(Some(self.receiver.receive_strategy()));
+        assert_eq!(self.receiver.wait(), vec![42]);
+        Ok(())
+    }
+}
+
+#[test]
+fn receivers_inherit_engine_receive_strategy() {
+    let strategy = message::ReceiveStrategy::Spin {
+        max: Duration::from_millis(5),
+    };
+    let (sender, receiver) = message::Queue::channel();
+    let observed = Shared::new(None);
+    let simulator = StrategySimulator {
+        receiver,
+        observed: observed.clone(),
+    };
+
+    let start = Instant::now();
+    MultiAgentEngine::new(PingController { sender }, simulator)
+        .with_receive_strategy(strategy)
+        .run()
+        .unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(**observed.load(), Some(strategy));
+}
+
+#[test]
+fn batched_frame_advances_sum_to_exact_count() {
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+
+    let workers: Vec<_> = (0..16)
+        .map(|_| {
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                let mut batch = ctx.frame_batch().with_flush_every(64);
+                for _ in 0..10_000 {
+                    batch.advance();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    assert_eq!(ctx.frame(), 16 * 10_000);
+
+    let mut batch = ctx.frame_batch();
+    batch.advance();
+    assert_eq!(ctx.frame(), 16 * 10_000);
+    assert_eq!(batch.flush(), 16 * 10_000 + 1);
+}
+
+struct DrainingSimulator {
+    receiver: message::Receiver<u32>,
+    local: message::Receiver<u32>,
+    batches: Shared<Vec<usize>>,
+}
+
+impl Simulator for DrainingSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let mut batches = Vec::new();
+        loop {
+            let batch = self.receiver.receive();
+            if batch.is_empty() {
+                break;
+            }
+            batches.push(batch.len());
+        }
+        batches.push(self.local.receive().len());
+        self.batches.store(batches);
+        Ok(())
+    }
+}
+
+#[test]
+fn engine_drain_cap_bounds_each_receive() {
+    let (sender, receiver) = message::Queue::channel();
+    let (local_sender, local) = message::Queue::channel();
+    for i in 0..10 {
+        sender.send(i).unwrap();
+        local_sender.send(i).unwrap();
+    }
+    let batches = Shared::new(Vec::new());
+    let simulator = DrainingSimulator {
+        receiver,
+        local: local.with_drain_at_most(5),
+        batches: batches.clone(),
+    };
+
+    MultiAgentEngine::new(IdleController, simulator)
+        .with_max_drain_per_frame(3)
+        .run()
+        .unwrap();
+
+    assert_eq!(**batches.load(), vec![3, 3, 3, 1, 5]);
+}
+
+#[test]
+fn start_barrier_holds_sends_until_both_agents_listen() {
+    let (sender, receiver) = message::Queue::channel();
+    let listening = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let controller = with_context({
+        let listening = Arc::clone(&listening);
+        move |ctx| {
+            if ctx.wait_for_start().is_continue() {
+                sender.send(listening.load(Ordering::Acquire))?;
+            }
+            let _ = ctx.wait_for_stop();
+            Ok(())
+        }
+    });
+    let simulator = with_context({
+        let received = Arc::clone(&received);
+        move |ctx| {
+            thread::sleep(Duration::from_millis(20));
+            listening.store(true, Ordering::Release);
+
+            if ctx.wait_for_start().is_continue() {
+                received.lock().unwrap().push(receiver.recv_blocking()?);
+            }
+            let _ = ctx.wait_for_stop();
+            Ok(())
+        }
+    });
+
+    MultiAgentEngine::new(controller, simulator)
+        .with_start_barrier()
+        .with_stop_barrier()
+        .spawn()
+        .join_timeout(Duration::from_secs(5))
+        .unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![true]);
+}
+
+#[test]
+fn checkpoint_interrupts_a_long_frame_and_keeps_the_heartbeat_fresh() {
+    const STEPS: usize = 10_000;
+    let steps = Arc::new(AtomicUsize::new(0));
+    let controller = with_context({
+        let steps = Arc::clone(&steps);
+        move |ctx| {
+            for _ in 0..STEPS {
+                if ctx.checkpoint().is_break() {
+                    break;
+                }
+                thread::sleep(Duration::from_micros(100));
+                steps.fetch_add(1, Ordering::Relaxed);
+            }
+            ctx.advance_frame();
+            Ok(())
+        }
+    });
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .with_heartbeat_timeout(Duration::from_millis(20))
+        .spawn();
+    thread::sleep(Duration::from_millis(50));
+    assert!(handle.health().controller.healthy);
+
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+    let steps = steps.load(Ordering::Relaxed);
+    assert!(steps > 0);
+    assert!(steps < STEPS);
+}
+
+struct IdleAgent {
+    receiver: message::Receiver<u32>,
+    clock: MockClock,
+    waited: Arc<Mutex<Option<Duration>>>,
+}
+
+impl IdleAgent {
+    fn new(receiver: message::Receiver<u32>) -> Self {
+        let clock = MockClock::new();
+
+        Self {
+            receiver: receiver.with_clock(clock.clone()),
+            clock,
+            waited: Arc::default(),
+        }
+    }
+
+    fn wait_idle(self) -> Result<()> {
+        let start = self.clock.now();
+        assert_eq!(self.receiver.recv_default()?, None);
+        *self.waited.lock().unwrap() = Some(self.clock.now() - start);
+
+        Ok(())
+    }
+}
+
+impl Controller for IdleAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.wait_idle()
+    }
+}
+
+impl Simulator for IdleAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.wait_idle()
+    }
+}
+
+#[test]
+fn per_agent_recv_timeouts_apply_to_their_receivers() {
+    let (_controller_sender, controller_receiver) = message::Queue::channel();
+    let (_simulator_sender, simulator_receiver) = message::Queue::channel();
+    let controller = IdleAgent::new(controller_receiver);
+    let simulator = IdleAgent::new(simulator_receiver);
+    let (controller_waited, simulator_waited) =
+        (controller.waited.clone(), simulator.waited.clone());
+
+    MultiAgentEngine::new(controller, simulator)
+        .with_controller_recv_timeout(Duration::from_millis(50))
+        .with_simulator_recv_timeout(Duration::from_millis(200))
+        .run()
+        .unwrap();
+
+    assert_eq!(
+        *controller_waited.lock().unwrap(),
+        Some(Duration::from_millis(50))
+    );
+    assert_eq!(
+        *simulator_waited.lock().unwrap(),
+        Some(Duration::from_millis(200))
+    );
+}
+
+#[test]
+fn receiver_default_timeout_overrides_the_engine() {
+    let (_sender, receiver) = message::Queue::channel::<u32>();
+    let clock = MockClock::new();
+    let receiver = receiver
+        .with_clock(clock.clone())
+        .with_default_timeout(Duration::from_millis(30));
+
+    assert_eq!(receiver.recv_default().unwrap(), None);
+    assert_eq!(clock.elapsed(), Duration::from_millis(30));
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_millis(5)).unwrap(),
+        None
+    );
+    assert_eq!(clock.elapsed(), Duration::from_millis(35));
+}
+
+#[test]
+fn rate_relation_runs_simulator_once_per_two_controller_frames() {
+    let relation = RateRelation::simulator_every(2);
+    let frames = Arc::new(AtomicUsize::new(0));
+
+    let controller = with_context({
+        let relation = relation.clone();
+        move |ctx| {
+            for _ in 0..60 {
+                if relation.tick(ctx).is_break() {
+                    break;
+                }
+            }
+            relation.close();
+            Ok(())
+        }
+    });
+    let simulator = with_context({
+        let (relation, frames) = (relation.clone(), frames.clone());
+        move |ctx| {
+            while relation.tick(ctx).is_continue() {
+                frames.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+    });
+    MultiAgentEngine::new(controller, simulator).run().unwrap();
+
+    assert_eq!(frames.load(Ordering::SeqCst), 30);
+    assert_eq!(relation.frames(AgentId::CONTROLLER), 60);
+    assert_eq!(relation.frames(AgentId::SIMULATOR), 30);
+    assert_eq!(
+        relation.interval(AgentId::SIMULATOR, Duration::from_millis(16)),
+        Duration::from_millis(32)
+    );
+}
+
+#[test]
+fn frames_completed_are_counted_per_agent() {
+    let relation = RateRelation::simulator_every(2);
+
+    let controller = with_context({
+        let relation = relation.clone();
+        move |ctx| {
+            for _ in 0..20 {
+                if relation.tick(ctx).is_break() {
+                    break;
+                }
+                ctx.complete_frame();
+            }
+            relation.close();
+            Ok(())
+        }
+    });
+    let simulator = with_context(move |ctx| {
+        while relation.tick(ctx).is_continue() {
+            ctx.complete_frame();
+        }
+        Ok(())
+    });
+    let mut handle = MultiAgentEngine::new(controller, simulator).spawn();
+
+    let joined = loop {
+        if let Some(joined) = handle.try_join() {
+            break joined;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    joined.unwrap();
+    assert_eq!(handle.frames_completed(AgentId::CONTROLLER), 20);
+    assert_eq!(handle.frames_completed(AgentId::SIMULATOR), 10);
+}
+
+#[test]
+fn advance_frame_counts_as_a_completed_frame() {
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+
+    ctx.advance_frame();
+    ctx.advance_frame();
+    assert_eq!(ctx.complete_frame(), 3);
+    assert_eq!(ctx.frames_completed(), 3);
+    assert_eq!(ctx.frame(), 2);
+}
+
+#[test]
+fn ordered_start_delivers_controller_hello_first() {
+    for _ in 0..50 {
+        let (hello, greetings) = message::Queue::channel();
+
+        let controller = with_context({
+            let hello = hello.clone();
+            move |ctx| {
+                if ctx.wait_for_start().is_break() {
+                    return Ok(());
+                }
+                hello.send(AgentId::CONTROLLER)?;
+                ctx.release_start();
+                hello.send(AgentId::CONTROLLER)
+            }
+        });
+        let simulator = with_context(move |ctx| {
+            if ctx.wait_for_start().is_break() {
+                return Ok(());
+            }
+            hello.send(AgentId::SIMULATOR)
+        });
+        MultiAgentEngine::new(controller, simulator)
+            .with_ordered_start()
+            .run()
+            .unwrap();
+
+        let greetings = greetings.receive();
+        assert_eq!(greetings.len(), 3);
+        assert_eq!(greetings[0], AgentId::CONTROLLER);
+    }
+}
+
+struct PlainPingController {
+    ping: message::Sender<u32>,
+    pong: message::Receiver<u32>,
+}
+
+impl Controller for PlainPingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.ping.send(1)?;
+        self.pong.recv_timeout(Duration::from_secs(5)).map(drop)
+    }
+}
+
+#[test]
+fn ordered_start_releases_a_plain_run_controller_at_its_first_send() {
+    let (ping, ping_receiver) = message::Queue::channel();
+    let (pong, pong_receiver) = message::Queue::channel();
+
+    let simulator = with_context(move |ctx| {
+        if ctx.wait_for_start().is_break() {
+            return Ok(());
+        }
+        let ping = ping_receiver.recv_blocking()?;
+        pong.send(ping + 1)
+    });
+    MultiAgentEngine::new(
+        PlainPingController {
+            ping,
+            pong: pong_receiver,
+        },
+        simulator,
+    )
+    .with_ordered_start()
+    .run()
+    .unwrap();
+}
+
+const SIMULATOR_SETUP: Duration = Duration::from_millis(100);
+
+fn quick_start(ctx: &AgentContext) -> Result<()> {
+    let _ = ctx.checkpoint();
+    Ok(())
+}
+
+#[derive(Default)]
+struct SlowStartupLog {
+    agents: Arc<Mutex<Vec<AgentId>>>,
+}
+
+impl multi_agent_engine::Observer for SlowStartupLog {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        if let EngineEvent::SlowStartup { agent, latency } = event {
+            assert!(latency > Duration::from_millis(50));
+            self.agents.lock().unwrap().push(agent);
+        }
+    }
+}
+
+#[test]
+fn startup_latency_is_reported_per_agent() {
+    let log = SlowStartupLog::default();
+    let slow = log.agents.clone();
+    let simulator = with_context(|ctx| {
+        thread::sleep(SIMULATOR_SETUP);
+        quick_start(ctx)
+    });
+    let engine = MultiAgentEngine::new(with_context(quick_start), simulator)
+        .with_slow_startup_threshold(Duration::from_millis(50))
+        .with_observer(log);
+    let report = engine.startup_report();
+
+    engine.run().unwrap();
+
+    let controller = report.startup_latency(AgentId::CONTROLLER).unwrap();
+    let simulator = report.startup_latency(AgentId::SIMULATOR).unwrap();
+    assert!(simulator >= SIMULATOR_SETUP, "{simulator:?}");
+    assert!(controller < simulator);
+    assert_eq!(*slow.lock().unwrap(), [AgentId::SIMULATOR]);
+}
+
+#[test]
+fn startup_latency_is_recorded_at_the_first_frame_advance() {
+    let simulator = with_context(|ctx| {
+        thread::sleep(SIMULATOR_SETUP);
+        ctx.advance_frame();
+        Ok(())
+    });
+    let engine = MultiAgentEngine::new(with_context(quick_start), simulator);
+    let report = engine.startup_report();
+
+    engine.run().unwrap();
+
+    let simulator = report.startup_latency(AgentId::SIMULATOR).unwrap();
+    assert!(simulator >= SIMULATOR_SETUP, "{simulator:?}");
+}
+
+#[test]
+fn reported_warnings_reach_the_monitor_without_stopping_the_agent() {
+    let frames = Arc::new(AtomicUsize::new(0));
+    let controller = with_context({
+        let frames = frames.clone();
+        move |ctx| {
+            for frame in 0..10 {
+                if frame == 2 || frame == 5 {
+                    ctx.report(Warning::Reported {
+                        agent: ctx.agent(),
+                        message: format!("sensor dropout at frame {frame}"),
+                    });
+                }
+                frames.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+    });
+    let engine = MultiAgentEngine::new(controller, IdleSimulator);
+    let monitor = engine.warning_monitor();
+
+    engine.run().unwrap();
+
+    assert_eq!(frames.load(Ordering::SeqCst), 10);
+    assert_eq!(
+        monitor.drain(),
+        [2, 5].map(|frame| Warning::Reported {
+            agent: AgentId::CONTROLLER,
+            message: format!("sensor dropout at frame {frame}"),
+        })
+    );
+    assert!(monitor.is_empty());
+}
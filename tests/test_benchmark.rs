@@ -0,0 +1,28 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::benchmark;
+
+#[test]
+fn ping_pong_reports_ordered_percentiles_over_all_iterations() {
+    let report = benchmark::ping_pong(200).unwrap();
+
+    assert_eq!(report.iterations, 200);
+    assert!(report.min > std::time::Duration::ZERO);
+    assert!(report.min <= report.median);
+    assert!(report.median <= report.p99);
+    assert!(report.p99 <= report.max);
+}
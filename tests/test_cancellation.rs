@@ -0,0 +1,301 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{IdleController, IdleSimulator, PanickingSimulator, cancellable_engine, with_context};
+use multi_agent_engine::{
+    CancellationToken, Controller, Error, MultiAgentEngine, Result, Runtime, Shared,
+    ShutdownReason, Simulator, message,
+};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn cancellation_token_shuts_agents_down_gracefully() {
+    let engine = cancellable_engine();
+    let token = engine.cancellation_token();
+
+    let handle = engine.spawn();
+    thread::sleep(Duration::from_millis(10));
+    token.cancel();
+
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+struct LoopingAgent {
+    token: CancellationToken,
+    runs: Arc<AtomicUsize>,
+}
+
+impl LoopingAgent {
+    fn run_until_cancelled(self) -> Result<()> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+impl Controller for LoopingAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.run_until_cancelled()
+    }
+}
+
+impl Simulator for LoopingAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.run_until_cancelled()
+    }
+}
+
+#[test]
+fn nested_engine_runs_as_simulator_and_inherits_cancellation() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let agent = |token: &CancellationToken| LoopingAgent {
+        token: token.clone(),
+        runs: runs.clone(),
+    };
+
+    let inner_token = CancellationToken::new();
+    let inner = MultiAgentEngine::new(agent(&inner_token), agent(&inner_token))
+        .with_cancellation_token(inner_token);
+
+    let outer_token = CancellationToken::new();
+    let handle = MultiAgentEngine::new(agent(&outer_token), inner)
+        .with_cancellation_token(outer_token.clone())
+        .spawn();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while runs.load(Ordering::SeqCst) < 3 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+    outer_token.cancel();
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn child_token_follows_parent_but_not_the_reverse() {
+    let parent = CancellationToken::new();
+    let child = parent.child_token();
+
+    child.cancel();
+    assert!(!parent.is_cancelled());
+
+    let child = parent.child_token();
+    parent.cancel();
+    assert!(child.is_cancelled());
+}
+
+#[test]
+fn nested_engine_rejects_a_second_link_and_cycles() {
+    let token = CancellationToken::new();
+    let mut engine =
+        MultiAgentEngine::new(IdleController, IdleSimulator).with_cancellation_token(token.clone());
+
+    let own = token.clone();
+    assert!(matches!(
+        Simulator::link_cancellation(&mut engine, &own),
+        Err(Error::CancellationCycle)
+    ));
+    let descendant = token.child_token().child_token();
+    assert!(matches!(
+        Simulator::link_cancellation(&mut engine, &descendant),
+        Err(Error::CancellationCycle)
+    ));
+
+    let parent = CancellationToken::new();
+    assert!(Simulator::link_cancellation(&mut engine, &parent).is_ok());
+    assert!(matches!(
+        Simulator::link_cancellation(&mut engine, &CancellationToken::new()),
+        Err(Error::AlreadyLinked)
+    ));
+
+    parent.cancel();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn runtime_join_all_waits_for_every_engine() {
+    let runtime = Runtime::new();
+    let runs = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicUsize::new(0));
+
+    for delay in [10, 30] {
+        let finished = finished.clone();
+        let controller = LoopingAgent {
+            token: CancellationToken::new(),
+            runs: runs.clone(),
+        };
+        let token = controller.token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay));
+            finished.fetch_add(1, Ordering::SeqCst);
+            token.cancel();
+        });
+        runtime.spawn(MultiAgentEngine::new(controller, IdleSimulator));
+    }
+    assert_eq!(runtime.len(), 2);
+
+    runtime.join_all().unwrap();
+
+    assert_eq!(finished.load(Ordering::SeqCst), 2);
+    assert!(runtime.is_empty());
+}
+
+#[test]
+fn runtime_shutdown_all_cancels_every_engine() {
+    let runtime = Runtime::new();
+
+    for _ in 0..2 {
+        runtime.spawn(cancellable_engine());
+    }
+    runtime.shutdown_all();
+
+    assert!(runtime.join_all().is_ok());
+}
+
+#[cfg(feature = "ctrlc")]
+#[test]
+fn ctrlc_handler_installs_on_the_engine_token() {
+    let engine = cancellable_engine();
+    engine.install_ctrlc_handler().unwrap();
+
+    let token = engine.cancellation_token();
+    let handle = engine.spawn();
+    token.cancel();
+
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn cancellation_carries_first_shutdown_reason() {
+    for reason in [
+        ShutdownReason::Normal,
+        ShutdownReason::Error,
+        ShutdownReason::Timeout,
+        ShutdownReason::Interrupt,
+    ] {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        assert_eq!(child.reason(), None);
+
+        parent.cancel_with(reason);
+        parent.cancel_with(ShutdownReason::Normal);
+
+        assert_eq!(parent.reason(), Some(reason));
+        assert_eq!(child.reason(), Some(reason));
+    }
+}
+
+struct ReasonController {
+    token: CancellationToken,
+    observed: Shared<Option<ShutdownReason>>,
+}
+
+impl Controller for ReasonController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        self.observed.store(self.token.reason());
+        Ok(())
+    }
+
+    fn link_cancellation(&mut self, parent: &CancellationToken) -> Result<()> {
+        self.token = parent.clone();
+        Ok(())
+    }
+}
+
+#[test]
+fn agent_reads_error_reason_when_peer_panics() {
+    let observed = Shared::new(None);
+    let controller = ReasonController {
+        token: CancellationToken::new(),
+        observed: observed.clone(),
+    };
+
+    let result = MultiAgentEngine::new(controller, PanickingSimulator).run();
+
+    assert!(matches!(result, Err(Error::AgentPanic { .. })));
+    assert_eq!(**observed.load(), Some(ShutdownReason::Error));
+}
+
+#[test]
+fn checkpoint_breaks_on_the_iteration_after_cancellation() {
+    let iterations = Shared::new(0);
+    let controller = with_context({
+        let iterations = iterations.clone();
+        move |ctx| {
+            let mut count = 0;
+            loop {
+                if ctx.checkpoint().is_break() {
+                    iterations.store(count);
+                    return Ok(());
+                }
+                count += 1;
+                if count == 5 {
+                    ctx.cancellation_token().cancel();
+                }
+            }
+        }
+    });
+
+    MultiAgentEngine::new(controller, IdleSimulator)
+        .run()
+        .unwrap();
+
+    assert_eq!(**iterations.load(), 5);
+}
+
+#[test]
+fn recv_cancellable_returns_none_on_shutdown() {
+    let (_sender, receiver) = message::Queue::channel::<u32>();
+    let received = Arc::new(Mutex::new(None));
+    let simulator = with_context({
+        let received = Arc::clone(&received);
+        move |ctx| {
+            let msg = receiver.recv_cancellable(ctx.cancellation_token())?;
+            *received.lock().unwrap() = Some(msg);
+            Ok(())
+        }
+    });
+
+    let handle = MultiAgentEngine::simulator_only(simulator).spawn();
+    thread::sleep(Duration::from_millis(10));
+
+    let start = Instant::now();
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+
+    assert!(start.elapsed() < Duration::from_millis(100));
+    assert_eq!(*received.lock().unwrap(), Some(None));
+}
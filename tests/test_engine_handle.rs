@@ -0,0 +1,648 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{
+    AccumulatingSimulator, FailingController, IdleController, IdleSimulator, PingController,
+    PongSimulator, SpinningController, WithContext, cancellable_engine, with_context,
+};
+use multi_agent_engine::{
+    AgentContext, AgentId, CancellationToken, Clock, Controller, Endpoint, Error, Heartbeat,
+    MockClock, MultiAgentEngine, Result, Shared, ShutdownOrder, Simulator, UndrainedQueue, message,
+};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn join_timeout_detaches_agents_that_never_finish() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let controller = SpinningController { stop: stop.clone() };
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator).spawn();
+    let result = handle.join_timeout(Duration::from_millis(50));
+
+    let Err(Error::Timeout { duration }) = result else {
+        panic!("expected a join timeout, got {result:?}");
+    };
+    assert_eq!(duration, Duration::from_millis(50));
+    stop.store(true, Ordering::Relaxed);
+}
+
+#[test]
+fn join_timeout_returns_result_of_finished_agents() {
+    let (sender, receiver) = message::Queue::channel();
+
+    let handle =
+        MultiAgentEngine::new(PingController { sender }, PongSimulator { receiver }).spawn();
+
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn try_join_polls_without_blocking() {
+    let engine = cancellable_engine();
+    let token = engine.cancellation_token();
+    let mut handle = engine.spawn();
+
+    assert!(handle.try_join().is_none());
+    token.cancel();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let result = loop {
+        if let Some(result) = handle.try_join() {
+            break result;
+        }
+        assert!(Instant::now() < deadline, "agents never finished");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert!(result.is_ok());
+}
+
+#[test]
+fn fresh_engine_reports_healthy() {
+    let engine = cancellable_engine().with_heartbeat_timeout(Duration::from_secs(60));
+    let token = engine.cancellation_token();
+
+    let handle = engine.spawn();
+    let health = handle.health();
+    token.cancel();
+
+    assert!(health.is_healthy(), "{health:?}");
+    assert!(health.controller.alive && health.simulator.alive);
+    assert!(!health.errored);
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn stalled_agent_reports_unhealthy() {
+    let clock = MockClock::new();
+    let engine = cancellable_engine()
+        .with_controller_heartbeat(Heartbeat::new().with_clock(clock.clone()))
+        .with_simulator_heartbeat(Heartbeat::new().with_clock(clock.clone()))
+        .with_heartbeat_timeout(Duration::from_secs(1));
+    let token = engine.cancellation_token();
+
+    let handle = engine.spawn();
+    clock.advance(Duration::from_secs(2));
+    let health = handle.health();
+    token.cancel();
+
+    assert!(!health.is_healthy());
+    assert!(health.simulator.alive);
+    assert!(!health.simulator.healthy);
+    assert_eq!(health.simulator.last_heartbeat, Duration::from_secs(2));
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn busy_agents_beat_their_heartbeat_every_frame() {
+    let clock = MockClock::new();
+    let controller = with_context(|ctx| {
+        while ctx.checkpoint().is_continue() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    });
+    let simulator = with_context(|ctx| {
+        while !ctx.is_cancelled() {
+            ctx.advance_frame();
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    });
+    let handle = MultiAgentEngine::new(controller, simulator)
+        .with_controller_heartbeat(Heartbeat::new().with_clock(clock.clone()))
+        .with_simulator_heartbeat(Heartbeat::new().with_clock(clock.clone()))
+        .with_heartbeat_timeout(Duration::from_secs(1))
+        .spawn();
+
+    clock.advance(Duration::from_secs(2));
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !handle.health().is_healthy() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let health = handle.health();
+    assert!(health.controller.healthy, "{health:?}");
+    assert!(health.simulator.healthy, "{health:?}");
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+}
+
+struct CountingController {
+    sender: message::Sender<u32>,
+}
+
+impl Controller for CountingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        (1..=4).try_for_each(|value| self.sender.send(value))
+    }
+}
+
+#[test]
+fn restart_simulator_resumes_against_the_same_shared_state() {
+    let (sender, receiver) = message::Queue::channel();
+    let total = Shared::new(0);
+
+    let simulator = AccumulatingSimulator {
+        receiver: receiver.clone(),
+        total: total.clone(),
+        fail_on: Some(2),
+    };
+    let handle = MultiAgentEngine::new(CountingController { sender }, simulator).spawn();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while handle.health().simulator.alive {
+        assert!(Instant::now() < deadline, "simulator never failed");
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert!(handle.health().errored);
+
+    let previous = handle.restart_simulator(|| AccumulatingSimulator {
+        receiver,
+        total: total.clone(),
+        fail_on: None,
+    });
+    assert!(!handle.health().errored);
+    assert!(matches!(
+        previous,
+        Err(Error::Agent {
+            agent: AgentId::SIMULATOR,
+            ..
+        })
+    ));
+
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+    assert_eq!(**total.load(), 1 + 3 + 4);
+}
+
+#[test]
+fn restart_simulator_refuses_a_running_simulator() {
+    let (sender, receiver) = message::Queue::channel::<u32>();
+    let total = Shared::new(0);
+    let simulator = AccumulatingSimulator {
+        receiver,
+        total: total.clone(),
+        fail_on: None,
+    };
+    let handle = MultiAgentEngine::new(IdleController, simulator).spawn();
+
+    let restarted = handle.restart_simulator(|| -> AccumulatingSimulator {
+        unreachable!("the running simulator must not be replaced")
+    });
+
+    assert!(matches!(
+        restarted,
+        Err(Error::AgentRunning {
+            agent: AgentId::SIMULATOR
+        })
+    ));
+    drop(sender);
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn restart_simulator_keeps_the_engine_usable_when_the_factory_panics() {
+    let handle = MultiAgentEngine::new(IdleController, IdleSimulator).spawn();
+    while handle.health().simulator.alive {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let restarted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        handle.restart_simulator(|| -> IdleSimulator { panic!("factory failed") })
+    }));
+
+    assert!(restarted.is_err());
+    assert!(!handle.health().simulator.alive);
+    assert!(handle.join_timeout(Duration::from_secs(5)).is_ok());
+}
+
+fn ordered_agent(
+    agent: AgentId,
+    stopped: Arc<Mutex<Vec<AgentId>>>,
+) -> WithContext<impl FnOnce(&AgentContext) -> Result<()>> {
+    with_context(move |ctx| {
+        while !ctx.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        stopped.lock().unwrap().push(agent);
+        Ok(())
+    })
+}
+
+fn shutdown_sequence(order: ShutdownOrder) -> Vec<AgentId> {
+    let stopped = Arc::new(Mutex::new(Vec::new()));
+    let controller = ordered_agent(AgentId::CONTROLLER, Arc::clone(&stopped));
+    let simulator = ordered_agent(AgentId::SIMULATOR, Arc::clone(&stopped));
+
+    let handle = MultiAgentEngine::new(controller, simulator)
+        .with_shutdown_order(order)
+        .spawn();
+    thread::sleep(Duration::from_millis(5));
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+
+    Arc::into_inner(stopped).unwrap().into_inner().unwrap()
+}
+
+#[test]
+fn shutdown_stops_agents_in_configured_order() {
+    assert_eq!(
+        shutdown_sequence(ShutdownOrder::ControllerFirst),
+        [AgentId::CONTROLLER, AgentId::SIMULATOR]
+    );
+    assert_eq!(
+        shutdown_sequence(ShutdownOrder::SimulatorFirst),
+        [AgentId::SIMULATOR, AgentId::CONTROLLER]
+    );
+}
+
+struct EngineTokenAgent {
+    token: CancellationToken,
+}
+
+impl EngineTokenAgent {
+    fn wait(self) -> Result<()> {
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+impl Controller for EngineTokenAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.wait()
+    }
+}
+
+impl Simulator for EngineTokenAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.wait()
+    }
+}
+
+#[test]
+fn ordered_shutdown_stops_agents_watching_only_the_engine_token() {
+    let token = CancellationToken::new();
+    let handle = MultiAgentEngine::new(
+        EngineTokenAgent {
+            token: token.clone(),
+        },
+        EngineTokenAgent {
+            token: token.clone(),
+        },
+    )
+    .with_cancellation_token(token.clone())
+    .with_shutdown_order(ShutdownOrder::ControllerFirst)
+    .spawn();
+    thread::sleep(Duration::from_millis(5));
+
+    let started = Instant::now();
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn ordered_shutdown_cancels_later_stages_after_a_timeout() {
+    let stopped = Arc::new(Mutex::new(Vec::new()));
+    // Watches its token once, then ignores it for a while.
+    let controller = with_context(|ctx| {
+        let _ = ctx.is_cancelled();
+        thread::sleep(Duration::from_millis(300));
+        Ok(())
+    });
+    let simulator = ordered_agent(AgentId::SIMULATOR, Arc::clone(&stopped));
+
+    let handle = MultiAgentEngine::new(controller, simulator)
+        .with_shutdown_order(ShutdownOrder::ControllerFirst)
+        .spawn();
+    thread::sleep(Duration::from_millis(5));
+
+    assert!(matches!(
+        handle.shutdown(Duration::from_millis(20)),
+        Err(Error::Timeout { .. })
+    ));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*stopped.lock().unwrap(), [AgentId::SIMULATOR]);
+}
+
+#[test]
+fn simultaneous_shutdown_cancels_engine_token() {
+    let engine = cancellable_engine();
+    let token = engine.cancellation_token();
+
+    engine.spawn().shutdown(Duration::from_secs(5)).unwrap();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn before_join_hook_runs_once_after_both_agents_finish() {
+    let (sender, receiver) = message::Queue::channel();
+    let total = Shared::new(0);
+    let simulator = AccumulatingSimulator {
+        receiver,
+        total: total.clone(),
+        fail_on: None,
+    };
+    let calls = Arc::new(AtomicUsize::new(0));
+    let report = Shared::new(None);
+
+    let result = MultiAgentEngine::new(CountingController { sender }, simulator)
+        .with_before_join({
+            let calls = Arc::clone(&calls);
+            let (total, report) = (total.clone(), report.clone());
+            move || {
+                calls.fetch_add(1, Ordering::Relaxed);
+                report.store(Some(**total.load()));
+            }
+        })
+        .run();
+
+    assert!(result.is_ok());
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    assert_eq!(**report.load(), Some(1 + 2 + 3 + 4));
+}
+
+fn slow_cleanup_controller(
+    cleaned_up: Shared<bool>,
+) -> WithContext<impl FnOnce(&AgentContext) -> Result<()>> {
+    with_context(move |ctx| {
+        while ctx.checkpoint().is_continue() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        thread::sleep(Duration::from_millis(50));
+        cleaned_up.store(true);
+        Ok(())
+    })
+}
+
+#[test]
+fn shutdown_grace_lets_cooperative_agents_finish_cleanup() {
+    let cleaned_up = Shared::new(false);
+    let controller = slow_cleanup_controller(cleaned_up.clone());
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .with_shutdown_grace(Duration::from_secs(5))
+        .spawn();
+
+    handle.shutdown(Duration::ZERO).unwrap();
+    assert!(**cleaned_up.load());
+}
+
+#[test]
+fn join_timeout_cancels_agents_and_waits_the_shutdown_grace() {
+    let cleaned_up = Shared::new(false);
+    let controller = slow_cleanup_controller(cleaned_up.clone());
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .with_shutdown_grace(Duration::from_secs(5))
+        .spawn();
+
+    handle.join_timeout(Duration::from_millis(10)).unwrap();
+    assert!(**cleaned_up.load());
+}
+
+#[test]
+fn join_timeout_without_a_grace_still_cancels_the_detached_agents() {
+    let cleaned_up = Shared::new(false);
+    let controller = slow_cleanup_controller(cleaned_up.clone());
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator).spawn();
+
+    assert!(matches!(
+        handle.join_timeout(Duration::from_millis(10)),
+        Err(Error::Timeout { .. })
+    ));
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !**cleaned_up.load() {
+        assert!(Instant::now() < deadline, "the agent was never cancelled");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn shutdown_escalates_to_the_engine_token_after_the_grace() {
+    let engine = CancellationToken::new();
+    let controller = with_context({
+        let engine = engine.clone();
+        move |ctx| {
+            // Checks its own token once, then only listens to the engine's.
+            let _ = ctx.cancellation_token().is_cancelled();
+            while !engine.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            Ok(())
+        }
+    });
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .with_cancellation_token(engine)
+        .with_shutdown_order(ShutdownOrder::ControllerFirst)
+        .with_shutdown_grace(Duration::from_millis(20))
+        .spawn();
+
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+}
+
+struct FloodingController {
+    sender: message::Sender<u32>,
+    count: usize,
+}
+
+impl Controller for FloodingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        (0..self.count).try_for_each(|_| self.sender.send(42))
+    }
+}
+
+#[test]
+fn teardown_report_lists_undrained_queues() {
+    let (sender, receiver) = message::Queue::channel();
+    let (_telemetry, telemetry) = message::Queue::channel::<u32>();
+    let controller = FloodingController { sender, count: 4 };
+    let simulator = PongSimulator {
+        receiver: receiver.clone(),
+    };
+
+    let engine = MultiAgentEngine::new(controller, simulator)
+        .with_queue("commands", &receiver)
+        .with_queue("telemetry", &telemetry);
+    let report = engine.teardown_report();
+    drop(telemetry);
+
+    let handle = engine.spawn();
+    assert!(!report.is_recorded());
+    handle.join().unwrap();
+    drop(receiver);
+
+    assert_eq!(
+        report.undrained(),
+        vec![UndrainedQueue {
+            name: "commands".to_owned(),
+            remaining: 3,
+        }]
+    );
+}
+
+#[test]
+fn teardown_report_counts_messages_left_when_the_receiver_dropped() {
+    let (sender, receiver) = message::Queue::channel();
+    (0..3).try_for_each(|_| sender.send(42)).unwrap();
+
+    let engine = MultiAgentEngine::new(
+        IdleController,
+        PongSimulator {
+            receiver: receiver.clone(),
+        },
+    )
+    .with_queue("commands", &receiver);
+    let report = engine.teardown_report();
+    drop(receiver);
+    engine.spawn().join().unwrap();
+
+    assert_eq!(report.undrained()[0].remaining, 2);
+    assert!(matches!(sender.send(42), Err(Error::Disconnected { .. })));
+}
+
+#[test]
+fn teardown_report_is_kept_by_a_later_try_join() {
+    let (sender, receiver) = message::Queue::channel();
+    (0..2).try_for_each(|_| sender.send(42)).unwrap();
+
+    let engine =
+        MultiAgentEngine::new(IdleController, IdleSimulator).with_queue("commands", &receiver);
+    let report = engine.teardown_report();
+    let mut handle = engine.spawn();
+    while handle.try_join().is_none() {
+        thread::yield_now();
+    }
+    assert!(handle.try_join().is_some());
+
+    assert_eq!(report.undrained()[0].remaining, 2);
+}
+
+#[test]
+fn teardown_report_is_recorded_when_a_join_times_out() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = message::Queue::channel();
+    sender.send(42).unwrap();
+
+    let engine = MultiAgentEngine::new(SpinningController { stop: stop.clone() }, IdleSimulator)
+        .with_queue("commands", &receiver);
+    let report = engine.teardown_report();
+    assert!(
+        engine
+            .spawn()
+            .join_timeout(Duration::from_millis(20))
+            .is_err()
+    );
+    stop.store(true, Ordering::Relaxed);
+
+    assert_eq!(report.undrained()[0].remaining, 1);
+}
+
+fn checkpointing_agent(
+    passed: Arc<AtomicUsize>,
+) -> WithContext<impl FnOnce(&AgentContext) -> Result<()>> {
+    with_context(move |ctx| {
+        while ctx.checkpoint().is_continue() {
+            passed.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn pause_freezes_agents_and_the_shared_clock() {
+    let passed = Arc::new(AtomicUsize::new(0));
+    let controller = checkpointing_agent(Arc::clone(&passed));
+    let simulator = checkpointing_agent(Arc::clone(&passed));
+    let handle = MultiAgentEngine::new(controller, simulator).spawn();
+    thread::sleep(Duration::from_millis(10));
+
+    let before = handle.clock().now();
+    handle.pause();
+    assert!(handle.is_paused());
+    thread::sleep(Duration::from_millis(10));
+    let frozen = passed.load(Ordering::Relaxed);
+    thread::sleep(Duration::from_millis(90));
+    assert_eq!(passed.load(Ordering::Relaxed), frozen);
+    handle.resume();
+
+    assert!(handle.clock().now() - before < Duration::from_millis(20));
+    thread::sleep(Duration::from_millis(10));
+    assert!(passed.load(Ordering::Relaxed) > frozen);
+    handle.shutdown(Duration::from_secs(5)).unwrap();
+}
+
+struct ThreadWaker(thread::Thread);
+
+impl std::task::Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn shutdown_complete_resolves_with_the_join_result() {
+    let (sender, receiver) = message::Queue::channel();
+    let mut complete = MultiAgentEngine::new(FailingController, PongSimulator { receiver })
+        .spawn()
+        .shutdown_complete();
+
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    assert!(std::pin::Pin::new(&mut complete).poll(&mut cx).is_pending());
+    assert!(!complete.is_complete());
+
+    sender.send(42).unwrap();
+
+    assert!(matches!(
+        block_on(&mut complete),
+        Err(Error::Disconnected {
+            endpoint: Endpoint::Sender
+        })
+    ));
+    assert!(complete.is_complete());
+    assert!(block_on(complete).is_ok());
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{Endpoint, Error, message::Queue};
+use std::{error::Error as _, io, time::Duration};
+
+#[test]
+fn send_on_dropped_receiver_is_sender_disconnect() {
+    let (sender, receiver) = Queue::channel();
+    drop(receiver);
+
+    let err = sender.send(1).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Disconnected {
+            endpoint: Endpoint::Sender
+        }
+    ));
+    assert_eq!(err.to_string(), "sending on a disconnected channel");
+}
+
+#[test]
+fn receive_on_dropped_sender_is_receiver_disconnect() {
+    let (sender, receiver) = Queue::channel::<u32>();
+    drop(sender);
+
+    let err = receiver.recv_blocking().unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Disconnected {
+            endpoint: Endpoint::Receiver
+        }
+    ));
+}
+
+#[test]
+fn timeout_carries_duration() {
+    let err = Error::Timeout {
+        duration: Duration::from_millis(250),
+    };
+
+    assert_eq!(err.to_string(), "operation timed out after 250ms");
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn deadlock_carries_agent_count() {
+    let err = Error::Deadlock { agents: 2 };
+
+    assert_eq!(
+        err.to_string(),
+        "all 2 agents are blocked on receive with empty queues"
+    );
+}
+
+#[test]
+fn transport_error_exposes_io_source() {
+    let err = Error::from(io::Error::from(io::ErrorKind::TimedOut));
+
+    assert!(matches!(&err, Error::Transport(io) if io.kind() == io::ErrorKind::TimedOut));
+    assert!(err.is_retriable());
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn multiple_lists_every_error() {
+    let err = Error::Multiple(vec![Error::CircuitOpen, Error::Deadlock { agents: 2 }]);
+
+    assert_eq!(
+        err.to_string(),
+        "2 errors occurred; circuit breaker is open; all 2 agents are blocked on receive with empty queues"
+    );
+}
@@ -0,0 +1,125 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    AgentContext, Controller, EngineEvent, Error, FixedTimestep, MockClock, MultiAgentEngine,
+    Observer, SimClock,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const INTERVAL: Duration = Duration::from_millis(10);
+
+#[test]
+fn frames_within_budget_sleep_out_the_interval() {
+    let clock = MockClock::new();
+    let mut timestep = FixedTimestep::new(INTERVAL).with_clock(clock.clone());
+
+    clock.advance(Duration::from_millis(4));
+    assert_eq!(timestep.tick(), None);
+    assert_eq!(clock.elapsed(), INTERVAL);
+
+    clock.advance(Duration::from_millis(25));
+    assert_eq!(timestep.tick(), Some(Duration::from_millis(15)));
+    assert_eq!(clock.elapsed(), Duration::from_millis(35));
+
+    assert_eq!(timestep.frame(), 2);
+    assert_eq!(timestep.missed_deadlines(), 1);
+}
+
+#[derive(Default)]
+struct DeadlineLog {
+    misses: Arc<Mutex<Vec<(u64, Duration)>>>,
+}
+
+impl Observer for DeadlineLog {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        if let EngineEvent::DeadlineMissed { frame, overrun } = event {
+            self.misses.lock().unwrap().push((frame, overrun));
+        }
+    }
+}
+
+struct OverrunningController {
+    clock: MockClock,
+}
+
+impl Controller for OverrunningController {
+    type Error = Error;
+
+    fn run(self) -> Result<(), Self::Error> {
+        unreachable!("the engine calls run_with_context")
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<(), Self::Error> {
+        let mut timestep = FixedTimestep::new(INTERVAL).with_context(ctx);
+
+        for frame in 1..=5 {
+            let work = if frame == 3 { 30 } else { 2 };
+            self.clock.advance(Duration::from_millis(work));
+            timestep.tick();
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn one_overrun_frame_reports_one_deadline_miss() {
+    let clock = MockClock::new();
+    let log = DeadlineLog::default();
+    let misses = Arc::clone(&log.misses);
+    let controller = OverrunningController {
+        clock: clock.clone(),
+    };
+
+    MultiAgentEngine::controller_only(controller)
+        .with_clock(clock)
+        .with_observer(log)
+        .run()
+        .unwrap();
+
+    assert_eq!(
+        *misses.lock().unwrap(),
+        vec![(3, Duration::from_millis(20))]
+    );
+}
+
+#[test]
+fn paused_clock_does_not_cause_catch_up_steps() {
+    let clock = MockClock::new();
+    let sim = SimClock::wrapping(clock.clone());
+    let mut timestep = FixedTimestep::new(INTERVAL).with_clock(sim.clone());
+
+    clock.advance(Duration::from_millis(35));
+    assert_eq!(timestep.due_steps(), 3);
+
+    sim.pause();
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(timestep.due_steps(), 0);
+    sim.resume();
+
+    clock.advance(Duration::from_millis(5));
+    assert_eq!(timestep.due_steps(), 1);
+    assert_eq!(timestep.frame(), 4);
+
+    sim.pause();
+    clock.advance(Duration::from_secs(1));
+    sim.resume();
+    assert_eq!(timestep.tick(), None);
+    assert_eq!(timestep.missed_deadlines(), 0);
+}
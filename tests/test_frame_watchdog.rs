@@ -0,0 +1,105 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    AgentContext, Controller, EngineEvent, Error, FrameWatchdog, MockClock, MultiAgentEngine,
+    Observer,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const BUDGET: Duration = Duration::from_millis(16);
+
+#[derive(Default)]
+struct SlowFrameLog {
+    frames: Arc<Mutex<Vec<(u64, Duration, Duration)>>>,
+}
+
+impl Observer for SlowFrameLog {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        if let EngineEvent::SlowFrame {
+            frame,
+            elapsed,
+            receive,
+        } = event
+        {
+            self.frames.lock().unwrap().push((frame, elapsed, receive));
+        }
+    }
+}
+
+struct HitchingController {
+    clock: MockClock,
+}
+
+impl Controller for HitchingController {
+    type Error = Error;
+
+    fn run(self) -> Result<(), Self::Error> {
+        unreachable!("the engine calls run_with_context")
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<(), Self::Error> {
+        let mut watchdog = FrameWatchdog::new(BUDGET).with_context(ctx);
+
+        for frame in 1..=5 {
+            watchdog.start_frame();
+            self.clock.advance(Duration::from_millis(2));
+            watchdog.mark_received();
+            let compute = if frame == 4 { 40 } else { 10 };
+            self.clock.advance(Duration::from_millis(compute));
+            watchdog.end_frame();
+        }
+        assert_eq!(watchdog.slow_frames(), 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn one_long_frame_fires_the_watchdog_once() {
+    let clock = MockClock::new();
+    let log = SlowFrameLog::default();
+    let frames = Arc::clone(&log.frames);
+    let controller = HitchingController {
+        clock: clock.clone(),
+    };
+
+    MultiAgentEngine::controller_only(controller)
+        .with_clock(clock)
+        .with_observer(log)
+        .run()
+        .unwrap();
+
+    assert_eq!(
+        *frames.lock().unwrap(),
+        vec![(4, Duration::from_millis(42), Duration::from_millis(2))]
+    );
+}
+
+#[test]
+fn frames_within_budget_are_not_reported() {
+    let clock = MockClock::new();
+    let mut watchdog = FrameWatchdog::new(BUDGET).with_clock(clock.clone());
+
+    clock.advance(BUDGET);
+    assert_eq!(watchdog.end_frame(), None);
+
+    clock.advance(BUDGET + Duration::from_millis(1));
+    assert_eq!(watchdog.end_frame(), Some(Duration::from_millis(17)));
+    assert_eq!(watchdog.slow_frames(), 1);
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    CancellationToken, Controller, EngineInspector, Error, Metrics, MultiAgentEngine, Result,
+    Simulator,
+    message::{self, MessageTap, Queue},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+struct BurstController {
+    sender: message::Sender<u32>,
+}
+
+impl Controller for BurstController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        for value in 1..=5 {
+            self.sender.send(value)?;
+        }
+        Ok(())
+    }
+}
+
+struct SlowSimulator {
+    receiver: message::Receiver<u32>,
+    token: CancellationToken,
+}
+
+impl Simulator for SlowSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.receiver.recv_blocking()?;
+        while !self.token.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn inspector_state_reflects_running_engine() {
+    let metrics = Metrics::new();
+    let tap = MessageTap::new(3);
+    let token = CancellationToken::new();
+    let (sender, receiver) = Queue::tapped_channel(&tap);
+
+    let controller = BurstController {
+        sender: sender.with_metrics(metrics.clone()),
+    };
+    let simulator = SlowSimulator {
+        receiver,
+        token: token.clone(),
+    };
+    let inspector = EngineInspector::new(metrics.clone()).with_queue("commands", &tap);
+
+    let handle = MultiAgentEngine::new(controller, simulator)
+        .with_cancellation_token(token.clone())
+        .spawn();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while tap.depth() != 4 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+    let state = inspector.state();
+    token.cancel();
+    handle.join().unwrap();
+
+    assert_eq!(state.messages_sent, 5);
+    assert_eq!(state.queues.len(), 1);
+    assert_eq!(state.queues[0].name, "commands");
+    assert_eq!(state.queues[0].depth, 4);
+    assert_eq!(state.queues[0].recent, ["3", "4", "5"]);
+}
+
+#[cfg(feature = "egui")]
+#[test]
+fn inspector_renders_headless() {
+    let tap = MessageTap::new(2);
+    let (sender, _receiver) = Queue::tapped_channel(&tap);
+    sender.send("ping").unwrap();
+
+    let inspector = EngineInspector::new(Metrics::new()).with_queue("commands", &tap);
+    let ctx = egui::Context::default();
+    let mut output = ctx.run_ui(egui::RawInput::default(), |ui| inspector.ui(ui.ctx()));
+    output.textures_delta.clear();
+
+    assert_eq!(inspector.state().queues[0].depth, 1);
+}
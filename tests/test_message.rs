@@ -0,0 +1,1692 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    AgentContext, AgentId, Clock, Endpoint, Error, MockClock, Warning,
+    message::{
+        self, AnyMessage, Backoff, Broadcast, BufferedSender, Causal, CausalReceiver,
+        CoalescingQueue, ConflatingQueue, DeadLetterQueue, Deadline, JitterQueue, LossyQueue,
+        MappedReceiver, MemoryBudget, MessageKind, MessageSampler, MessageSize, MessageStats,
+        MessageTap, OrderingMode, OverflowPolicy, PriorityQueue, PrioritySelect, Queue,
+        RateLimitPolicy, RateLimitedSender, ReceiveStrategy, RecvState, SequencedReceiver,
+        Sequencer, ShuffleQueue, StateSync,
+    },
+};
+use std::{
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn recv_batch_blocks_for_first_then_drains_up_to_max() {
+    let (sender, receiver) = Queue::channel();
+
+    sender.send(1).unwrap();
+    assert_eq!(receiver.recv_batch(10).unwrap(), vec![1]);
+
+    sender.send(2).unwrap();
+    sender.send(3).unwrap();
+    sender.send(4).unwrap();
+    assert_eq!(receiver.recv_batch(10).unwrap(), vec![2, 3, 4]);
+}
+
+#[test]
+fn recv_batch_leaves_messages_beyond_max_queued() {
+    let (sender, receiver) = Queue::channel();
+
+    for i in 0..5 {
+        sender.send(i).unwrap();
+    }
+
+    assert_eq!(receiver.recv_batch(3).unwrap(), vec![0, 1, 2]);
+    assert_eq!(receiver.receive(), vec![3, 4]);
+}
+
+#[test]
+fn recv_batch_fails_once_disconnected() {
+    let (sender, receiver) = Queue::channel::<u32>();
+    drop(sender);
+
+    assert!(receiver.recv_batch(10).is_err());
+}
+
+#[test]
+fn receive_state_drains_buffered_messages_before_disconnecting() {
+    let (sender, receiver) = Queue::channel::<u32>();
+    let receiver = receiver.with_drain_at_most(2);
+
+    assert_eq!(receiver.receive_state(), RecvState::Empty);
+    for i in 0..3 {
+        sender.send(i).unwrap();
+    }
+    drop(sender);
+
+    assert_eq!(receiver.receive_state(), RecvState::Messages(vec![0, 1]));
+    assert_eq!(receiver.receive_state(), RecvState::Messages(vec![2]));
+    assert_eq!(receiver.receive_state(), RecvState::Disconnected);
+    assert!(receiver.receive().is_empty());
+}
+
+#[test]
+fn send_batch_partial_returns_what_did_not_fit() {
+    let (sender, receiver) = Queue::bounded_channel::<u32>(3);
+
+    let rejected = sender.send_batch_partial(0..5);
+
+    assert_eq!(rejected, vec![3, 4]);
+    assert_eq!(receiver.receive(), vec![0, 1, 2]);
+    assert!(sender.send_batch_partial(rejected).is_empty());
+    assert_eq!(receiver.receive(), vec![3, 4]);
+}
+
+#[test]
+fn send_batch_partial_hands_back_everything_once_disconnected() {
+    let (sender, receiver) = Queue::bounded_channel::<u32>(3);
+    drop(receiver);
+
+    assert_eq!(sender.send_batch_partial(0..2), vec![0, 1]);
+}
+
+#[test]
+fn weak_sender_does_not_keep_channel_open() {
+    let (sender, receiver) = Queue::channel::<u32>();
+    let weak = sender.downgrade();
+
+    weak.upgrade().unwrap().send(7).unwrap();
+    drop(sender);
+
+    assert!(weak.upgrade().is_none());
+    assert_eq!(receiver.recv_batch(10).unwrap(), vec![7]);
+    assert!(receiver.recv_batch(10).is_err());
+}
+
+#[test]
+fn iter_yields_a_deadlock_instead_of_ending_silently() {
+    let detector = message::DeadlockDetector::new();
+    let (sender, receiver) = Queue::channel::<u32>();
+    let receiver = receiver.with_deadlock_detector(&detector, AgentId::CONTROLLER);
+
+    sender.send(1).unwrap();
+    let mut iter = receiver.iter();
+
+    assert_eq!(iter.next().unwrap().unwrap(), 1);
+    assert!(matches!(
+        iter.next(),
+        Some(Err(Error::Deadlock { agents: 1 }))
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn deadlock_detector_counts_an_agent_with_several_receivers_once() {
+    let detector = message::DeadlockDetector::new();
+    let (_to_controller, commands) = Queue::channel::<u32>();
+    let (_to_controller_too, events) = Queue::channel::<u32>();
+    let (_to_simulator, inputs) = Queue::channel::<u32>();
+    let commands = commands.with_deadlock_detector(&detector, AgentId::CONTROLLER);
+    let _events = events.with_deadlock_detector(&detector, AgentId::CONTROLLER);
+    let inputs = inputs.with_deadlock_detector(&detector, AgentId::SIMULATOR);
+
+    let simulator = thread::spawn(move || inputs.recv_blocking().map(drop));
+
+    assert!(matches!(
+        commands.recv_blocking(),
+        Err(Error::Deadlock { agents: 2 })
+    ));
+    assert!(matches!(
+        simulator.join().unwrap(),
+        Err(Error::Deadlock { agents: 2 })
+    ));
+}
+
+#[test]
+fn deadlock_detector_ignores_agents_with_pending_messages() {
+    let detector = message::DeadlockDetector::new();
+    let (sender, receiver) = Queue::channel::<u32>();
+    let receiver = receiver.with_deadlock_detector(&detector, AgentId::CONTROLLER);
+
+    sender.send(1).unwrap();
+
+    assert_eq!(receiver.recv_blocking().unwrap(), 1);
+    assert!(matches!(
+        receiver.recv_blocking(),
+        Err(Error::Deadlock { agents: 1 })
+    ));
+}
+
+#[test]
+fn rate_limited_sender_paces_messages() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let sender =
+        RateLimitedSender::new(sender, 10, RateLimitPolicy::Block).with_clock(clock.clone());
+
+    let mut sent_at = Vec::new();
+    for i in 0..100 {
+        sender.send(i).unwrap();
+        sent_at.push(clock.elapsed());
+    }
+
+    assert_eq!(receiver.receive(), (0..100).collect::<Vec<_>>());
+    assert!(sent_at[..10].iter().all(|at| at.is_zero()));
+    for (i, at) in sent_at.iter().enumerate().skip(10) {
+        assert_eq!(*at, Duration::from_millis(100) * (i as u32 - 9));
+    }
+}
+
+#[test]
+fn rate_limited_sender_drops_excess_messages() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let sender =
+        RateLimitedSender::new(sender, 10, RateLimitPolicy::Drop).with_clock(clock.clone());
+
+    for i in 0..15 {
+        sender.send(i).unwrap();
+    }
+    clock.advance(Duration::from_millis(200));
+    sender.send(15).unwrap();
+
+    assert_eq!(sender.dropped(), 5);
+    assert_eq!(receiver.receive().len(), 11);
+}
+
+#[test]
+fn conflating_queue_keeps_latest_message_per_key() {
+    let queue = ConflatingQueue::new();
+    let sender = queue.clone();
+
+    sender.send('A', 1);
+    sender.send('B', 10);
+    sender.send('A', 2);
+    sender.send('A', 3);
+    sender.send('B', 20);
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.receive(), vec![3, 20]);
+    assert!(queue.is_empty());
+
+    sender.send('B', 30);
+    assert_eq!(queue.receive(), vec![30]);
+}
+
+#[test]
+fn window_batches_messages_arriving_within_the_window() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_clock(clock.clone());
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    assert_eq!(receiver.window(Duration::from_millis(100)), vec![1, 2]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+    sender.send(3).unwrap();
+    assert_eq!(receiver.window(Duration::from_millis(100)), vec![3]);
+    assert!(receiver.window(Duration::from_millis(100)).is_empty());
+    assert_eq!(clock.elapsed(), Duration::from_millis(300));
+}
+
+#[test]
+fn receive_fair_interleaves_fast_and_slow_senders() {
+    let (fast, receiver) = Queue::tagged_channel();
+    let slow = fast.clone();
+    assert_ne!(fast.id(), slow.id());
+
+    for i in 0..50 {
+        fast.send(i).unwrap();
+    }
+    slow.send(100).unwrap();
+    slow.send(101).unwrap();
+
+    let batch = receiver.receive_fair();
+
+    assert_eq!(batch.len(), 52);
+    assert_eq!(&batch[..5], &[0, 100, 1, 101, 2]);
+    assert_eq!(&batch[4..], &(2..50).collect::<Vec<_>>()[..]);
+}
+
+#[test]
+fn receive_tagged_attributes_messages_to_their_sender() {
+    let (player_one, receiver) = Queue::tagged_channel();
+    let player_two = player_one.clone();
+
+    player_one.send("jump").unwrap();
+    player_two.send("duck").unwrap();
+    player_one.send("fire").unwrap();
+
+    assert_eq!(
+        receiver.receive_tagged(),
+        vec![
+            (player_one.id(), "jump"),
+            (player_two.id(), "duck"),
+            (player_one.id(), "fire"),
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Pause,
+    Sample(u32),
+}
+
+#[test]
+fn control_lane_is_drained_before_data_backlog() {
+    let (sender, receiver) = Queue::lanes();
+
+    for i in 0..1000 {
+        sender.send_data(Command::Sample(i)).unwrap();
+    }
+    sender.send_control(Command::Pause).unwrap();
+
+    let batch = receiver.receive();
+
+    assert_eq!(batch.len(), 1001);
+    assert_eq!(batch[0], Command::Pause);
+    assert_eq!(batch[1], Command::Sample(0));
+    assert_eq!(batch[1000], Command::Sample(999));
+}
+
+#[derive(Debug, PartialEq)]
+enum Spawn {
+    Entity(u32),
+}
+
+#[test]
+fn dedup_by_key_suppresses_repeats_within_window() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver
+        .with_clock(clock.clone())
+        .dedup_by_key(|Spawn::Entity(id)| *id, Duration::from_secs(1));
+
+    sender.send(Spawn::Entity(7)).unwrap();
+    sender.send(Spawn::Entity(7)).unwrap();
+    sender.send(Spawn::Entity(8)).unwrap();
+    assert_eq!(receiver.receive(), vec![Spawn::Entity(7), Spawn::Entity(8)]);
+
+    clock.advance(Duration::from_millis(500));
+    sender.send(Spawn::Entity(7)).unwrap();
+    assert!(receiver.receive().is_empty());
+
+    clock.advance(Duration::from_millis(500));
+    sender.send(Spawn::Entity(7)).unwrap();
+    assert_eq!(receiver.receive(), vec![Spawn::Entity(7)]);
+}
+
+#[test]
+fn dedup_with_an_unbounded_window_suppresses_repeats_forever() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver
+        .with_clock(clock.clone())
+        .dedup_by_key(|Spawn::Entity(id)| *id, Duration::MAX);
+
+    sender.send(Spawn::Entity(7)).unwrap();
+    assert_eq!(receiver.receive(), vec![Spawn::Entity(7)]);
+
+    clock.advance(Duration::from_secs(3600));
+    sender.send(Spawn::Entity(7)).unwrap();
+    assert!(receiver.receive().is_empty());
+}
+
+#[test]
+fn map_converts_messages_on_receive() {
+    let (sender, receiver) = Queue::channel::<String>();
+    let lengths: MappedReceiver<usize> = receiver.map(|s| s.len());
+
+    sender.send("ping".to_owned()).unwrap();
+    sender.send("hello".to_owned()).unwrap();
+    sender.send(String::new()).unwrap();
+
+    assert_eq!(lengths.receive(), vec![4, 5, 0]);
+    assert_eq!(lengths.try_recv_one(), None);
+
+    sender.send("pong".to_owned()).unwrap();
+    drop(sender);
+    assert_eq!(lengths.recv_blocking().unwrap(), 4);
+    assert!(lengths.recv_blocking().is_err());
+}
+
+#[test]
+fn receive_bounded_stops_at_count_limit() {
+    let (sender, receiver) = Queue::channel();
+    (0..10).for_each(|value| sender.send(value).unwrap());
+
+    let start = Instant::now();
+    assert_eq!(
+        receiver.receive_bounded(4, Duration::from_secs(5)),
+        vec![0, 1, 2, 3]
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(receiver.receive().len(), 6);
+}
+
+#[test]
+fn receive_until_stops_at_sentinel() {
+    let (sender, receiver) = Queue::channel();
+
+    let producer = thread::spawn(move || {
+        for value in [1, 2, 3, 0, 4, 5] {
+            sender.send(value).unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        sender
+    });
+
+    let start = Instant::now();
+    let batch = receiver.receive_until(|&value| value == 0, Duration::from_secs(5));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(batch, vec![1, 2, 3, 0]);
+
+    drop(producer.join().unwrap());
+    assert_eq!(receiver.receive(), vec![4, 5]);
+    assert!(
+        receiver
+            .receive_until(|&value| value == 0, Duration::from_millis(5))
+            .is_empty()
+    );
+}
+
+#[test]
+fn receive_bounded_returns_partial_batch_on_timeout() {
+    let (sender, receiver) = Queue::channel();
+    sender.send(1).unwrap();
+
+    let producer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(5));
+        sender.send(2).unwrap();
+        sender
+    });
+
+    let start = Instant::now();
+    let batch = receiver.receive_bounded(10, Duration::from_millis(50));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(batch, vec![1, 2]);
+    drop(producer.join().unwrap());
+}
+
+#[test]
+fn receive_bounded_honors_clock_drain_limit_and_unbounded_timeouts() {
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_clock(MockClock::new()).with_drain_at_most(2);
+    (0..3).for_each(|value| sender.send(value).unwrap());
+
+    assert_eq!(receiver.receive_bounded(10, Duration::MAX), vec![0, 1]);
+    let start = Instant::now();
+    assert_eq!(
+        receiver.receive_bounded(10, Duration::from_secs(10)),
+        vec![2]
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn recv_timeout_blocks_on_the_system_clock() {
+    let (sender, receiver) = Queue::channel();
+
+    let start = Instant::now();
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_millis(20)).unwrap(),
+        None
+    );
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    let producer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        sender.send(7).unwrap();
+    });
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+        Some(7)
+    );
+    producer.join().unwrap();
+
+    assert!(matches!(
+        receiver.recv_timeout(Duration::from_secs(5)),
+        Err(Error::Disconnected { .. })
+    ));
+}
+
+#[test]
+fn receive_until_honors_clock_and_unbounded_timeouts() {
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_clock(MockClock::new());
+    sender.send(0).unwrap();
+
+    assert_eq!(
+        receiver.receive_until(|&value| value == 0, Duration::MAX),
+        vec![0]
+    );
+    let start = Instant::now();
+    assert!(
+        receiver
+            .receive_until(|&value| value == 0, Duration::from_secs(10))
+            .is_empty()
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn spin_receive_returns_promptly_when_message_arrives_mid_spin() {
+    let (sender, receiver) = Queue::channel();
+
+    let producer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(5));
+        sender.send(1).unwrap();
+        sender
+    });
+
+    let start = Instant::now();
+    assert_eq!(receiver.spin_receive(Duration::from_secs(5)), vec![1]);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    drop(producer.join().unwrap());
+}
+
+#[test]
+fn spin_receive_falls_back_to_blocking_after_spinning() {
+    let (sender, receiver) = Queue::channel();
+
+    let producer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.send(2).unwrap();
+    });
+
+    assert_eq!(receiver.spin_receive(Duration::from_millis(1)), vec![2]);
+    assert!(receiver.spin_receive(Duration::from_millis(1)).is_empty());
+
+    producer.join().unwrap();
+}
+
+#[test]
+fn receive_yield_returns_all_queued_messages() {
+    let (sender, receiver) = Queue::channel();
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    assert_eq!(receiver.receive_yield(), vec![1, 2]);
+}
+
+#[test]
+fn receive_yield_parks_after_bounded_yields() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_clock(clock.clone());
+
+    sender.send(1).unwrap();
+    assert_eq!(receiver.receive_yield(), vec![1]);
+    assert_eq!(clock.yields(), 0);
+
+    let producer = thread::spawn({
+        let clock = clock.clone();
+        move || {
+            while clock.yields() < message::Receiver::<u32>::YIELD_ATTEMPTS as u64 {
+                thread::yield_now();
+            }
+            thread::sleep(Duration::from_millis(20));
+            sender.send(3).unwrap();
+        }
+    });
+
+    assert_eq!(receiver.receive_yield(), vec![3]);
+    assert_eq!(
+        clock.yields(),
+        message::Receiver::<u32>::YIELD_ATTEMPTS as u64
+    );
+    producer.join().unwrap();
+
+    assert!(receiver.receive_yield().is_empty());
+    assert_eq!(
+        clock.yields(),
+        2 * message::Receiver::<u32>::YIELD_ATTEMPTS as u64
+    );
+}
+
+enum Telemetry {
+    Position,
+    Heartbeat,
+}
+
+impl MessageKind for Telemetry {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Position => "Position",
+            Self::Heartbeat => "Heartbeat",
+        }
+    }
+}
+
+#[test]
+fn message_stats_export_csv_per_kind() {
+    let stats = MessageStats::new();
+
+    for micros in [10, 20, 30, 40] {
+        stats.record(&Telemetry::Position, Duration::from_micros(micros));
+    }
+    stats.record(&Telemetry::Heartbeat, Duration::from_micros(5));
+
+    let mut csv = Vec::new();
+    stats.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(stats.count("Position"), 4);
+    assert_eq!(
+        lines,
+        [
+            "kind,count,p50_latency_ns,p99_latency_ns",
+            "Heartbeat,1,5000,5000",
+            "Position,4,20000,40000",
+        ]
+    );
+}
+
+#[test]
+fn receive_by_deadline_is_earliest_deadline_first_and_drops_expired() {
+    let clock = MockClock::new();
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_clock(clock.clone());
+    let start = clock.now();
+
+    for (message, due_ms) in [(1, 30), (2, 10), (3, 5), (4, 20)] {
+        let due = start + Duration::from_millis(due_ms);
+        sender.send(Deadline::new(message, due)).unwrap();
+    }
+    clock.advance(Duration::from_millis(7));
+
+    let order: Vec<_> = receiver
+        .receive_by_deadline()
+        .into_iter()
+        .map(Deadline::into_inner)
+        .collect();
+    assert_eq!(order, vec![2, 4, 1]);
+    assert_eq!(receiver.expired_count(), 1);
+}
+
+#[test]
+fn fullness_reports_ratio_of_bounded_capacity_in_use() {
+    let (sender, _receiver) = Queue::bounded_channel(10);
+
+    for i in 0..7 {
+        sender.send(i).unwrap();
+    }
+
+    assert!((sender.fullness() - 0.7).abs() < f32::EPSILON);
+}
+
+#[test]
+fn fullness_is_zero_for_unbounded_queues() {
+    let (sender, _receiver) = Queue::channel();
+    sender.send(1).unwrap();
+
+    assert_eq!(sender.fullness(), 0.0);
+}
+
+#[test]
+fn flush_and_wait_returns_once_slow_receiver_drains_queue() {
+    let (sender, receiver) = Queue::channel();
+    for i in 0..5 {
+        sender.send(i).unwrap();
+    }
+
+    let consumer = thread::spawn(move || {
+        let mut received = Vec::new();
+        while received.len() < 5 {
+            thread::sleep(Duration::from_millis(2));
+            received.extend(receiver.recv_batch(1).unwrap());
+        }
+        received
+    });
+
+    sender.flush_and_wait(Duration::from_secs(5)).unwrap();
+    assert_eq!(consumer.join().unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn flush_and_wait_times_out_when_nobody_drains() {
+    let (sender, _receiver) = Queue::channel();
+    sender.send(1).unwrap();
+
+    assert!(matches!(
+        sender.flush_and_wait(Duration::from_millis(5)),
+        Err(Error::Timeout { .. })
+    ));
+}
+
+#[test]
+fn flush_and_wait_reports_a_dropped_receiver() {
+    for (sender, receiver) in [Queue::channel(), Queue::bounded_channel(4)] {
+        sender.send(1).unwrap();
+        drop(receiver);
+
+        assert!(matches!(
+            sender.flush_and_wait(Duration::from_secs(5)),
+            Err(Error::Disconnected {
+                endpoint: Endpoint::Sender
+            })
+        ));
+    }
+}
+
+struct Blob(usize);
+
+impl MessageSize for Blob {
+    fn size_hint(&self) -> usize {
+        self.0
+    }
+}
+
+#[test]
+fn byte_bounded_queue_blocks_producer_over_budget() {
+    let (sender, receiver) = Queue::byte_bounded(100);
+    sender.send(Blob(40)).unwrap();
+    sender.send(Blob(40)).unwrap();
+
+    let producer = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(Blob(30)))
+    };
+    thread::sleep(Duration::from_millis(20));
+    assert!(!producer.is_finished());
+    assert_eq!(sender.queued_bytes(), 80);
+
+    assert_eq!(receiver.recv_blocking().unwrap().0, 40);
+    producer.join().unwrap().unwrap();
+    assert_eq!(sender.queued_bytes(), 70);
+}
+
+#[test]
+fn memory_budget_blocks_sends_once_the_combined_budget_is_hit() {
+    let budget = MemoryBudget::new(100);
+    let (telemetry, telemetry_receiver) = Queue::byte_bounded_in(&budget);
+    let (commands, commands_receiver) = Queue::byte_bounded_in(&budget);
+    assert_eq!(telemetry.byte_limit(), 100);
+
+    telemetry.send(Blob(70)).unwrap();
+    commands.send(Blob(30)).unwrap();
+    assert_eq!(budget.queued_bytes(), 100);
+    assert_eq!(commands_receiver.recv_blocking().unwrap().0, 30);
+    telemetry.send(Blob(30)).unwrap();
+
+    let producers = [&telemetry, &commands].map(|sender| {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(Blob(10)))
+    });
+    thread::sleep(Duration::from_millis(20));
+    assert!(producers.iter().all(|producer| !producer.is_finished()));
+
+    assert_eq!(telemetry_receiver.recv_blocking().unwrap().0, 70);
+    for producer in producers {
+        producer.join().unwrap().unwrap();
+    }
+    assert_eq!(budget.queued_bytes(), 50);
+}
+
+#[test]
+fn memory_budget_gets_bytes_back_when_a_receiver_drops() {
+    let budget = MemoryBudget::new(100);
+    let (telemetry, _telemetry_receiver) = Queue::byte_bounded_in(&budget);
+    let (commands, commands_receiver) = Queue::byte_bounded_in(&budget);
+
+    telemetry.send(Blob(40)).unwrap();
+    commands.send(Blob(60)).unwrap();
+
+    let producer = thread::spawn(move || telemetry.send(Blob(60)));
+    thread::sleep(Duration::from_millis(20));
+    assert!(!producer.is_finished());
+
+    drop(commands_receiver);
+    producer.join().unwrap().unwrap();
+    assert_eq!(budget.queued_bytes(), 100);
+}
+
+#[test]
+fn byte_bounded_sender_fails_once_receiver_is_dropped() {
+    let (sender, receiver) = Queue::byte_bounded(10);
+    sender.send(Blob(10)).unwrap();
+
+    let producer = thread::spawn(move || sender.send(Blob(5)));
+    thread::sleep(Duration::from_millis(5));
+    drop(receiver);
+
+    assert!(matches!(
+        producer.join().unwrap(),
+        Err(Error::Disconnected { .. })
+    ));
+}
+
+#[test]
+fn catch_up_keeps_newest_messages_and_reports_discarded() {
+    let (sender, receiver) = Queue::channel();
+    for i in 0..100 {
+        sender.send(i).unwrap();
+    }
+
+    let (recent, dropped) = receiver.catch_up(5);
+    assert_eq!(recent, vec![95, 96, 97, 98, 99]);
+    assert_eq!(dropped, 95);
+    assert_eq!(receiver.catch_up(5), (Vec::new(), 0));
+}
+
+#[test]
+fn iter_yields_until_sender_drops() {
+    let (sender, receiver) = Queue::channel();
+
+    let producer = thread::spawn(move || {
+        for i in 0..3 {
+            sender.send(i).unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+    });
+
+    assert_eq!(
+        receiver.iter().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![0, 1, 2]
+    );
+    producer.join().unwrap();
+}
+
+#[test]
+fn try_iter_stops_at_empty_queue() {
+    let (sender, receiver) = Queue::channel();
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(receiver.try_iter().next(), None);
+}
+
+#[derive(Debug, PartialEq)]
+enum SimInput {
+    Input(u32),
+    Config(&'static str),
+    Unknown,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Route {
+    Input,
+    Config,
+    Other,
+}
+
+#[test]
+fn router_partitions_mixed_stream_by_key() {
+    let (sender, receiver) = Queue::channel();
+    let mut router = receiver.route_by(|msg: &SimInput| match msg {
+        SimInput::Input(_) => Route::Input,
+        SimInput::Config(_) => Route::Config,
+        SimInput::Unknown => Route::Other,
+    });
+    let inputs = router.route(Route::Input);
+    let configs = router.route(Route::Config);
+
+    for msg in [
+        SimInput::Input(1),
+        SimInput::Config("gravity"),
+        SimInput::Unknown,
+        SimInput::Input(2),
+    ] {
+        sender.send(msg).unwrap();
+    }
+
+    assert_eq!(router.dispatch(), 3);
+    assert_eq!(router.unrouted(), 1);
+    assert_eq!(
+        inputs.receive(),
+        vec![SimInput::Input(1), SimInput::Input(2)]
+    );
+    assert_eq!(configs.receive(), vec![SimInput::Config("gravity")]);
+}
+
+fn delivered(seed: u64, loss: f64) -> Vec<u32> {
+    let queue = LossyQueue::new(seed, loss);
+    for i in 0..64 {
+        queue.send(i);
+    }
+    assert_eq!(queue.dropped() + queue.len() as u64, 64);
+    queue.receive()
+}
+
+fn jitter_delays(seed: u64) -> Vec<Duration> {
+    let queue = JitterQueue::new(seed, Duration::from_millis(5)..=Duration::from_millis(20))
+        .with_clock(MockClock::new());
+    (0..32).map(|i| queue.send(i)).collect()
+}
+
+#[test]
+fn jitter_queue_draws_reproducible_delays_within_bounds() {
+    let delays = jitter_delays(3);
+
+    assert_eq!(delays, jitter_delays(3));
+    assert_ne!(delays, jitter_delays(4));
+    assert!(
+        delays.iter().all(|delay| {
+            (Duration::from_millis(5)..=Duration::from_millis(20)).contains(delay)
+        })
+    );
+}
+
+#[test]
+fn jitter_queue_delivers_messages_once_their_delay_elapsed() {
+    let clock = MockClock::new();
+    let queue = JitterQueue::new(9, Duration::from_millis(1)..=Duration::from_millis(10))
+        .with_clock(clock.clone());
+    let mut delays: Vec<(Duration, u32)> = (0..8).map(|i| (queue.send(i), i)).collect();
+    delays.sort();
+
+    assert!(queue.receive().is_empty());
+    clock.advance(delays[3].0);
+    let early = queue.receive();
+    assert!(early.len() >= 4);
+    assert_eq!(
+        early[..4],
+        delays[..4].iter().map(|(_, i)| *i).collect::<Vec<_>>()[..]
+    );
+
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(early.len() + queue.receive().len(), 8);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn jitter_queue_clones_share_messages_without_a_clone_bound() {
+    struct Token;
+
+    let clock = MockClock::new();
+    let queue = JitterQueue::new(3, Duration::ZERO..=Duration::ZERO).with_clock(clock);
+    queue.clone().send(Token);
+
+    assert_eq!(queue.receive().len(), 1);
+}
+
+#[test]
+fn lossy_queue_drops_the_same_messages_for_a_seed() {
+    let kept = delivered(11, 0.5);
+
+    assert_eq!(kept, delivered(11, 0.5));
+    assert_ne!(kept, delivered(12, 0.5));
+    assert!((16..=48).contains(&kept.len()), "kept {}", kept.len());
+    assert_eq!(delivered(11, 0.0), (0..64).collect::<Vec<_>>());
+    assert!(delivered(11, 1.0).is_empty());
+}
+
+#[test]
+fn lossy_queue_clones_share_messages_without_a_clone_bound() {
+    struct Token;
+
+    let queue = LossyQueue::new(3, 0.0);
+    queue.clone().send(Token);
+
+    assert_eq!(queue.receive().len(), 1);
+}
+
+fn shuffled(seed: u64, window: usize) -> Vec<u32> {
+    let queue = ShuffleQueue::new(seed, window);
+    for i in 0..32 {
+        queue.send(i);
+    }
+    queue.receive()
+}
+
+#[test]
+fn shuffle_queue_is_deterministic_for_a_seed() {
+    let order = shuffled(7, 4);
+
+    assert_eq!(order, shuffled(7, 4));
+    assert_ne!(order, (0..32).collect::<Vec<_>>());
+
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..32).collect::<Vec<_>>());
+}
+
+#[test]
+fn shuffle_queue_with_zero_window_preserves_order() {
+    assert_eq!(shuffled(7, 0), (0..32).collect::<Vec<_>>());
+}
+
+#[test]
+fn shuffle_queue_moves_messages_at_most_the_window() {
+    for seed in 0..20 {
+        for (position, msg) in shuffled(seed, 3).into_iter().enumerate() {
+            assert!(position.abs_diff(msg as usize) <= 3, "seed {seed}");
+        }
+    }
+}
+
+#[test]
+fn receiver_strategy_overrides_thread_default() {
+    let (sender, receiver) = Queue::channel();
+    assert_eq!(receiver.receive_strategy(), ReceiveStrategy::Park);
+
+    let receiver = receiver.with_receive_strategy(ReceiveStrategy::Yield);
+    assert_eq!(receiver.receive_strategy(), ReceiveStrategy::Yield);
+
+    sender.send(1).unwrap();
+    assert_eq!(receiver.wait(), vec![1]);
+}
+
+#[test]
+fn broadcast_drops_oldest_for_slow_subscriber_only() {
+    let broadcast = Broadcast::new(OverflowPolicy::DropOldest);
+    let fast = broadcast.subscribe(8);
+    let slow = broadcast.subscribe(2);
+
+    for i in 0..5 {
+        broadcast.send(i);
+    }
+
+    assert_eq!(fast.receive(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(slow.receive(), vec![3, 4]);
+    assert_eq!(broadcast.overflowed(), 3);
+    assert_eq!(fast.dropped_count(), 0);
+    assert_eq!(slow.dropped_count(), 3);
+}
+
+#[test]
+fn broadcast_disconnects_overflowing_subscriber() {
+    let broadcast = Broadcast::new(OverflowPolicy::Disconnect);
+    let fast = broadcast.subscribe(8);
+    let slow = broadcast.subscribe(2);
+
+    for i in 0..5 {
+        broadcast.send(i);
+    }
+
+    assert_eq!(broadcast.subscriber_count(), 1);
+    assert_eq!(fast.receive(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(slow.receive(), vec![0, 1]);
+    assert!(slow.recv_blocking().is_err());
+}
+
+#[test]
+fn coalescing_queue_merges_pending_deltas() {
+    let queue = CoalescingQueue::new(|a: (i32, i32), b: (i32, i32)| (a.0 + b.0, a.1 + b.1));
+
+    for delta in [(1, 0), (2, -1), (0, 5)] {
+        queue.send(delta);
+    }
+
+    assert_eq!(queue.receive(), Some((3, 4)));
+    assert_eq!(queue.receive(), None);
+
+    queue.send((7, 7));
+    assert_eq!(queue.receive(), Some((7, 7)));
+}
+
+#[test]
+fn capacity_reflects_configured_bound() {
+    let (sender, receiver) = Queue::bounded_channel::<u32>(16);
+    assert_eq!(receiver.capacity(), Some(16));
+    assert_eq!(sender.capacity(), Some(16));
+
+    let (sender, receiver) = Queue::channel::<u32>();
+    assert_eq!(receiver.capacity(), None);
+    assert_eq!(sender.capacity(), None);
+}
+
+#[test]
+fn capacity_hint_reserves_room_in_received_batches() {
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_capacity_hint(32);
+
+    assert_eq!(receiver.receive().capacity(), 0);
+    sender.send(1).unwrap();
+    assert!(receiver.receive().capacity() >= 32);
+
+    let receiver = receiver.with_drain_at_most(4);
+    sender.send(2).unwrap();
+    assert_eq!(receiver.receive(), vec![2]);
+}
+
+#[test]
+fn send_and_close_delivers_final_message_before_disconnect() {
+    let (sender, receiver) = Queue::channel();
+    sender.send(1).unwrap();
+    sender.send_and_close(2).unwrap();
+
+    assert_eq!(receiver.recv_blocking().unwrap(), 1);
+    assert_eq!(receiver.recv_blocking().unwrap(), 2);
+    assert!(matches!(
+        receiver.recv_blocking(),
+        Err(Error::Disconnected { .. })
+    ));
+}
+
+#[test]
+fn send_and_close_refuses_while_other_clones_are_alive() {
+    let (sender, receiver) = Queue::channel::<u32>();
+    let clone = sender.clone();
+
+    assert!(matches!(
+        sender.send_and_close(1),
+        Err(Error::SenderInUse { clones: 1 })
+    ));
+    assert!(receiver.receive().is_empty());
+    clone.send_and_close(2).unwrap();
+    assert_eq!(receiver.receive_state(), RecvState::Messages(vec![2]));
+    assert_eq!(receiver.receive_state(), RecvState::Disconnected);
+}
+
+#[test]
+fn causal_receiver_delivers_in_happens_before_order() {
+    let (orders, order_queue) = Queue::channel();
+    let (fills, fill_queue) = Queue::channel();
+    let receiver = CausalReceiver::new([order_queue, fill_queue]);
+
+    fills.send(Causal::new("fill", 2).after(1)).unwrap();
+    fills.send(Causal::new("settle", 3).after(2)).unwrap();
+    assert!(receiver.receive().is_empty());
+    assert_eq!(receiver.pending(), 2);
+
+    orders.send(Causal::new("order", 1)).unwrap();
+    let delivered: Vec<_> = receiver
+        .receive()
+        .into_iter()
+        .map(Causal::into_inner)
+        .collect();
+
+    assert_eq!(delivered, ["order", "fill", "settle"]);
+    assert_eq!(receiver.pending(), 0);
+}
+
+#[test]
+fn causal_receiver_keeps_each_queue_in_send_order() {
+    let (left, left_queue) = Queue::channel();
+    let (right, right_queue) = Queue::channel();
+    let receiver = CausalReceiver::new([left_queue, right_queue]);
+
+    left.send(Causal::new("wait", 2).after(1)).unwrap();
+    left.send(Causal::new("free", 3)).unwrap();
+    assert!(receiver.receive().is_empty());
+    assert_eq!(receiver.pending(), 2);
+
+    right.send(Causal::new("cause", 1)).unwrap();
+    right.send(Causal::new("late", 5)).unwrap();
+    let delivered: Vec<_> = receiver
+        .receive()
+        .into_iter()
+        .map(Causal::into_inner)
+        .collect();
+
+    assert_eq!(delivered, ["cause", "wait", "free", "late"]);
+}
+
+#[test]
+fn causal_receiver_counts_dependencies_below_every_watermark_as_delivered() {
+    let (left, left_queue) = Queue::channel();
+    let (right, right_queue) = Queue::channel();
+    let receiver = CausalReceiver::new([left_queue, right_queue]);
+
+    left.send(Causal::new("left", 4)).unwrap();
+    right.send(Causal::new("right", 6)).unwrap();
+    assert_eq!(receiver.receive().len(), 2);
+
+    // Stamp 3 was never delivered, but both queues are past it.
+    left.send(Causal::new("old", 10).after(3)).unwrap();
+    // Stamp 8 may still come from the right queue.
+    left.send(Causal::new("new", 12).after(8)).unwrap();
+    let delivered: Vec<_> = receiver
+        .receive()
+        .into_iter()
+        .map(Causal::into_inner)
+        .collect();
+
+    assert_eq!(delivered, ["old"]);
+    assert_eq!(receiver.pending(), 1);
+
+    right.send(Causal::new("cause", 8)).unwrap();
+    assert_eq!(receiver.receive().len(), 2);
+}
+
+#[test]
+fn sequenced_receiver_merges_queues_in_global_order() {
+    let sequencer = Sequencer::new();
+    let (left, left_queue) = sequencer.channel();
+    let (right, right_queue) = sequencer.channel();
+    let merged = SequencedReceiver::new([left_queue, right_queue]);
+
+    let mut stamps = Vec::new();
+    for (i, sender) in [&left, &right, &right, &left, &right]
+        .into_iter()
+        .enumerate()
+    {
+        stamps.push((sender.send(i).unwrap(), i));
+    }
+
+    assert_eq!(merged.receive(), stamps);
+    assert_eq!(merged.pending(), 0);
+}
+
+#[test]
+fn sequenced_receiver_holds_messages_behind_a_gap() {
+    let sequencer = Sequencer::new();
+    let (merged_sender, merged_queue) = sequencer.channel();
+    let (other_sender, _other_queue) = sequencer.channel();
+    let merged = SequencedReceiver::new([merged_queue]);
+
+    merged_sender.send("a").unwrap();
+    other_sender.send("elsewhere").unwrap();
+    merged_sender.send("b").unwrap();
+
+    assert_eq!(merged.receive(), [(0, "a")]);
+    assert_eq!(merged.pending(), 1);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn queue_histogram_buckets_sizes_and_frame_counts() {
+    let histogram = message::QueueHistogram::new();
+    let (sender, _receiver) = Queue::channel();
+    let sender = sender.with_histogram(&histogram);
+
+    for size in [1, 3, 4, 100, 300, 512] {
+        sender.send(Blob(size)).unwrap();
+    }
+    histogram.end_frame();
+    sender.send(Blob(2)).unwrap();
+    histogram.end_frame();
+
+    assert_eq!(
+        histogram.sizes(),
+        [(1, 1), (2, 1), (4, 2), (128, 1), (512, 2)]
+    );
+    assert_eq!(histogram.frame_counts(), [(1, 1), (8, 1)]);
+}
+
+#[test]
+fn try_recv_one_takes_messages_one_at_a_time() {
+    let (sender, receiver) = Queue::channel();
+    assert_eq!(receiver.try_recv_one(), None);
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    assert_eq!(receiver.try_recv_one(), Some(1));
+    assert_eq!(receiver.try_recv_one(), Some(2));
+    assert_eq!(receiver.try_recv_one(), None);
+}
+
+#[test]
+fn send_at_next_frame_waits_for_frame_boundary() {
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+    let (sender, receiver) = Queue::channel();
+    let sender = sender.with_frame_boundary(&ctx);
+
+    sender.send_at_next_frame(1).unwrap();
+    sender.send(2).unwrap();
+    assert_eq!(receiver.receive(), [2]);
+
+    ctx.advance_frame();
+    assert_eq!(receiver.receive(), [1]);
+    ctx.advance_frame();
+    assert!(receiver.receive().is_empty());
+}
+
+#[test]
+fn send_at_next_frame_needs_a_frame_boundary() {
+    let (sender, receiver) = Queue::channel();
+
+    assert!(matches!(
+        sender.send_at_next_frame(1),
+        Err(Error::NoFrameBoundary)
+    ));
+    assert!(receiver.receive().is_empty());
+}
+
+#[test]
+fn send_at_next_frame_records_deferred_messages_in_the_tap() {
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+    let tap = MessageTap::new(4);
+    let (sender, receiver) = Queue::tapped_channel(&tap);
+    let sender = sender.with_frame_boundary(&ctx);
+
+    sender.send_at_next_frame(7).unwrap();
+    assert!(tap.recent().is_empty());
+
+    ctx.advance_frame();
+    assert_eq!(receiver.receive(), [7]);
+    assert_eq!(tap.recent(), ["7"]);
+}
+
+#[derive(Clone)]
+struct Formatted(Arc<AtomicUsize>);
+
+impl fmt::Debug for Formatted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        f.write_str("Formatted")
+    }
+}
+
+#[test]
+fn tap_formats_only_messages_that_were_sent() {
+    let formats = Arc::new(AtomicUsize::new(0));
+    let tap = MessageTap::new(4);
+    let (sender, receiver) = Queue::tapped_channel(&tap);
+
+    sender.send(Formatted(formats.clone())).unwrap();
+    assert_eq!(formats.load(Ordering::Relaxed), 1);
+
+    drop(receiver);
+    assert!(sender.send(Formatted(formats.clone())).is_err());
+    assert_eq!(formats.load(Ordering::Relaxed), 1);
+    assert_eq!(tap.recent(), ["Formatted"]);
+}
+
+#[test]
+fn drop_oldest_queue_counts_evicted_messages() {
+    let (sender, receiver) = Queue::drop_oldest_channel(3);
+
+    for msg in 1..=7 {
+        sender.send(msg).unwrap();
+    }
+
+    assert_eq!(sender.dropped_count(), 4);
+    assert_eq!(receiver.dropped_count(), 4);
+    assert_eq!(receiver.receive(), [5, 6, 7]);
+}
+
+#[test]
+fn upgraded_drop_oldest_sender_evicts_into_the_shared_count() {
+    let (sender, receiver) = Queue::drop_oldest_channel(2);
+    let upgraded = sender.downgrade().upgrade().unwrap();
+
+    for msg in 1..=5 {
+        upgraded.send(msg).unwrap();
+    }
+
+    assert_eq!(upgraded.dropped_count(), 3);
+    assert_eq!(sender.dropped_count(), 3);
+    assert_eq!(receiver.dropped_count(), 3);
+    assert_eq!(receiver.receive(), [4, 5]);
+}
+
+#[test]
+fn per_sender_fifo_keeps_each_senders_order() {
+    let (sender, receiver) = Queue::ordered_channel(OrderingMode::default());
+    assert_eq!(receiver.mode(), OrderingMode::PerSenderFifo);
+
+    let producers: Vec<_> = (0..4)
+        .map(|id| {
+            let sender = sender.clone();
+            thread::spawn(move || (0..100).for_each(|seq| sender.send((id, seq)).unwrap()))
+        })
+        .collect();
+    producers
+        .into_iter()
+        .for_each(|producer| producer.join().unwrap());
+
+    let received = receiver.receive();
+    assert_eq!(received.len(), 400);
+    for id in 0..4 {
+        let seqs: Vec<_> = received
+            .iter()
+            .filter(|(from, _)| *from == id)
+            .map(|&(_, seq)| seq)
+            .collect();
+        assert_eq!(seqs, (0..100).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn total_order_delivers_globally_sorted_across_senders() {
+    let (sender, receiver) = Queue::ordered_channel(OrderingMode::TotalOrder);
+    let clock = Arc::new(Mutex::new(0));
+
+    let producers: Vec<_> = (0..4)
+        .map(|_| {
+            let sender = sender.clone();
+            let clock = clock.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let mut now = clock.lock().unwrap();
+                    sender.send(*now).unwrap();
+                    *now += 1;
+                }
+            })
+        })
+        .collect();
+    producers
+        .into_iter()
+        .for_each(|producer| producer.join().unwrap());
+
+    assert_eq!(receiver.receive(), (0..400).collect::<Vec<_>>());
+}
+
+#[test]
+fn buffered_sender_delivers_in_batches_on_flush() {
+    let (sender, receiver) = Queue::channel();
+    let buffered = BufferedSender::new(sender);
+
+    for value in 0..100 {
+        buffered.send(value).unwrap();
+        if value == 59 {
+            assert!(receiver.receive().is_empty());
+            buffered.flush().unwrap();
+        }
+    }
+    assert_eq!(buffered.buffered(), 40);
+    buffered.flush().unwrap();
+
+    let batches = receiver.receive();
+    assert_eq!(
+        batches.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![60, 40]
+    );
+    assert_eq!(batches.concat(), (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn buffered_sender_flushes_at_frame_boundary() {
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+    let (sender, receiver) = Queue::channel();
+    let buffered = BufferedSender::new(sender).with_frame_boundary(&ctx);
+
+    buffered.send(1).unwrap();
+    buffered.send(2).unwrap();
+    ctx.advance_frame();
+    assert!(receiver.receive().is_empty());
+
+    buffered.send(3).unwrap();
+    assert_eq!(receiver.receive(), vec![vec![1, 2]]);
+
+    drop(buffered);
+    assert_eq!(receiver.receive(), vec![vec![3]]);
+}
+
+#[test]
+fn receive_backoff_returns_promptly_when_messages_are_queued() {
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_backoff(Backoff::new().with_spin_limit(3).with_yield_limit(2));
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    let start = Instant::now();
+    assert_eq!(receiver.receive_backoff(), vec![1, 2]);
+    assert!(start.elapsed() < Duration::from_millis(50));
+
+    let sending = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.send(3).unwrap();
+    });
+    assert_eq!(receiver.receive_backoff(), vec![3]);
+    sending.join().unwrap();
+    assert!(receiver.receive_backoff().is_empty());
+}
+
+#[test]
+fn backoff_spin_limit_is_clamped() {
+    let backoff = Backoff::new().with_spin_limit(u32::MAX).with_yield_limit(0);
+    assert_eq!(backoff.spin_limit(), Backoff::MAX_SPIN_LIMIT);
+
+    let (sender, receiver) = Queue::channel::<u32>();
+    let receiver = receiver.with_backoff(backoff);
+    drop(sender);
+    assert!(receiver.receive_backoff().is_empty());
+}
+
+#[test]
+fn state_sync_reader_always_sees_the_newest_snapshot() {
+    let sync = StateSync::new();
+    assert_eq!(sync.latest(), None::<Vec<u32>>);
+
+    let mut state = Vec::new();
+    for tick in 1..=3 {
+        state.push(tick);
+        sync.publish(&state);
+    }
+    assert_eq!(sync.latest(), Some(vec![1, 2, 3]));
+    assert_eq!(sync.latest(), Some(vec![1, 2, 3]));
+    assert_eq!(sync.version(), 3);
+
+    let reader = sync.clone();
+    let publisher = thread::spawn(move || {
+        for tick in 0..1000_u32 {
+            sync.publish(&vec![tick]);
+        }
+    });
+    let mut last = 0;
+    while !publisher.is_finished() {
+        if let Some([tick]) = reader.latest().as_deref() {
+            assert!(*tick >= last);
+            last = *tick;
+        }
+    }
+    publisher.join().unwrap();
+    assert_eq!(reader.latest(), Some(vec![999]));
+}
+
+#[test]
+fn drain_lazy_leaves_unpulled_messages_queued() {
+    let (sender, receiver) = Queue::channel();
+    (1..=5).for_each(|value| sender.send(value).unwrap());
+
+    let mut drained = Vec::new();
+    for msg in receiver.drain_lazy() {
+        drained.push(msg);
+        if drained.len() == 2 {
+            break;
+        }
+    }
+
+    assert_eq!(drained, vec![1, 2]);
+    assert_eq!(receiver.drain_lazy().collect::<Vec<_>>(), vec![3, 4, 5]);
+    assert_eq!(receiver.drain_lazy().count(), 0);
+}
+
+#[test]
+fn drain_into_appends_to_the_reused_buffer() {
+    let (sender, receiver) = Queue::channel();
+    let mut buf = Vec::with_capacity(8);
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    assert_eq!(receiver.drain_into(&mut buf), 2);
+
+    sender.send(3).unwrap();
+    assert_eq!(receiver.drain_into(&mut buf), 1);
+    assert_eq!(buf, vec![1, 2, 3]);
+
+    buf.clear();
+    assert_eq!(receiver.drain_into(&mut buf), 0);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn priority_select_serves_the_higher_priority_queue_first() {
+    let (bulk, bulk_receiver) = Queue::channel();
+    let (control, control_receiver) = Queue::channel();
+    let select = PrioritySelect::new()
+        .with_receiver(bulk_receiver, 1)
+        .with_receiver(control_receiver, 10);
+
+    bulk.send("frame").unwrap();
+    bulk.send("frame").unwrap();
+    control.send("stop").unwrap();
+
+    assert_eq!(select.select().unwrap(), "stop");
+    assert_eq!(select.select().unwrap(), "frame");
+    assert_eq!(select.try_select(), Some("frame"));
+    assert_eq!(select.try_select(), None);
+
+    let sending = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        bulk.send("late").unwrap();
+    });
+    assert_eq!(select.select().unwrap(), "late");
+    sending.join().unwrap();
+
+    drop(control);
+    assert!(matches!(select.select(), Err(Error::Disconnected { .. })));
+}
+
+#[test]
+fn priority_select_waits_on_the_live_queue_once_another_disconnected() {
+    let (bulk, bulk_receiver) = Queue::channel();
+    let (control, control_receiver) = Queue::channel::<&str>();
+    let select = PrioritySelect::new()
+        .with_receiver(bulk_receiver, 1)
+        .with_receiver(control_receiver, 10);
+    drop(control);
+
+    let sending = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        bulk.send("late").unwrap();
+    });
+    assert_eq!(select.select().unwrap(), "late");
+    sending.join().unwrap();
+
+    assert!(matches!(select.select(), Err(Error::Disconnected { .. })));
+}
+
+#[test]
+fn any_message_receive_only_returns_its_own_type() {
+    #[derive(Debug, PartialEq)]
+    struct Move(i32);
+
+    let queue = AnyMessage::new();
+    let sender = queue.clone();
+    sender.send(Move(1));
+    sender.send(String::from("hello"));
+    sender.send(Move(2));
+    assert_eq!(queue.len(), 3);
+
+    assert_eq!(queue.receive::<Move>(), vec![Move(1), Move(2)]);
+    assert!(queue.receive::<u32>().is_empty());
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.receive::<String>(), vec![String::from("hello")]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn sampled_channel_keeps_one_in_every_n_messages() {
+    let sampler = MessageSampler::new(10, 64);
+    let (sender, receiver) = Queue::sampled_channel(&sampler);
+
+    for i in 0..100 {
+        sender.send(i).unwrap();
+    }
+
+    assert_eq!(receiver.receive().len(), 100);
+    assert_eq!(sampler.seen(), 100);
+    assert_eq!(
+        sampler.sampled(),
+        vec![9, 19, 29, 39, 49, 59, 69, 79, 89, 99]
+    );
+}
+
+#[test]
+fn priority_queue_is_fifo_within_a_priority() {
+    let queue = PriorityQueue::new();
+    let sender = queue.clone();
+
+    for msg in 0..8 {
+        sender.send(1, msg);
+    }
+    sender.send(5, 100);
+    sender.send(0, 200);
+    sender.send(5, 101);
+
+    assert_eq!(queue.try_receive(), Some(100));
+    assert_eq!(queue.try_receive(), Some(101));
+    assert_eq!(queue.try_receive(), Some(0));
+    assert_eq!(queue.receive(), [1, 2, 3, 4, 5, 6, 7, 200]);
+    assert!(queue.is_empty());
+}
+
+#[allow(dead_code)]
+enum SmallMessage {
+    Tick,
+    Move(u32, u32),
+}
+
+#[allow(dead_code, clippy::large_enum_variant)]
+enum OversizedMessage {
+    Tick,
+    Frame([u8; 4096]),
+}
+
+#[test]
+fn message_size_check_flags_oversized_enums() {
+    message::debug_assert_message_size::<SmallMessage>(16);
+    assert_eq!(message::check_message_size::<SmallMessage>(16), None);
+
+    let warning = message::check_message_size::<OversizedMessage>(64).unwrap();
+    assert!(matches!(
+        warning,
+        Warning::MessageSize { size: 4097, max: 64, type_name } if type_name.ends_with("OversizedMessage")
+    ));
+    assert!(warning.to_string().contains("boxing"));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "consider boxing")]
+fn debug_assert_message_size_panics_when_oversized() {
+    message::debug_assert_message_size::<OversizedMessage>(64);
+}
+
+#[test]
+fn shutdown_send_into_full_queue_goes_to_dead_letters() {
+    let (sender, receiver) = Queue::bounded_channel(1);
+    let ctx = AgentContext::new(AgentId::CONTROLLER);
+    let dead_letters = DeadLetterQueue::new();
+    let sender = sender.with_dead_letters(&ctx, &dead_letters);
+    sender.send(1).unwrap();
+
+    let producer = thread::spawn({
+        let sender = sender.clone();
+        move || sender.send(2)
+    });
+    thread::sleep(Duration::from_millis(20));
+    assert!(!producer.is_finished());
+
+    ctx.cancellation_token().cancel();
+    let start = Instant::now();
+    assert!(producer.join().unwrap().is_ok());
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(sender.send(3).is_ok());
+
+    assert_eq!(dead_letters.drain(), vec![2, 3]);
+    assert_eq!(receiver.receive(), vec![1]);
+}
+
+#[test]
+fn drain_cap_spills_the_backlog_into_later_frames() {
+    let (sender, receiver) = Queue::channel();
+    let receiver = receiver.with_drain_at_most(10);
+    (0..25).for_each(|value| sender.send(value).unwrap());
+
+    let mut processed = Vec::new();
+    for expected in [10, 10, 5] {
+        let frame = receiver.receive();
+        assert_eq!(frame.len(), expected);
+        processed.extend(frame);
+        assert_eq!(receiver.backlog(), 25 - processed.len());
+    }
+
+    assert_eq!(processed, (0..25).collect::<Vec<_>>());
+    assert!(receiver.receive().is_empty());
+}
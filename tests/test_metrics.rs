@@ -0,0 +1,241 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::with_context;
+use multi_agent_engine::{
+    Error, Metrics, MockClock, MultiAgentEngine, Result, Simulator, Step, SteppedEngine,
+    message::{self, MessageKind, Queue, RateLimitPolicy, RateLimitedSender},
+};
+use std::{ops::ControlFlow, time::Duration};
+
+struct Emitter {
+    sender: message::Sender<u32>,
+    remaining: u32,
+}
+
+impl Step for Emitter {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        if self.remaining == 0 {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        self.sender.send(self.remaining)?;
+        self.remaining -= 1;
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+struct Sink;
+
+impl Step for Sink {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        Ok(ControlFlow::Break(()))
+    }
+}
+
+fn run_frames(metrics: &Metrics) -> message::Receiver<u32> {
+    let (sender, receiver) = Queue::channel();
+    let emitter = Emitter {
+        sender: sender.with_metrics(metrics.clone()),
+        remaining: 3,
+    };
+
+    SteppedEngine::new(emitter, Sink)
+        .with_metrics(metrics.clone())
+        .run()
+        .unwrap();
+
+    receiver
+}
+
+#[test]
+fn sender_and_stepped_engine_record_metrics() {
+    let metrics = Metrics::new();
+    let _receiver = run_frames(&metrics);
+
+    assert_eq!(metrics.messages_sent(), 3);
+    assert_eq!(metrics.queue_depth(), 3);
+    assert_eq!(metrics.frames(), 4);
+}
+
+#[test]
+fn rate_limited_drops_are_recorded() {
+    let metrics = Metrics::new();
+    let (sender, _receiver) = Queue::channel();
+    let sender = RateLimitedSender::new(
+        sender.with_metrics(metrics.clone()),
+        1,
+        RateLimitPolicy::Drop,
+    )
+    .with_clock(MockClock::new());
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    assert_eq!(metrics.messages_sent(), 1);
+    assert_eq!(metrics.messages_dropped(), 1);
+}
+
+#[test]
+fn frame_time_tracks_last_frame() {
+    let metrics = Metrics::new();
+    metrics.record_frame(Duration::from_millis(4));
+    metrics.record_frame(Duration::from_millis(2));
+
+    assert_eq!(metrics.frames(), 2);
+    assert_eq!(metrics.frame_time(), Duration::from_millis(2));
+}
+
+#[test]
+fn samples_during_warmup_are_ignored() {
+    let clock = MockClock::new();
+    let metrics = Metrics::new()
+        .with_clock(clock.clone())
+        .with_warmup(Duration::from_secs(1));
+
+    metrics.record_sent(5);
+    metrics.record_dropped();
+    metrics.record_frame(Duration::from_millis(40));
+    assert!(metrics.is_warming_up());
+
+    clock.advance(Duration::from_secs(1));
+    metrics.record_sent(1);
+    metrics.record_frame(Duration::from_millis(4));
+
+    assert!(!metrics.is_warming_up());
+    assert_eq!(metrics.messages_sent(), 1);
+    assert_eq!(metrics.messages_dropped(), 0);
+    assert_eq!(metrics.queue_depth(), 1);
+    assert_eq!(metrics.frames(), 1);
+    assert_eq!(metrics.frame_time(), Duration::from_millis(4));
+}
+
+struct Ping;
+
+impl MessageKind for Ping {
+    fn kind(&self) -> &'static str {
+        "Ping"
+    }
+}
+
+#[test]
+fn latencies_land_in_the_message_stats_after_warmup() {
+    let clock = MockClock::new();
+    let metrics = Metrics::new()
+        .with_clock(clock.clone())
+        .with_warmup(Duration::from_secs(1));
+
+    metrics.record_latency(&Ping, Duration::from_millis(9));
+    clock.advance(Duration::from_secs(1));
+    metrics.record_latency(&Ping, Duration::from_micros(20));
+    metrics
+        .clone()
+        .record_latency(&Ping, Duration::from_micros(40));
+
+    assert_eq!(metrics.message_stats().count("Ping"), 2);
+    let mut csv = Vec::new();
+    metrics.message_stats().write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "kind,count,p50_latency_ns,p99_latency_ns\nPing,2,20000,40000\n"
+    );
+}
+
+struct Draining {
+    receiver: message::Receiver<u32>,
+}
+
+impl Simulator for Draining {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while self.receiver.recv_blocking().is_ok() {}
+        Ok(())
+    }
+}
+
+#[test]
+fn engine_metrics_count_agent_sends_and_frames() {
+    let (sender, receiver) = Queue::channel();
+    let controller = with_context(move |ctx| {
+        for msg in 0..3 {
+            sender.send(msg)?;
+            ctx.advance_frame();
+        }
+        Ok(())
+    });
+    let engine = MultiAgentEngine::new(controller, Draining { receiver });
+    let metrics = engine.metrics();
+
+    engine.run().unwrap();
+
+    assert_eq!(metrics.messages_sent(), 3);
+    assert_eq!(metrics.frames(), 3);
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn prometheus_registry_exports_engine_metrics() {
+    use multi_agent_engine::Controller;
+    use prometheus::{Encoder, TextEncoder};
+
+    struct Idle;
+
+    impl Controller for Idle {
+        type Error = Error;
+
+        fn run(self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Simulator for Idle {
+        type Error = Error;
+
+        fn run(self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let metrics = Metrics::new();
+    let _receiver = run_frames(&metrics);
+    metrics.record_dropped();
+
+    let engine = MultiAgentEngine::new(Idle, Idle).with_metrics(metrics);
+    let registry = engine.metrics_registry();
+    let scrape = || {
+        let mut text = Vec::new();
+        TextEncoder::new()
+            .encode(&registry.gather(), &mut text)
+            .unwrap();
+        String::from_utf8(text).unwrap()
+    };
+    assert_eq!(scrape(), scrape());
+    let text = scrape();
+
+    for line in [
+        "multi_agent_engine_messages_sent_total 3",
+        "multi_agent_engine_messages_dropped_total 1",
+        "multi_agent_engine_queue_depth 3",
+        "multi_agent_engine_frames_total 4",
+    ] {
+        assert!(text.contains(line), "missing {line:?} in:\n{text}");
+    }
+    assert!(text.contains("multi_agent_engine_frame_time_seconds "));
+    assert!(!text.contains("multi_agent_engine_frame_time_seconds 0\n"));
+}
@@ -14,4 +14,1252 @@
  * limitations under the License.
  */
 
-use multi_agent_engine::MultiAgentEngine;
+mod common;
+
+use common::{
+    AccumulatingSimulator, FailingController, IdleController, IdleSimulator, PanickingSimulator,
+    PhysicsError, PingController, PongSimulator, SpinningController, with_context,
+};
+use multi_agent_engine::{
+    AgentContext, AgentId, CancellationToken, Controller, Endpoint, EngineEvent, Error, LogLevel,
+    LogSink, MultiAgentEngine, PanicPolicy, ReadOnly, Reproduction, Result, SeededRng, Shared,
+    Simulator, Warning, message,
+};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    sync::{
+        Arc, Barrier, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn run_joins_both_agents() {
+    let (sender, receiver) = message::Queue::channel();
+
+    let engine = MultiAgentEngine::new(PingController { sender }, PongSimulator { receiver });
+
+    assert!(engine.run().is_ok());
+}
+
+struct WaitingController {
+    _sender: message::Sender<u32>,
+    receiver: message::Receiver<u32>,
+}
+
+impl Controller for WaitingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.receiver.recv_blocking().map(drop)
+    }
+}
+
+struct WaitingSimulator {
+    _sender: message::Sender<u32>,
+    receiver: message::Receiver<u32>,
+}
+
+impl Simulator for WaitingSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.receiver.recv_blocking().map(drop)
+    }
+}
+
+#[test]
+fn run_reports_deadlock_when_both_agents_wait_on_each_other() {
+    let detector = message::DeadlockDetector::new();
+    let (controller_sender, controller_receiver) = message::Queue::channel();
+    let (simulator_sender, simulator_receiver) = message::Queue::channel();
+
+    let controller = WaitingController {
+        _sender: controller_sender,
+        receiver: simulator_receiver.with_deadlock_detector(&detector, AgentId::CONTROLLER),
+    };
+    let simulator = WaitingSimulator {
+        _sender: simulator_sender,
+        receiver: controller_receiver.with_deadlock_detector(&detector, AgentId::SIMULATOR),
+    };
+
+    let result = MultiAgentEngine::new(controller, simulator).run();
+
+    let Err(Error::Multiple(errors)) = result else {
+        panic!("expected both agents to fail, got {result:?}");
+    };
+    assert!(
+        errors
+            .iter()
+            .all(|err| matches!(err, Error::Deadlock { agents: 2 }))
+    );
+}
+
+#[test]
+fn run_converts_agent_panic_into_error_with_backtrace() {
+    let controller = SpinningController {
+        stop: Arc::new(AtomicBool::new(true)),
+    };
+
+    let result = MultiAgentEngine::new(controller, PanickingSimulator).run();
+
+    let Err(Error::AgentPanic {
+        agent,
+        message,
+        backtrace,
+    }) = result
+    else {
+        panic!("expected an agent panic, got {result:?}");
+    };
+    assert_eq!(agent, AgentId::SIMULATOR);
+    assert_eq!(message, "simulation diverged");
+    if Backtrace::capture().status() == BacktraceStatus::Captured {
+        assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+        assert!(!backtrace.to_string().is_empty());
+    }
+}
+
+struct TickingController {
+    token: CancellationToken,
+    ticks: Arc<AtomicUsize>,
+    limit: usize,
+}
+
+impl Controller for TickingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        while !self.token.is_cancelled() && self.ticks.load(Ordering::Relaxed) < self.limit {
+            self.ticks.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+}
+
+fn ticking_engine(policy: PanicPolicy, limit: usize) -> (Arc<AtomicUsize>, Result<()>) {
+    let token = CancellationToken::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let controller = TickingController {
+        token: token.clone(),
+        ticks: Arc::clone(&ticks),
+        limit,
+    };
+
+    let result = MultiAgentEngine::new(controller, PanickingSimulator)
+        .with_cancellation_token(token)
+        .with_simulator_panic_policy(policy)
+        .run();
+
+    (ticks, result)
+}
+
+#[test]
+fn isolate_policy_lets_peer_run_to_completion() {
+    let (ticks, result) = ticking_engine(PanicPolicy::Isolate, 20);
+
+    assert_eq!(ticks.load(Ordering::Relaxed), 20);
+    assert!(matches!(
+        result,
+        Err(Error::AgentPanic {
+            agent: AgentId::SIMULATOR,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn abort_policy_cancels_peer_on_panic() {
+    let (ticks, result) = ticking_engine(PanicPolicy::Abort, 5_000);
+
+    assert!(ticks.load(Ordering::Relaxed) < 5_000);
+    assert!(matches!(result, Err(Error::AgentPanic { .. })));
+}
+
+#[cfg(feature = "core_affinity")]
+struct AffinityController {
+    allowed: Shared<Option<String>>,
+}
+
+#[cfg(feature = "core_affinity")]
+impl Controller for AffinityController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let status = std::fs::read_to_string("/proc/thread-self/status").ok();
+        let allowed = status.and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+                .map(|list| list.trim().to_string())
+        });
+        self.allowed.store(allowed);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "core_affinity")]
+#[test]
+fn pin_controller_to_sets_thread_affinity() {
+    let allowed = Shared::new(None);
+    let controller = AffinityController {
+        allowed: allowed.clone(),
+    };
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .pin_controller_to(0)
+        .pin_simulator_to(0)
+        .spawn();
+    assert_eq!(handle.warnings(), []);
+    handle.join().unwrap();
+
+    match Option::clone(&allowed.load()).as_deref() {
+        Some(list) => assert_eq!(list, "0"),
+        None => eprintln!("thread affinity is not observable on this platform, skipping"),
+    }
+}
+
+#[cfg(feature = "core_affinity")]
+#[test]
+fn pin_to_unknown_core_reports_warning() {
+    let handle = MultiAgentEngine::new(IdleController, IdleSimulator)
+        .pin_simulator_to(usize::MAX)
+        .spawn();
+
+    assert_eq!(
+        handle.warnings(),
+        [Warning::Affinity {
+            agent: AgentId::SIMULATOR,
+            core: usize::MAX
+        }]
+    );
+    assert!(handle.join().is_ok());
+}
+
+#[cfg(feature = "thread-priority")]
+struct PriorityController {
+    priority: Shared<Option<thread_priority::ThreadPriority>>,
+}
+
+#[cfg(feature = "thread-priority")]
+impl Controller for PriorityController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.priority
+            .store(thread_priority::get_current_thread_priority().ok());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "thread-priority")]
+#[test]
+fn priority_is_applied_or_reported_as_warning() {
+    use multi_agent_engine::ThreadPriority;
+
+    let priority = Shared::new(None);
+    let controller = PriorityController {
+        priority: priority.clone(),
+    };
+
+    let handle = MultiAgentEngine::new(controller, IdleSimulator)
+        .with_controller_priority(ThreadPriority::Max)
+        .spawn();
+    let warnings = handle.warnings().to_vec();
+    assert!(handle.join().is_ok());
+
+    match warnings.as_slice() {
+        [] => {
+            let expected = thread::spawn(|| {
+                thread_priority::set_current_thread_priority(ThreadPriority::Max).unwrap();
+                thread_priority::get_current_thread_priority().ok()
+            })
+            .join()
+            .unwrap();
+            assert_eq!(**priority.load(), expected);
+        }
+        [Warning::Priority { agent, .. }] => assert_eq!(*agent, AgentId::CONTROLLER),
+        other => panic!("unexpected warnings: {other:?}"),
+    }
+}
+
+struct FailingSimulator;
+
+impl Simulator for FailingSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        Err(Error::Disconnected {
+            endpoint: Endpoint::Receiver,
+        })
+    }
+}
+
+#[test]
+fn run_reports_errors_from_both_agents() {
+    let result = MultiAgentEngine::new(FailingController, FailingSimulator).run();
+
+    let Err(Error::Multiple(errors)) = result else {
+        panic!("expected both agents to fail, got {result:?}");
+    };
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            Error::Disconnected {
+                endpoint: Endpoint::Sender
+            },
+            Error::Disconnected {
+                endpoint: Endpoint::Receiver
+            }
+        ]
+    ));
+}
+
+#[test]
+fn run_reports_single_failing_agent_unwrapped() {
+    let result = MultiAgentEngine::new(FailingController, IdleSimulator).run();
+
+    assert!(matches!(
+        result,
+        Err(Error::Disconnected {
+            endpoint: Endpoint::Sender
+        })
+    ));
+}
+
+struct RollingController {
+    rng: Option<SeededRng>,
+    rolls: Shared<Vec<u64>>,
+}
+
+impl Controller for RollingController {
+    type Error = Error;
+
+    fn run(mut self) -> Result<()> {
+        let rng = self.rng.as_mut().unwrap();
+        self.rolls.store((0..8).map(|_| rng.next_u64()).collect());
+        Ok(())
+    }
+
+    fn seed(&mut self, rng: SeededRng) {
+        self.rng = Some(rng);
+    }
+}
+
+struct RollingSimulator {
+    rng: Option<SeededRng>,
+    rolls: Shared<Vec<u64>>,
+}
+
+impl Simulator for RollingSimulator {
+    type Error = Error;
+
+    fn run(mut self) -> Result<()> {
+        let rng = self.rng.as_mut().unwrap();
+        self.rolls.store((0..8).map(|_| rng.below(1000)).collect());
+        Ok(())
+    }
+
+    fn seed(&mut self, rng: SeededRng) {
+        self.rng = Some(rng);
+    }
+}
+
+fn seeded_run(seed: u64) -> (Vec<u64>, Vec<u64>) {
+    let controller = RollingController {
+        rng: None,
+        rolls: Shared::new(Vec::new()),
+    };
+    let simulator = RollingSimulator {
+        rng: None,
+        rolls: Shared::new(Vec::new()),
+    };
+    let controller_rolls = controller.rolls.clone();
+    let simulator_rolls = simulator.rolls.clone();
+
+    MultiAgentEngine::new(controller, simulator)
+        .with_seed(seed)
+        .run()
+        .unwrap();
+
+    let controller_rolls = controller_rolls.load().to_vec();
+    let simulator_rolls = simulator_rolls.load().to_vec();
+    (controller_rolls, simulator_rolls)
+}
+
+#[test]
+fn same_seed_reproduces_both_agents_streams() {
+    let first = seeded_run(1234);
+    let second = seeded_run(1234);
+
+    assert_eq!(first, second);
+    assert_ne!(first, seeded_run(4321));
+    assert!(first.1.iter().all(|roll| *roll < 1000));
+}
+
+#[test]
+fn agents_derive_distinct_streams_from_one_seed() {
+    let mut controller = SeededRng::for_agent(7, AgentId::CONTROLLER);
+    let mut simulator = SeededRng::for_agent(7, AgentId::SIMULATOR);
+
+    assert_ne!(controller.next_u64(), simulator.next_u64());
+}
+
+struct FrameRollingController {
+    rng: Option<SeededRng>,
+    rolls: Shared<Vec<u64>>,
+}
+
+impl FrameRollingController {
+    fn roll(mut self, ctx: &AgentContext) -> Result<()> {
+        let rng = self.rng.as_mut().unwrap();
+        let mut rolls = Vec::new();
+        while ctx.checkpoint().is_continue() {
+            ctx.advance_frame();
+            let roll = rng.below(10);
+            rolls.push(roll);
+            if roll == 0 {
+                break;
+            }
+        }
+        self.rolls.store(rolls);
+        Ok(())
+    }
+}
+
+impl Controller for FrameRollingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.roll(&AgentContext::new(AgentId::CONTROLLER))
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        self.roll(ctx)
+    }
+
+    fn seed(&mut self, rng: SeededRng) {
+        self.rng = Some(rng);
+    }
+}
+
+fn frame_rolling_run(seed: u64, frame_limit: Option<u64>) -> (Vec<u64>, Reproduction) {
+    let rolls = Shared::new(Vec::new());
+    let controller = FrameRollingController {
+        rng: None,
+        rolls: rolls.clone(),
+    };
+    let mut engine = MultiAgentEngine::new(controller, IdleSimulator).with_seed(seed);
+    if let Some(frames) = frame_limit {
+        engine = engine.with_frame_limit(frames);
+    }
+    let seed_log = engine.seed_log();
+
+    engine.run().unwrap();
+
+    let rolls = rolls.load().to_vec();
+    (rolls, seed_log.reproduction().unwrap())
+}
+
+#[test]
+fn seed_log_reports_the_seeds_and_frames_of_a_run() {
+    let (rolls, reproduction) = frame_rolling_run(99, None);
+
+    assert_eq!(reproduction.seed, 99);
+    assert_eq!(
+        reproduction.controller_seed,
+        SeededRng::agent_seed(99, AgentId::CONTROLLER)
+    );
+    assert_eq!(
+        reproduction.simulator_seed,
+        SeededRng::agent_seed(99, AgentId::SIMULATOR)
+    );
+    assert_eq!(reproduction.frames, rolls.len() as u64);
+    assert!(
+        reproduction
+            .to_string()
+            .starts_with(&format!("reproduce with seed 99, frames {}", rolls.len()))
+    );
+}
+
+#[test]
+fn seed_log_reproduction_replays_the_same_run() {
+    let (rolls, reproduction) = frame_rolling_run(99, None);
+    let (replayed, replay) = frame_rolling_run(reproduction.seed, Some(reproduction.frames));
+
+    assert_eq!(replayed, rolls);
+    assert_eq!(replay, reproduction);
+}
+
+#[test]
+fn seed_log_is_empty_without_a_seed() {
+    let engine = MultiAgentEngine::new(IdleController, IdleSimulator);
+    let seed_log = engine.seed_log();
+
+    engine.run().unwrap();
+
+    assert_eq!(seed_log.reproduction(), None);
+}
+
+struct DivergingSimulator;
+
+impl Simulator for DivergingSimulator {
+    type Error = PhysicsError;
+
+    fn run(self) -> std::result::Result<(), PhysicsError> {
+        Err(PhysicsError { step: 7 })
+    }
+}
+
+#[test]
+fn run_wraps_custom_agent_errors() {
+    let result = MultiAgentEngine::new(IdleController, DivergingSimulator).run();
+
+    let Err(Error::Agent { agent, source }) = result else {
+        panic!("expected a wrapped agent error, got {result:?}");
+    };
+    assert_eq!(agent, AgentId::SIMULATOR);
+    assert_eq!(
+        source.downcast_ref::<PhysicsError>(),
+        Some(&PhysicsError { step: 7 })
+    );
+    assert_eq!(
+        Error::Agent { agent, source }.to_string(),
+        "simulator failed: physics diverged at step 7"
+    );
+}
+
+enum Request {
+    Answer(message::OneShot<u32>),
+    Name(message::OneShot<&'static str>),
+}
+
+struct AskingController {
+    sender: message::Sender<Request>,
+    answers: Shared<Option<(u32, &'static str)>>,
+}
+
+impl Controller for AskingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let (answer, answer_reply) = message::OneShot::channel();
+        let (name, name_reply) = message::OneShot::channel();
+        self.sender.send(Request::Answer(answer))?;
+        self.sender.send(Request::Name(name))?;
+
+        let answer = answer_reply.wait_timeout(Duration::from_secs(5))?;
+        let name = name_reply.wait_timeout(Duration::from_secs(5))?;
+        self.answers.store(Some((answer, name)));
+        Ok(())
+    }
+}
+
+struct AnsweringSimulator {
+    receiver: message::Receiver<Request>,
+}
+
+impl Simulator for AnsweringSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        for request in self.receiver.iter() {
+            match request? {
+                Request::Answer(reply) => reply.respond(42)?,
+                Request::Name(reply) => reply.respond("deep thought")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn one_shot_replies_carry_per_request_types() {
+    let (sender, receiver) = message::Queue::channel();
+    let answers = Shared::new(None);
+    let controller = AskingController {
+        sender,
+        answers: answers.clone(),
+    };
+
+    MultiAgentEngine::new(controller, AnsweringSimulator { receiver })
+        .run()
+        .unwrap();
+
+    assert_eq!(**answers.load(), Some((42, "deep thought")));
+}
+
+#[test]
+fn dropped_one_shot_disconnects_reply() {
+    let (responder, reply) = message::OneShot::<u32>::channel();
+    drop(responder);
+
+    assert!(matches!(reply.wait(), Err(Error::Disconnected { .. })));
+}
+
+#[derive(Debug)]
+enum Protocol {
+    Ping(u32),
+    Pong(u32),
+}
+
+#[test]
+fn transcript_matches_golden_protocol() {
+    const GOLDEN: &str = "\
+controller: Ping(1)
+controller sent Protocol
+simulator: Pong(1)
+simulator sent Protocol
+-- frame 1
+controller: Ping(2)
+controller sent Protocol
+simulator: Pong(2)
+simulator sent Protocol
+-- frame 2
+";
+    let (sender, receiver) = message::Queue::channel();
+    let (reply_sender, replies) = message::Queue::channel();
+    let controller = with_context(move |ctx| {
+        for n in 1..=2 {
+            let ping = Protocol::Ping(n);
+            ctx.emit(EngineEvent::Message {
+                agent: ctx.agent(),
+                message: &ping,
+            });
+            sender.send(ping)?;
+            assert!(matches!(replies.recv_blocking()?, Protocol::Pong(m) if m == n));
+            ctx.advance_frame();
+        }
+        Ok(())
+    });
+    let simulator = with_context(move |ctx| {
+        for request in receiver.iter() {
+            if let Protocol::Ping(n) = request? {
+                let pong = Protocol::Pong(n);
+                ctx.emit(EngineEvent::Message {
+                    agent: ctx.agent(),
+                    message: &pong,
+                });
+                reply_sender.send(pong)?;
+            }
+        }
+        Ok(())
+    });
+
+    let (result, transcript) = MultiAgentEngine::new(controller, simulator).run_with_transcript();
+
+    result.unwrap();
+    assert_eq!(transcript.to_string(), GOLDEN);
+}
+
+#[test]
+fn transcript_records_every_frame_of_a_batch() {
+    let controller = with_context(|ctx| {
+        let mut batch = ctx.frame_batch();
+        batch.advance();
+        batch.advance();
+        batch.flush();
+        Ok(())
+    });
+    let (result, transcript) =
+        MultiAgentEngine::new(controller, IdleSimulator).run_with_transcript();
+
+    result.unwrap();
+    assert_eq!(transcript.to_string(), "-- frame 1\n-- frame 2\n");
+}
+
+#[test]
+fn transcript_reports_a_deferred_send_once_when_it_is_delivered() {
+    let controller = with_context(|ctx| {
+        let (sender, receiver) = message::Queue::bounded_channel(1);
+        let sender = sender.with_frame_boundary(ctx);
+        sender.send(1_u32)?;
+        sender.send_at_next_frame(2)?;
+        ctx.advance_frame();
+        assert_eq!(receiver.receive(), [1]);
+        ctx.advance_frame();
+        assert_eq!(receiver.receive(), [2]);
+        Ok(())
+    });
+    let (result, transcript) =
+        MultiAgentEngine::new(controller, IdleSimulator).run_with_transcript();
+
+    result.unwrap();
+    assert_eq!(
+        transcript.to_string(),
+        "controller sent u32\n-- frame 1\ncontroller sent u32\n-- frame 2\n"
+    );
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+struct BusyController;
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+impl Controller for BusyController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let start = std::time::Instant::now();
+        while start.elapsed() < Duration::from_millis(20) {
+            std::hint::spin_loop();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+#[test]
+fn busy_agent_reports_cpu_time() {
+    let mut handle = MultiAgentEngine::new(BusyController, IdleSimulator).spawn();
+    let result = loop {
+        if let Some(result) = handle.try_join() {
+            break result;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    result.unwrap();
+    assert!(handle.cpu_time(AgentId::CONTROLLER).unwrap() >= Duration::from_millis(10));
+    assert!(handle.cpu_time(AgentId::SIMULATOR).is_some());
+}
+
+#[cfg(feature = "scheduler-hook")]
+struct RacyIncrement {
+    counter: Shared<u32>,
+}
+
+#[cfg(feature = "scheduler-hook")]
+impl RacyIncrement {
+    fn increment(self) -> Result<()> {
+        let value = **self.counter.load();
+        self.counter.store(value + 1);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "scheduler-hook")]
+impl Controller for RacyIncrement {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.increment()
+    }
+}
+
+#[cfg(feature = "scheduler-hook")]
+impl Simulator for RacyIncrement {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.increment()
+    }
+}
+
+#[cfg(feature = "scheduler-hook")]
+fn run_racy(seed: u64) -> (u32, Vec<(AgentId, multi_agent_engine::SchedulePoint)>) {
+    let scheduler = Arc::new(multi_agent_engine::PctScheduler::new(seed).with_horizon(6));
+    let counter = Shared::new(0);
+    let agent = || RacyIncrement {
+        counter: counter.clone(),
+    };
+
+    MultiAgentEngine::new(agent(), agent())
+        .with_scheduler_hook(scheduler.clone())
+        .run()
+        .unwrap();
+
+    (**counter.load(), scheduler.trace())
+}
+
+#[cfg(feature = "scheduler-hook")]
+#[test]
+fn scheduler_hook_replays_interleavings_and_surfaces_lost_updates() {
+    for seed in 0..8 {
+        assert_eq!(run_racy(seed), run_racy(seed));
+    }
+
+    let totals: Vec<u32> = (0..32).map(|seed| run_racy(seed).0).collect();
+    assert!(totals.contains(&1), "no seed lost an update: {totals:?}");
+    assert!(totals.contains(&2));
+}
+
+struct Config {
+    tick_rate: u32,
+    name: &'static str,
+}
+
+struct ConfigReader {
+    config: ReadOnly<Config>,
+    start: Arc<Barrier>,
+    seen: Arc<Mutex<Vec<(u32, &'static str)>>>,
+}
+
+impl ConfigReader {
+    fn read(self) -> Result<()> {
+        self.start.wait();
+        for _ in 0..1_000 {
+            let seen = (self.config.tick_rate, self.config.name);
+            assert_eq!(seen, (60, "arena"));
+        }
+        self.seen
+            .lock()
+            .unwrap()
+            .push((self.config.tick_rate, self.config.name));
+        Ok(())
+    }
+}
+
+impl Controller for ConfigReader {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.read()
+    }
+}
+
+impl Simulator for ConfigReader {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.read()
+    }
+}
+
+#[test]
+fn read_only_config_is_read_by_both_agents_without_locking() {
+    let config = ReadOnly::new(Config {
+        tick_rate: 60,
+        name: "arena",
+    });
+    let start = Arc::new(Barrier::new(2));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let reader = || ConfigReader {
+        config: config.clone(),
+        start: Arc::clone(&start),
+        seen: Arc::clone(&seen),
+    };
+
+    MultiAgentEngine::new(reader(), reader()).run().unwrap();
+
+    assert_eq!(*seen.lock().unwrap(), [(60, "arena"), (60, "arena")]);
+}
+
+#[test]
+fn controller_only_engine_runs_to_completion() {
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let controller = TickingController {
+        token: CancellationToken::new(),
+        ticks: Arc::clone(&ticks),
+        limit: 5,
+    };
+
+    let handle = MultiAgentEngine::controller_only(controller).spawn();
+
+    handle.join_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(ticks.load(Ordering::Relaxed), 5);
+}
+
+#[test]
+fn simulator_only_engine_runs_to_completion() {
+    let (sender, receiver) = message::Queue::channel();
+    (1..=4).for_each(|value| sender.send(value).unwrap());
+    drop(sender);
+    let total = Shared::new(0);
+    let simulator = AccumulatingSimulator {
+        receiver,
+        total: total.clone(),
+        fail_on: None,
+    };
+
+    let handle = MultiAgentEngine::simulator_only(simulator).spawn();
+
+    handle.join_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(**total.load(), 10);
+}
+
+struct SleepingAgent {
+    sleep: Duration,
+}
+
+impl Controller for SleepingAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        thread::sleep(self.sleep);
+        Ok(())
+    }
+}
+
+impl Simulator for SleepingAgent {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        thread::sleep(self.sleep);
+        Err(Error::Multiple(Vec::new()))
+    }
+}
+
+#[test]
+fn run_for_keeps_results_of_agents_that_finished_in_time() {
+    let controller = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+    let simulator = SleepingAgent {
+        sleep: Duration::from_millis(500),
+    };
+
+    let outcomes = MultiAgentEngine::new(controller, simulator).run_for(Duration::from_millis(50));
+
+    assert!(!outcomes.is_complete());
+    assert!(matches!(outcomes.controller, Some(Ok(()))));
+    assert!(outcomes.simulator.is_none());
+}
+
+#[test]
+fn run_for_reports_both_results_when_agents_finish() {
+    let controller = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+    let simulator = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+
+    let outcomes = MultiAgentEngine::new(controller, simulator).run_for(Duration::from_secs(5));
+
+    assert!(matches!(outcomes.controller, Some(Ok(()))));
+    assert!(matches!(outcomes.simulator, Some(Err(Error::Multiple(_)))));
+}
+
+struct OutputController {
+    output: Shared<u32>,
+}
+
+impl Controller for OutputController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.output.store(42);
+        Ok(())
+    }
+}
+
+#[test]
+fn run_detailed_keeps_the_controller_output_when_the_simulator_fails() {
+    let output = Shared::new(0);
+    let controller = OutputController {
+        output: output.clone(),
+    };
+    let simulator = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+
+    let (controller, simulator) = MultiAgentEngine::new(controller, simulator).run_detailed();
+
+    assert!(controller.is_ok());
+    assert_eq!(**output.load(), 42);
+    assert!(matches!(simulator, Err(Error::Multiple(_))));
+}
+
+struct FrameSummingController {
+    total: Shared<u64>,
+}
+
+impl FrameSummingController {
+    fn sum(self, ctx: &AgentContext) -> Result<()> {
+        while ctx.checkpoint().is_continue() {
+            let frame = ctx.advance_frame();
+            self.total.update(|total| *total += frame);
+        }
+        Ok(())
+    }
+}
+
+impl Controller for FrameSummingController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.sum(&AgentContext::new(AgentId::CONTROLLER))
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        self.sum(ctx)
+    }
+}
+
+#[test]
+fn frame_limit_stops_agents_after_the_requested_frames() {
+    let total = Shared::new(0);
+    let controller = FrameSummingController {
+        total: total.clone(),
+    };
+
+    MultiAgentEngine::new(controller, IdleSimulator)
+        .with_frame_limit(10)
+        .run()
+        .unwrap();
+
+    assert_eq!(**total.load(), 55);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn run_frames_then_snapshot_writes_the_final_state() {
+    let path = std::env::temp_dir().join(format!("frame-snapshot-{}.json", std::process::id()));
+    let total = Shared::new(0);
+    let controller = FrameSummingController {
+        total: total.clone(),
+    };
+
+    MultiAgentEngine::new(controller, IdleSimulator)
+        .run_frames_then_snapshot(10, &total, &path)
+        .unwrap();
+
+    let snapshot: u64 = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(snapshot, 55);
+}
+
+struct MacroController {
+    config: Shared<u32>,
+    state: Shared<Vec<String>>,
+    sender: message::Sender<u32>,
+    receiver: message::Receiver<String>,
+}
+
+impl Controller for MacroController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.sender.send(**self.config.load())?;
+        let reply = self.receiver.recv_blocking()?;
+        self.state.update(|log| log.push(reply));
+
+        Ok(())
+    }
+}
+
+struct MacroSimulator {
+    config: Shared<u32>,
+    state: Shared<Vec<String>>,
+    sender: message::Sender<String>,
+    receiver: message::Receiver<u32>,
+}
+
+impl Simulator for MacroSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        let value = self.receiver.recv_blocking()?;
+        self.state
+            .update(|log| log.push(format!("simulator got {value}")));
+
+        self.sender
+            .send(format!("config was {}", **self.config.load()))
+    }
+}
+
+#[test]
+fn engine_macro_wires_both_agents() {
+    let state = Shared::new(Vec::new());
+
+    let engine = multi_agent_engine::engine! {
+        controller: MacroController,
+        simulator: MacroSimulator,
+        config: Shared::new(7),
+        state: state.clone(),
+    };
+
+    engine.run().unwrap();
+
+    assert_eq!(**state.load(), ["simulator got 7", "config was 7"]);
+}
+
+#[test]
+fn oversized_queue_messages_are_reported_on_spawn() {
+    let (_commands, commands) = message::Queue::channel::<u8>();
+    let (_frames, frames) = message::Queue::channel::<[u64; 64]>();
+
+    let handle = MultiAgentEngine::new(IdleController, IdleSimulator)
+        .with_queue("commands", &commands)
+        .with_queue("frames", &frames)
+        .with_max_message_size(64)
+        .spawn();
+
+    assert_eq!(
+        handle.warnings(),
+        [Warning::MessageSize {
+            type_name: "[u64; 64]",
+            size: 512,
+            max: 64,
+        }]
+    );
+    handle.join().unwrap();
+}
+
+#[derive(Debug, Clone, Default)]
+struct CapturingLog {
+    lines: Arc<Mutex<Vec<(LogLevel, String)>>>,
+}
+
+impl LogSink for CapturingLog {
+    fn log(&self, level: LogLevel, msg: &str) {
+        self.lines.lock().unwrap().push((level, msg.to_string()));
+    }
+}
+
+#[test]
+fn log_sink_receives_engine_lifecycle_messages() {
+    let log = CapturingLog::default();
+
+    MultiAgentEngine::new(IdleController, IdleSimulator)
+        .with_log_sink(log.clone())
+        .run()
+        .unwrap();
+
+    assert_eq!(
+        *log.lines.lock().unwrap(),
+        vec![
+            (LogLevel::Info, "engine started".to_string()),
+            (LogLevel::Info, "engine stopped".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn log_sink_receives_agent_failures() {
+    let log = CapturingLog::default();
+    let controller = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+    let simulator = SleepingAgent {
+        sleep: Duration::ZERO,
+    };
+
+    let result = MultiAgentEngine::new(controller, simulator)
+        .with_log_sink(log.clone())
+        .run();
+
+    assert!(result.is_err());
+    let lines = log.lines.lock().unwrap();
+    assert!(
+        lines
+            .iter()
+            .any(|(level, msg)| *level == LogLevel::Error && msg.starts_with("simulator failed"))
+    );
+    assert_eq!(
+        lines.last(),
+        Some(&(LogLevel::Info, "engine stopped".to_string()))
+    );
+}
+
+struct ConvergingSimulator {
+    value: Shared<f64>,
+    steps: Shared<u64>,
+}
+
+impl ConvergingSimulator {
+    fn converge(self, ctx: &AgentContext) -> Result<()> {
+        while ctx.checkpoint().is_continue() {
+            self.value.update(|value| *value += (100.0 - *value) / 2.0);
+            self.steps.update(|steps| *steps += 1);
+            ctx.advance_frame();
+        }
+        Ok(())
+    }
+}
+
+impl Simulator for ConvergingSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        self.converge(&AgentContext::new(AgentId::SIMULATOR))
+    }
+
+    fn run_with_context(self, ctx: &AgentContext) -> Result<()> {
+        self.converge(ctx)
+    }
+}
+
+#[test]
+fn run_until_converged_stops_once_the_state_converged() {
+    let value = Shared::new(0.0);
+    let steps = Shared::new(0);
+    let simulator = ConvergingSimulator {
+        value: value.clone(),
+        steps: steps.clone(),
+    };
+
+    let converged = MultiAgentEngine::new(IdleController, simulator)
+        .run_until_converged(&value, |value| (100.0 - value).abs() < 0.01, 1_000)
+        .unwrap();
+
+    assert!(converged);
+    assert!((100.0 - **value.load()).abs() < 0.01);
+    assert!(**steps.load() < 1_000);
+}
+
+#[test]
+fn run_until_converged_gives_up_at_the_frame_cap() {
+    let value = Shared::new(0.0);
+    let steps = Shared::new(0);
+    let simulator = ConvergingSimulator {
+        value: value.clone(),
+        steps: steps.clone(),
+    };
+
+    let converged = MultiAgentEngine::new(IdleController, simulator)
+        .run_until_converged(&value, |value| *value > 100.0, 20)
+        .unwrap();
+
+    assert!(!converged);
+    assert_eq!(**steps.load(), 20);
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+struct LateController {
+    sender: message::Sender<u32>,
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+impl Controller for LateController {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        thread::sleep(Duration::from_millis(200));
+        self.sender.send(1)
+    }
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+struct BackoffSimulator {
+    receiver: message::Receiver<u32>,
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+impl Simulator for BackoffSimulator {
+    type Error = Error;
+
+    fn run(self) -> Result<()> {
+        assert_eq!(self.receiver.receive_backoff(), vec![1]);
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+#[test]
+fn receive_backoff_parks_when_idle() {
+    let (sender, receiver) = message::Queue::channel();
+    let mut handle =
+        MultiAgentEngine::new(LateController { sender }, BackoffSimulator { receiver }).spawn();
+    let result = loop {
+        if let Some(result) = handle.try_join() {
+            break result;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    result.unwrap();
+    assert!(handle.cpu_time(AgentId::SIMULATOR).unwrap() < Duration::from_millis(50));
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{CancellationToken, Pipeline, Result, Shared, message};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn pipeline_throttles_the_source_to_its_slowest_stage() {
+    const MESSAGES: u32 = 40;
+    const SINK_DELAY: Duration = Duration::from_millis(5);
+
+    let source_elapsed = Shared::new(Duration::ZERO);
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let elapsed = source_elapsed.clone();
+    let sink_received = Arc::clone(&received);
+
+    Pipeline::new(2, move |sender: &message::Sender<u32>| {
+        let started = Instant::now();
+        for msg in 0..MESSAGES {
+            sender.send(msg)?;
+        }
+        elapsed.store(started.elapsed());
+        Ok(())
+    })
+    .with_stage(|msg| msg * 2)
+    .run(move |msg| {
+        thread::sleep(SINK_DELAY);
+        sink_received.lock().unwrap().push(msg);
+    })
+    .unwrap();
+
+    let received = received.lock().unwrap();
+    assert_eq!(
+        *received,
+        (0..MESSAGES).map(|msg| msg * 2).collect::<Vec<_>>()
+    );
+    // At most two queues of two plus one message in each stage can be
+    // buffered, the rest of the sends had to wait for the sink.
+    assert!(**source_elapsed.load() >= SINK_DELAY * (MESSAGES - 8));
+}
+
+#[test]
+fn pipeline_stages_can_change_the_message_type() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink_received = Arc::clone(&received);
+
+    Pipeline::new(4, |sender: &message::Sender<u32>| {
+        (1..=3).try_for_each(|msg| sender.send(msg))
+    })
+    .with_stage(|msg| f64::from(msg) / 2.0)
+    .with_stage(|msg| format!("{msg:.1}"))
+    .run(move |msg: String| sink_received.lock().unwrap().push(msg))
+    .unwrap();
+
+    assert_eq!(*received.lock().unwrap(), ["0.5", "1.0", "1.5"]);
+}
+
+#[test]
+fn pipeline_stops_once_cancelled() {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+
+    let pipeline = Pipeline::new(2, |sender: &message::Sender<u32>| -> Result<()> {
+        for msg in 0.. {
+            sender.send(msg)?;
+        }
+        Ok(())
+    })
+    .with_stage(|msg| msg + 1)
+    .with_cancellation_token(token);
+
+    let cancelled = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        cancel.cancel();
+    });
+
+    pipeline.run(drop).unwrap();
+    cancelled.join().unwrap();
+}
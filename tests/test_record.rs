@@ -0,0 +1,351 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use multi_agent_engine::{
+    Clock, MockClock, Result, Shared, Step, SteppedEngine, message,
+    record::{Recorded, Recorder, RecordingSender, ReplaySender, Session},
+};
+use std::{ops::ControlFlow, time::Duration};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Input {
+    Push(i64),
+    Scale(i64),
+    Done,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct World {
+    total: i64,
+    history: Vec<i64>,
+}
+
+struct ScriptedController {
+    sender: RecordingSender<Input>,
+    script: std::vec::IntoIter<Input>,
+}
+
+impl Step for ScriptedController {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        match self.script.next() {
+            Some(input) => self.sender.send(input).map(ControlFlow::Continue),
+            None => Ok(ControlFlow::Break(())),
+        }
+    }
+}
+
+struct WorldSimulator {
+    receiver: message::Receiver<Input>,
+    world: World,
+    state: Shared<World>,
+}
+
+impl Step for WorldSimulator {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        for input in self.receiver.receive() {
+            match input {
+                Input::Push(value) => self.world.total += value,
+                Input::Scale(factor) => self.world.total *= factor,
+                Input::Done => {
+                    self.state.store(self.world.clone());
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+            self.world.history.push(self.world.total);
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+fn world_simulator(receiver: message::Receiver<Input>) -> WorldSimulator {
+    WorldSimulator {
+        receiver,
+        world: World::default(),
+        state: Shared::new(World::default()),
+    }
+}
+
+#[test]
+fn replayed_session_reproduces_final_state() {
+    let recorder = Recorder::new();
+    let (sender, receiver) = message::Queue::channel();
+
+    let controller = ScriptedController {
+        sender: recorder.sender(sender),
+        script: vec![
+            Input::Push(3),
+            Input::Scale(4),
+            Input::Push(-5),
+            Input::Done,
+        ]
+        .into_iter(),
+    };
+    let simulator = world_simulator(receiver);
+    let state = simulator.state.clone();
+
+    SteppedEngine::new(controller, simulator).run().unwrap();
+
+    let session = Session::new(recorder.take(), state.load().as_ref().clone());
+    assert_eq!(session.messages().len(), 4);
+    assert_eq!(session.final_state().total, 7);
+
+    let (sender, receiver) = message::Queue::channel();
+    let mut fresh = world_simulator(receiver);
+    assert_eq!(session.replay(sender).unwrap(), 4);
+    while fresh.step().unwrap().is_continue() {}
+
+    assert!(session.matches(&fresh.state.load()));
+    assert_eq!(fresh.world.history, vec![3, 12, 7]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_lines_recorder_writes_one_object_per_message() {
+    use multi_agent_engine::{AgentId, record::JsonLinesRecorder};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    enum Command {
+        Move { x: i32, y: i32 },
+        Stop,
+    }
+
+    let clock = MockClock::new();
+    let recorder = JsonLinesRecorder::new(Vec::new()).with_clock(clock.clone());
+
+    recorder
+        .record(AgentId::CONTROLLER, &Command::Move { x: 1, y: 2 })
+        .unwrap();
+    clock.advance(Duration::from_millis(5));
+    recorder.record(AgentId::SIMULATOR, &Command::Stop).unwrap();
+    recorder.record(AgentId::new(7), &"custom").unwrap();
+
+    let output = String::from_utf8(recorder.into_inner()).unwrap();
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        serde_json::json!({
+            "timestamp_ns": 0,
+            "agent": "controller",
+            "message": { "Move": { "x": 1, "y": 2 } },
+        })
+    );
+    assert_eq!(lines[1]["timestamp_ns"], 5_000_000);
+    assert_eq!(lines[1]["agent"], "simulator");
+    assert_eq!(lines[1]["message"], "Stop");
+    assert_eq!(lines[2]["agent"], "agent-7");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn event_log_replay_stops_at_truncated_tail() {
+    use multi_agent_engine::record::EventLog;
+    use serde::{Deserialize, Serialize};
+    use std::fs::{self, OpenOptions};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Event {
+        Spawned { id: u32 },
+        Moved { id: u32, x: f32 },
+    }
+
+    let path = std::env::temp_dir().join(format!("event-log-{}.bin", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let log = EventLog::open(&path).unwrap();
+    log.append(&Event::Spawned { id: 1 }).unwrap();
+    log.append(&Event::Moved { id: 1, x: 2.5 }).unwrap();
+    log.append(&Event::Spawned { id: 2 }).unwrap();
+    drop(log);
+
+    let len = fs::metadata(&path).unwrap().len();
+    OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(len - 3)
+        .unwrap();
+
+    let log = EventLog::<Event>::open(&path).unwrap();
+    let replayed: Vec<Event> = log.replay().unwrap().map(Result::unwrap).collect();
+    assert_eq!(
+        replayed,
+        [Event::Spawned { id: 1 }, Event::Moved { id: 1, x: 2.5 }]
+    );
+
+    log.append(&Event::Spawned { id: 3 }).unwrap();
+    let replayed: Vec<Event> = log.replay().unwrap().map(Result::unwrap).collect();
+    assert_eq!(replayed.len(), 3);
+    assert_eq!(replayed[2], Event::Spawned { id: 3 });
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn event_log_checks_schema_version_and_migrates_old_records() {
+    use multi_agent_engine::{Error, record::EventLog};
+    use serde::{Deserialize, Serialize};
+    use std::fs;
+
+    #[derive(Serialize, Deserialize)]
+    struct PositionV1 {
+        x: f32,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct PositionV2 {
+        x: f32,
+        y: f32,
+    }
+
+    let path = std::env::temp_dir().join(format!("event-log-schema-{}.bin", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let log = EventLog::open(&path).unwrap();
+    assert_eq!(log.schema_version(), 1);
+    log.append(&PositionV1 { x: 1.5 }).unwrap();
+    drop(log);
+
+    let log = EventLog::<PositionV2>::open(&path)
+        .unwrap()
+        .with_schema_version(2);
+    let result = log.replay().unwrap().next().unwrap();
+    assert!(matches!(
+        result,
+        Err(Error::SchemaMismatch {
+            found: 1,
+            expected: 2
+        })
+    ));
+
+    let log = log.with_migration(|found, json| match found {
+        1 => {
+            let old: PositionV1 = serde_json::from_value(json).map_err(std::io::Error::from)?;
+            Ok(PositionV2 { x: old.x, y: 0.0 })
+        }
+        _ => Err(Error::SchemaMismatch { found, expected: 2 }),
+    });
+    let replayed: Vec<_> = log.replay().unwrap().map(Result::unwrap).collect();
+    assert_eq!(replayed, [PositionV2 { x: 1.5, y: 0.0 }]);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn replay_at_double_speed_halves_delivery_times() {
+    let clock = MockClock::new();
+    let (sender, receiver) = message::Queue::channel();
+    let recorded = [0, 100, 300].map(|ms| Recorded {
+        at: Duration::from_millis(ms),
+        message: ms,
+    });
+    let mut replay = ReplaySender::new(recorded, sender).with_clock(clock.clone());
+    replay.set_speed(2.0);
+
+    let start = clock.now();
+    let mut delivered = Vec::new();
+    while replay.replay_next().unwrap() {
+        delivered.push(clock.now() - start);
+    }
+
+    assert_eq!(delivered, [0, 50, 150].map(Duration::from_millis));
+    assert_eq!(receiver.receive(), vec![0, 100, 300]);
+}
+
+#[test]
+fn step_one_replays_frame_by_frame_without_waiting() {
+    let clock = MockClock::new();
+    let (sender, receiver) = message::Queue::channel();
+    let recorded = [10, 20].map(|ms| Recorded {
+        at: Duration::from_secs(ms),
+        message: ms,
+    });
+    let mut replay = ReplaySender::new(recorded, sender).with_clock(clock.clone());
+    let start = clock.now();
+
+    assert!(replay.step_one().unwrap());
+    assert_eq!(receiver.receive(), vec![10]);
+    assert!(replay.step_one().unwrap());
+    assert!(!replay.step_one().unwrap());
+    assert_eq!(receiver.receive(), vec![20]);
+    assert_eq!(clock.now(), start);
+}
+
+#[test]
+fn replay_filter_only_re_emits_matching_messages() {
+    let recorder = Recorder::new();
+    let (sender, _receiver) = message::Queue::channel();
+    let recording = recorder.sender(sender);
+    for input in [Input::Push(1), Input::Scale(2), Input::Push(3), Input::Done] {
+        recording.send(input).unwrap();
+    }
+
+    let (sender, receiver) = message::Queue::channel();
+    let mut replay = ReplaySender::new(recorder.take(), sender)
+        .with_filter(|input| matches!(input, Input::Push(_)));
+
+    assert_eq!(replay.remaining(), 2);
+    assert_eq!(replay.replay_all().unwrap(), 2);
+    assert_eq!(receiver.receive(), vec![Input::Push(1), Input::Push(3)]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn shutdown_flushes_the_recording_of_a_detached_agent() {
+    use multi_agent_engine::{Error, MultiAgentEngine, record::JsonLinesRecorder};
+    use std::{fs::File, io::BufWriter, sync::Arc, thread};
+
+    let path = std::env::temp_dir().join(format!("shutdown-flush-{}.jsonl", std::process::id()));
+    let recorder = Arc::new(JsonLinesRecorder::new(BufWriter::new(
+        File::create(&path).unwrap(),
+    )));
+    let controller = common::with_context({
+        let recorder = Arc::clone(&recorder);
+        move |ctx| {
+            recorder.record(ctx.agent(), &"first")?;
+            while !ctx.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            recorder.record(ctx.agent(), &"last")?;
+            thread::sleep(Duration::from_millis(500));
+            Ok(())
+        }
+    });
+
+    let handle = MultiAgentEngine::controller_only(controller)
+        .with_shutdown_flush(move || recorder.flush())
+        .spawn();
+    thread::sleep(Duration::from_millis(10));
+    let result = handle.shutdown(Duration::from_millis(50));
+
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let messages: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["message"].clone())
+        .collect();
+    assert_eq!(messages, ["first", "last"]);
+}
@@ -0,0 +1,430 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    AgentId, CommitOutcome, Diff, Error, Patch, Shared, SharedAtomic, SharedRcu, TwoPhaseCommit,
+    message::{DeltaDecoder, DeltaEncoder, StateSync, SyncFrame},
+};
+use std::{
+    sync::{Arc, Barrier},
+    thread,
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Agent {
+    position: (i32, i32),
+    energy: u32,
+    name: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct AgentDelta {
+    position: Option<(i32, i32)>,
+    energy: Option<u32>,
+    name: Option<String>,
+}
+
+impl Diff for Agent {
+    type Delta = AgentDelta;
+
+    fn diff(&self, other: &Self) -> AgentDelta {
+        AgentDelta {
+            position: (self.position != other.position).then_some(other.position),
+            energy: (self.energy != other.energy).then_some(other.energy),
+            name: (self.name != other.name).then(|| other.name.clone()),
+        }
+    }
+}
+
+impl Patch for Agent {
+    fn patch(&mut self, delta: AgentDelta) {
+        if let Some(position) = delta.position {
+            self.position = position;
+        }
+        if let Some(energy) = delta.energy {
+            self.energy = energy;
+        }
+        if let Some(name) = delta.name {
+            self.name = name;
+        }
+    }
+}
+
+#[test]
+fn diff_since_only_contains_changed_fields() {
+    let prev = Agent {
+        position: (0, 0),
+        energy: 100,
+        name: String::from("scout"),
+    };
+    let shared = Shared::new(prev.clone());
+
+    shared.store(Agent {
+        position: (1, 0),
+        energy: 100,
+        name: String::from("scout"),
+    });
+
+    assert_eq!(
+        shared.diff_since(&prev),
+        AgentDelta {
+            position: Some((1, 0)),
+            ..AgentDelta::default()
+        }
+    );
+}
+
+#[test]
+fn diff_since_unchanged_state_is_empty() {
+    let prev = Agent {
+        position: (3, 4),
+        energy: 7,
+        name: String::from("worker"),
+    };
+    let shared = Shared::new(prev.clone());
+
+    assert_eq!(shared.diff_since(&prev), AgentDelta::default());
+}
+
+#[test]
+fn version_increments_on_store_only() {
+    let shared = Shared::new(0u32);
+    assert_eq!(shared.version(), 0);
+
+    shared.store(1);
+    shared.store(2);
+    assert_eq!(shared.version(), 2);
+
+    assert_eq!(**shared.load(), 2);
+    assert_eq!(**shared.clone().load(), 2);
+    assert_eq!(shared.version(), 2);
+}
+
+#[test]
+fn subscribe_notifies_once_per_undrained_burst_of_stores() {
+    let shared = Shared::new(0u32);
+    let changes = shared.subscribe();
+    assert!(changes.receive().is_empty());
+
+    shared.store(1);
+    shared.store(2);
+    shared.store(3);
+    assert_eq!(changes.receive(), vec![()]);
+    assert!(changes.receive().is_empty());
+
+    shared.store(4);
+    assert_eq!(changes.receive(), vec![()]);
+    assert_eq!(**shared.load(), 4);
+}
+
+#[test]
+fn dropped_subscribers_are_forgotten() {
+    let shared = Shared::new(0u32);
+    let kept = shared.subscribe();
+    drop(shared.subscribe());
+
+    shared.store(1);
+
+    assert_eq!(kept.receive(), vec![()]);
+}
+
+#[test]
+fn contending_write_records_wait_time() {
+    let shared = Shared::new(0u32);
+    let holding = Arc::new(Barrier::new(2));
+
+    let writer = {
+        let shared = shared.clone();
+        let holding = Arc::clone(&holding);
+        thread::spawn(move || {
+            shared.update(|value| {
+                holding.wait();
+                thread::sleep(Duration::from_millis(20));
+                *value += 1;
+            });
+        })
+    };
+
+    holding.wait();
+    shared.update(|value| *value += 1);
+    writer.join().unwrap();
+
+    let stats = shared.contention_stats();
+    assert_eq!(**shared.load(), 2);
+    assert_eq!(stats.waits, 1);
+    assert!(stats.max_wait >= Duration::from_millis(10), "{stats:?}");
+    assert_eq!(stats.total_wait, stats.max_wait);
+}
+
+#[test]
+fn update_timeout_gives_up_on_a_writer_holding_on() {
+    let shared = Shared::new(0u32);
+    let holding = Arc::new(Barrier::new(2));
+
+    let writer = {
+        let shared = shared.clone();
+        let holding = Arc::clone(&holding);
+        thread::spawn(move || {
+            shared.update(|value| {
+                holding.wait();
+                thread::sleep(Duration::from_millis(50));
+                *value += 1;
+            });
+        })
+    };
+
+    holding.wait();
+    assert!(matches!(
+        shared.update_timeout(Duration::from_millis(5), |value| *value += 10),
+        Err(Error::Timeout { .. })
+    ));
+    writer.join().unwrap();
+
+    shared
+        .update_timeout(Duration::from_secs(1), |value| *value += 1)
+        .unwrap();
+    assert_eq!(**shared.load(), 2);
+}
+
+#[test]
+fn reads_never_block_writes() {
+    let shared = Shared::new(1u32);
+    let guard = shared.load();
+
+    shared.update(|value| *value = 2);
+
+    assert_eq!(**guard, 1);
+    assert_eq!(**shared.load(), 2);
+    assert_eq!(shared.contention_stats().waits, 0);
+}
+
+#[test]
+fn concurrent_updates_are_never_lost() {
+    let counter = Shared::new(0u32);
+    let changed = counter.subscribe();
+
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn(move || (0..1000).for_each(|_| counter.update(|value| *value += 1)))
+        })
+        .collect();
+    workers
+        .into_iter()
+        .for_each(|worker| worker.join().unwrap());
+
+    assert_eq!(**counter.load(), 2000);
+    assert_eq!(counter.version(), 2000);
+    assert_eq!(changed.receive(), vec![()]);
+}
+
+#[test]
+fn vetoed_proposal_leaves_state_unchanged() {
+    let world = Shared::new(vec![1, 2, 3]);
+    let commit = TwoPhaseCommit::new(world.clone(), 2);
+
+    assert!(commit.prepare(vec![1, 2]));
+    assert!(!commit.prepare(vec![9]));
+    assert_eq!(commit.outcome(), None);
+
+    commit.vote(AgentId::CONTROLLER, true);
+    commit.vote(AgentId::SIMULATOR, commit.proposal().unwrap().len() == 3);
+
+    assert_eq!(commit.outcome(), Some(CommitOutcome::Aborted));
+    assert_eq!(**world.load(), vec![1, 2, 3]);
+    assert_eq!(world.version(), 0);
+}
+
+#[test]
+fn unanimous_proposal_commits() {
+    let world = Shared::new(vec![1, 2, 3]);
+    let commit = TwoPhaseCommit::new(world.clone(), 2);
+    assert!(commit.prepare(vec![1, 2, 3, 4]));
+
+    let peers: Vec<_> = [AgentId::CONTROLLER, AgentId::SIMULATOR]
+        .into_iter()
+        .map(|voter| {
+            let commit = commit.clone();
+            thread::spawn(move || {
+                let valid = commit.proposal().is_some_and(|state| state.len() == 4);
+                commit.vote(voter, valid);
+            })
+        })
+        .collect();
+
+    assert_eq!(commit.wait(), Some(CommitOutcome::Committed));
+    peers.into_iter().for_each(|peer| peer.join().unwrap());
+    assert_eq!(**world.load(), vec![1, 2, 3, 4]);
+    assert_eq!(world.version(), 1);
+}
+
+#[test]
+fn repeated_votes_from_one_peer_count_once() {
+    let world = Shared::new(0);
+    let commit = TwoPhaseCommit::new(world.clone(), 2);
+    assert!(commit.prepare(1));
+
+    commit.vote(AgentId::CONTROLLER, true);
+    commit.vote(AgentId::CONTROLLER, true);
+
+    assert_eq!(commit.outcome(), None);
+    assert!(matches!(
+        commit.wait_timeout(Duration::from_millis(10)),
+        Err(Error::Timeout { .. })
+    ));
+    assert_eq!(**world.load(), 0);
+
+    commit.vote(AgentId::SIMULATOR, true);
+    assert_eq!(
+        commit.wait_timeout(Duration::from_millis(10)).unwrap(),
+        Some(CommitOutcome::Committed)
+    );
+}
+
+#[test]
+fn deltas_after_one_keyframe_reconstruct_the_source_state() {
+    let mut encoder = DeltaEncoder::new(100);
+    let sync = StateSync::new();
+    let mut decoder = DeltaDecoder::new().with_state_sync(&sync);
+    let mut agent = Agent {
+        position: (0, 0),
+        energy: 100,
+        name: String::from("scout"),
+    };
+
+    let mut keyframes = 0;
+    for tick in 0..10 {
+        agent.position.0 += 1;
+        if tick % 3 == 0 {
+            agent.energy -= 5;
+        }
+        let frame = encoder.encode(&agent);
+        keyframes += usize::from(frame.is_keyframe());
+
+        let ack = decoder.decode(frame).unwrap();
+        encoder.acknowledge(ack);
+        assert_eq!(decoder.state(), Some(&agent));
+    }
+
+    assert_eq!(keyframes, 1);
+    assert_eq!(sync.latest(), Some(agent));
+}
+
+#[test]
+fn decoder_resyncs_on_keyframe_after_missing_a_base() {
+    let mut encoder = DeltaEncoder::new(3);
+    let mut decoder = DeltaDecoder::new();
+    let mut agent = Agent {
+        position: (0, 0),
+        energy: 1,
+        name: String::from("worker"),
+    };
+
+    let lost = encoder.encode(&agent);
+    encoder.acknowledge(lost.version());
+    agent.energy = 2;
+    let delta = encoder.encode(&agent);
+    assert!(matches!(delta, SyncFrame::Delta { base: 1, .. }));
+    assert_eq!(decoder.decode(delta), None);
+
+    encoder.force_keyframe();
+    agent.energy = 3;
+    let keyframe = encoder.encode(&agent);
+    assert!(keyframe.is_keyframe());
+    assert_eq!(decoder.decode(keyframe), Some(3));
+    assert_eq!(decoder.state(), Some(&agent));
+}
+
+#[test]
+fn shared_atomic_fetch_update_loses_no_increment_under_contention() {
+    let counter = SharedAtomic::new(0_u64);
+    let start = Arc::new(Barrier::new(2));
+
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let counter = counter.clone();
+            let start = Arc::clone(&start);
+            thread::spawn(move || {
+                start.wait();
+                for _ in 0..10_000 {
+                    counter.fetch_update(|n| Some(n + 1)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    assert_eq!(counter.load(), 20_000);
+}
+
+#[test]
+fn shared_atomic_round_trips_packed_values() {
+    let position = SharedAtomic::new((-3_i32, 4.5_f32));
+    position.store((7, -0.25));
+    assert_eq!(position.load(), (7, -0.25));
+
+    let flag = SharedAtomic::new(false);
+    assert_eq!(flag.fetch_update(|set| (!set).then_some(true)), Ok(false));
+    assert_eq!(flag.fetch_update(|set| (!set).then_some(true)), Err(true));
+}
+
+#[test]
+fn shared_rcu_readers_only_see_whole_versions() {
+    let state = SharedRcu::new(vec![0u64; 64]);
+    let barrier = Arc::new(Barrier::new(5));
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let state = state.clone();
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                let mut last = 0;
+                for _ in 0..10_000 {
+                    let snapshot = state.load();
+                    let version = snapshot[0];
+                    assert!(snapshot.iter().all(|value| *value == version));
+                    assert!(version >= last);
+                    last = version;
+                }
+            })
+        })
+        .collect();
+
+    barrier.wait();
+    for version in 1..=200 {
+        state.store(vec![version; 64]);
+    }
+
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(*state.load(), vec![200; 64]);
+}
+
+#[test]
+fn shared_rcu_snapshot_outlives_a_store() {
+    let state = SharedRcu::new(String::from("old"));
+    let snapshot = state.load();
+
+    state.store(String::from("new"));
+
+    assert_eq!(*snapshot, "old");
+    assert_eq!(*state.load(), "new");
+}
@@ -0,0 +1,250 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{EngineEvent, Observer, Result, Step, SteppedEngine, message};
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, Mutex},
+};
+
+struct PingController {
+    sender: message::Sender<u32>,
+    receiver: message::Receiver<u32>,
+    sent: u32,
+    pongs: Vec<u32>,
+}
+
+impl Step for PingController {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        self.pongs.extend(self.receiver.receive());
+
+        if self.sent < 3 {
+            self.sender.send(self.sent)?;
+            self.sent += 1;
+        } else if self.pongs.len() == 3 {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+struct PongSimulator {
+    sender: message::Sender<u32>,
+    receiver: message::Receiver<u32>,
+    pings: Vec<u32>,
+}
+
+impl Step for PongSimulator {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        for ping in self.receiver.receive() {
+            self.pings.push(ping);
+            self.sender.send(ping * 10)?;
+        }
+
+        if self.pings.len() == 3 {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+fn ping_pong() -> SteppedEngine<PingController, PongSimulator> {
+    let (ping_sender, ping_receiver) = message::Queue::channel();
+    let (pong_sender, pong_receiver) = message::Queue::channel();
+
+    let controller = PingController {
+        sender: ping_sender,
+        receiver: pong_receiver,
+        sent: 0,
+        pongs: Vec::new(),
+    };
+    let simulator = PongSimulator {
+        sender: pong_sender,
+        receiver: ping_receiver,
+        pings: Vec::new(),
+    };
+
+    SteppedEngine::new(controller, simulator)
+}
+
+#[test]
+fn step_advances_both_agents_one_frame() {
+    let mut engine = ping_pong();
+
+    let outcome = engine.step().unwrap();
+    assert_eq!(outcome.frame, 1);
+    assert!(!outcome.is_finished());
+    assert_eq!(engine.simulator().pings, vec![0]);
+    assert!(engine.controller().pongs.is_empty());
+
+    engine.step().unwrap();
+    assert_eq!(engine.simulator().pings, vec![0, 1]);
+    assert_eq!(engine.controller().pongs, vec![0]);
+
+    let outcome = engine.step().unwrap();
+    assert!(outcome.simulator_finished);
+    assert!(!outcome.controller_finished);
+    assert_eq!(engine.controller().pongs, vec![0, 10]);
+
+    let outcome = engine.step().unwrap();
+    assert!(outcome.is_complete());
+    assert_eq!(engine.controller().pongs, vec![0, 10, 20]);
+}
+
+#[test]
+fn run_steps_until_both_agents_finish() {
+    assert_eq!(ping_pong().run().unwrap(), 4);
+}
+
+#[derive(Default)]
+struct CountingAgent {
+    work: u32,
+}
+
+impl Step for CountingAgent {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        self.work += 1;
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[test]
+fn steps_from_an_external_loop_run_one_frame_of_agent_work_each() {
+    let mut engine = SteppedEngine::new(CountingAgent::default(), CountingAgent::default());
+
+    for _ in 0..10 {
+        engine.step().unwrap();
+    }
+
+    assert_eq!(engine.frame(), 10);
+    assert_eq!(engine.controller().work, 10);
+    assert_eq!(engine.simulator().work, 10);
+}
+
+#[test]
+fn step_does_nothing_once_both_agents_finished() {
+    let mut engine = ping_pong();
+    let mut outcome = engine.step().unwrap();
+    while !outcome.is_complete() {
+        outcome = engine.step().unwrap();
+    }
+
+    assert_eq!(engine.step().unwrap(), outcome);
+    assert_eq!(engine.frame(), outcome.frame);
+}
+
+#[derive(Default)]
+struct FrameLog {
+    frames: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Observer for FrameLog {
+    fn observe(&mut self, event: EngineEvent<'_>) {
+        if let EngineEvent::Frame { frame } = event {
+            self.frames.lock().unwrap().push(frame);
+        }
+    }
+}
+
+#[test]
+fn observers_see_every_frame() {
+    let log = FrameLog::default();
+    let frames = log.frames.clone();
+    let mut engine = ping_pong().with_observer(log);
+
+    while !engine.step().unwrap().is_complete() {}
+
+    assert_eq!(
+        *frames.lock().unwrap(),
+        (1..=engine.frame()).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "rerun")]
+#[test]
+fn rerun_sink_logs_a_frame() {
+    use multi_agent_engine::{AgentId, RerunSink};
+
+    let mut sink = RerunSink::buffered("multi-agent-engine-tests").unwrap();
+    sink.observe(EngineEvent::Frame { frame: 1 });
+    sink.observe(EngineEvent::Message {
+        agent: AgentId::CONTROLLER,
+        message: &"ping",
+    });
+    sink.observe(EngineEvent::State {
+        agent: AgentId::SIMULATOR,
+        state: &[1.0, 2.0],
+    });
+
+    ping_pong().with_observer(sink).run().unwrap();
+}
+
+struct GreedyAgent {
+    backlog: u64,
+    handled: u64,
+}
+
+impl Step for GreedyAgent {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        self.step_bounded(usize::MAX)
+    }
+
+    fn step_bounded(&mut self, quantum: usize) -> Result<ControlFlow<()>> {
+        let work = self.backlog.min(quantum as u64);
+        self.backlog -= work;
+        self.handled += work;
+        self.backlog += 2 * work;
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[derive(Default)]
+struct OccasionalAgent {
+    steps: u64,
+    handled: u64,
+}
+
+impl Step for OccasionalAgent {
+    fn step(&mut self) -> Result<ControlFlow<()>> {
+        self.steps += 1;
+        if self.steps.is_multiple_of(10) {
+            self.handled += 1;
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[test]
+fn work_quantum_keeps_a_greedy_agent_from_starving_the_other() {
+    let greedy = GreedyAgent {
+        backlog: 1000,
+        handled: 0,
+    };
+    let mut engine = SteppedEngine::new(greedy, OccasionalAgent::default()).with_work_quantum(4);
+
+    for _ in 0..1000 {
+        assert!(!engine.step().unwrap().is_finished());
+    }
+
+    assert_eq!(engine.controller().handled, 4000);
+    assert_eq!(engine.simulator().steps, 1000);
+    assert_eq!(engine.simulator().handled, 100);
+    assert_eq!(engine.frame(), 1000);
+}
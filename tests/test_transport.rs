@@ -0,0 +1,733 @@
+/*
+ * Copyright 2025 Nicolas Spijkerman
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use multi_agent_engine::{
+    Error, MockClock, Result,
+    transport::{
+        CircuitBreaker, CircuitState, ClockSkew, ConnectionState, InMemoryTransport,
+        ReconnectingTransport, ReliableSender, RetrySender, SkewCorrectedTransport, Timestamped,
+        Transport, unix_nanos,
+    },
+};
+use std::{
+    io,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+struct FlakyTransport {
+    failures: AtomicU32,
+    kind: io::ErrorKind,
+    attempts: AtomicU32,
+    delivered: Mutex<Vec<u32>>,
+}
+
+impl FlakyTransport {
+    fn new(failures: u32, kind: io::ErrorKind) -> Self {
+        Self {
+            failures: AtomicU32::new(failures),
+            kind,
+            attempts: AtomicU32::new(0),
+            delivered: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Transport for FlakyTransport {
+    type Outgoing = u32;
+    type Incoming = u32;
+
+    fn send(&self, msg: u32) -> Result<()> {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+
+        if self.failures.load(Ordering::Relaxed) > 0 {
+            self.failures.fetch_sub(1, Ordering::Relaxed);
+            return Err(io::Error::from(self.kind).into());
+        }
+
+        self.delivered.lock().unwrap().push(msg);
+        Ok(())
+    }
+
+    fn receive(&self) -> Result<Vec<u32>> {
+        Ok(Vec::new())
+    }
+}
+
+#[test]
+fn retry_sender_delivers_after_transient_failures() {
+    let clock = MockClock::new();
+    let sender = RetrySender::new(
+        FlakyTransport::new(2, io::ErrorKind::WouldBlock),
+        5,
+        Duration::from_millis(10),
+    )
+    .with_clock(clock.clone());
+
+    sender.send(42).unwrap();
+
+    assert_eq!(sender.inner().attempts.load(Ordering::Relaxed), 3);
+    assert_eq!(*sender.inner().delivered.lock().unwrap(), vec![42]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(30));
+}
+
+#[test]
+fn retry_sender_gives_up_after_max_attempts() {
+    let sender = RetrySender::new(
+        FlakyTransport::new(10, io::ErrorKind::TimedOut),
+        3,
+        Duration::ZERO,
+    )
+    .with_clock(MockClock::new());
+
+    assert!(matches!(sender.send(1), Err(Error::Transport(_))));
+    assert_eq!(sender.inner().attempts.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn retry_sender_does_not_retry_fatal_errors() {
+    let sender = RetrySender::new(
+        FlakyTransport::new(1, io::ErrorKind::BrokenPipe),
+        5,
+        Duration::from_millis(10),
+    );
+
+    assert!(sender.send(1).is_err());
+    assert_eq!(sender.inner().attempts.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn circuit_breaker_opens_then_half_opens_then_closes() {
+    let clock = MockClock::new();
+    let breaker = CircuitBreaker::new(
+        FlakyTransport::new(3, io::ErrorKind::ConnectionRefused),
+        3,
+        Duration::from_secs(1),
+    )
+    .with_clock(clock.clone());
+
+    for _ in 0..3 {
+        assert!(matches!(breaker.send(1), Err(Error::Transport(_))));
+    }
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    assert!(matches!(breaker.send(2), Err(Error::CircuitOpen)));
+    assert_eq!(breaker.inner().attempts.load(Ordering::Relaxed), 3);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+    breaker.send(3).unwrap();
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert_eq!(*breaker.inner().delivered.lock().unwrap(), vec![3]);
+}
+
+#[test]
+fn circuit_breaker_reopens_when_probe_fails() {
+    let clock = MockClock::new();
+    let breaker = CircuitBreaker::new(
+        FlakyTransport::new(2, io::ErrorKind::ConnectionRefused),
+        1,
+        Duration::from_secs(1),
+    )
+    .with_clock(clock.clone());
+
+    assert!(breaker.send(1).is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    clock.advance(Duration::from_secs(1));
+    assert!(matches!(breaker.send(2), Err(Error::Transport(_))));
+    assert_eq!(breaker.state(), CircuitState::Open);
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_transport_exchanges_json_messages_over_loopback() {
+    use multi_agent_engine::{
+        Endpoint,
+        transport::{JsonCodec, WebSocketTransport},
+    };
+    use serde::{Deserialize, Serialize};
+    use std::{net::TcpListener, thread, time::Instant};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Command {
+        Spawn { id: u32 },
+        Reset,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Ack {
+        id: u32,
+    }
+
+    fn drain<I>(transport: &impl Transport<Incoming = I>, count: usize) -> Vec<I> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut received = Vec::new();
+
+        while received.len() < count && Instant::now() < deadline {
+            received.extend(transport.receive().unwrap());
+            thread::sleep(Duration::from_millis(1));
+        }
+        received
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/engine", listener.local_addr().unwrap());
+
+    let (done, finished) = std::sync::mpsc::channel::<()>();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let simulator = WebSocketTransport::<Ack, Command>::accept(stream)
+            .unwrap()
+            .with_codec(JsonCodec);
+
+        let commands = drain(&simulator, 3);
+        for (id, _) in commands.iter().enumerate() {
+            simulator.send(Ack { id: id as u32 }).unwrap();
+        }
+        finished.recv().unwrap();
+        simulator.close().unwrap();
+        commands
+    });
+
+    let controller = WebSocketTransport::<Command, Ack>::connect(&url)
+        .unwrap()
+        .with_codec(JsonCodec);
+    controller.send(Command::Spawn { id: 1 }).unwrap();
+    controller.send(Command::Spawn { id: 2 }).unwrap();
+    controller.send(Command::Reset).unwrap();
+
+    let acks = drain(&controller, 3);
+    done.send(()).unwrap();
+    let commands = server.join().unwrap();
+
+    assert_eq!(
+        commands,
+        [
+            Command::Spawn { id: 1 },
+            Command::Spawn { id: 2 },
+            Command::Reset
+        ]
+    );
+    assert_eq!(acks, [Ack { id: 0 }, Ack { id: 1 }, Ack { id: 2 }]);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let closed = loop {
+        match controller.receive() {
+            Err(err) => break err,
+            Ok(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(1)),
+            Ok(_) => panic!("close frame was never observed"),
+        }
+    };
+    assert!(matches!(
+        closed,
+        Error::Disconnected {
+            endpoint: Endpoint::Receiver
+        }
+    ));
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_transport_keeps_messages_around_an_undecodable_frame() {
+    use multi_agent_engine::transport::{JsonCodec, WebSocketTransport};
+    use std::{net::TcpListener, thread, time::Instant};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/engine", listener.local_addr().unwrap());
+
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let peer = WebSocketTransport::<serde_json::Value, ()>::accept(stream)
+            .unwrap()
+            .with_codec(JsonCodec);
+        for msg in [
+            serde_json::json!(1),
+            serde_json::json!("two"),
+            serde_json::json!(3),
+        ] {
+            peer.send(msg).unwrap();
+        }
+        peer
+    });
+    let client = WebSocketTransport::<(), u32>::connect(&url)
+        .unwrap()
+        .with_codec(JsonCodec);
+    let _peer = server.join().unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let (mut received, mut errors) = (Vec::new(), 0);
+    while received.len() + errors < 3 && Instant::now() < deadline {
+        match client.receive() {
+            Ok(batch) => received.extend(batch),
+            Err(_) => errors += 1,
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(received, [1, 3]);
+    assert_eq!(errors, 1);
+}
+
+#[cfg(feature = "websocket")]
+fn handshake(
+    controller: std::ops::RangeInclusive<u16>,
+    simulator: std::ops::RangeInclusive<u16>,
+) -> (Result<Option<u16>>, Result<Option<u16>>) {
+    use multi_agent_engine::transport::WebSocketTransport;
+    use std::{net::TcpListener, thread};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/engine", listener.local_addr().unwrap());
+
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        WebSocketTransport::<u32, u32>::accept_with_versions(stream, simulator)
+            .map(|transport| transport.protocol_version())
+    });
+
+    let client = WebSocketTransport::<u32, u32>::connect_with_versions(&url, controller)
+        .map(|transport| transport.protocol_version());
+    (client, server.join().unwrap())
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_handshake_agrees_on_highest_common_version() {
+    let (client, server) = handshake(1..=3, 2..=4);
+
+    assert_eq!(client.unwrap(), Some(3));
+    assert_eq!(server.unwrap(), Some(3));
+}
+
+#[cfg(feature = "websocket")]
+fn clock_sync(
+    controller: std::ops::RangeInclusive<u16>,
+    simulator: std::ops::RangeInclusive<u16>,
+    skew_since: u16,
+) -> (
+    multi_agent_engine::transport::WebSocketTransport<u32, u32>,
+    multi_agent_engine::transport::WebSocketTransport<u32, u32>,
+) {
+    use multi_agent_engine::transport::WebSocketTransport;
+    use std::{net::TcpListener, thread};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/engine", listener.local_addr().unwrap());
+
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        WebSocketTransport::accept_with_clock_sync(stream, simulator, skew_since).unwrap()
+    });
+    let client = WebSocketTransport::connect_with_clock_sync(&url, controller, skew_since).unwrap();
+    (client, server.join().unwrap())
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_handshake_estimates_clock_skew() {
+    use std::time::Instant;
+
+    let (client, server) = clock_sync(1..=2, 2..=3, 2);
+    assert_eq!(client.protocol_version(), Some(2));
+
+    let (to_server, to_client) = (client.clock_skew().unwrap(), server.clock_skew().unwrap());
+    for skew in [to_server, to_client] {
+        assert!(skew.round_trip() > Duration::ZERO);
+        assert!(skew.round_trip() < Duration::from_secs(1), "{skew:?}");
+        assert!(skew.offset_ns().unsigned_abs() <= skew.round_trip().as_nanos() as u64);
+    }
+    // Both ends share one clock, so their estimates must nearly cancel out.
+    let slack = (to_server.round_trip() + to_client.round_trip()).as_nanos() as i64;
+    assert!((to_server.offset_ns() + to_client.offset_ns()).abs() <= slack);
+
+    // The exchange leaves nothing behind for the message stream.
+    client.send(7).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let mut received = Vec::new();
+    while received.is_empty() && Instant::now() < deadline {
+        received = server.receive().unwrap();
+    }
+    assert_eq!(received, vec![7]);
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_handshake_skips_clock_sync_below_its_version() {
+    use std::time::Instant;
+
+    let (client, server) = clock_sync(1..=1, 1..=3, 2);
+
+    assert_eq!(server.protocol_version(), Some(1));
+    assert_eq!(client.clock_skew(), None);
+    assert_eq!(server.clock_skew(), None);
+
+    server.send(9).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let mut received = Vec::new();
+    while received.is_empty() && Instant::now() < deadline {
+        received = client.receive().unwrap();
+    }
+    assert_eq!(received, vec![9]);
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_handshake_refuses_disjoint_versions() {
+    let (client, server) = handshake(1..=1, 2..=3);
+
+    assert!(matches!(
+        client,
+        Err(Error::IncompatibleVersion { local, remote }) if local == (1..=1) && remote == (2..=3)
+    ));
+    assert!(matches!(
+        server,
+        Err(Error::IncompatibleVersion { local, remote }) if local == (2..=3) && remote == (1..=1)
+    ));
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket_connect_retries_until_listener_is_up() {
+    use multi_agent_engine::transport::WebSocketTransport;
+    use std::{net::TcpListener, thread};
+
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let url = format!("ws://{addr}/engine");
+
+    let server = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        let listener = TcpListener::bind(addr).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        WebSocketTransport::<u32, u32>::accept(stream).unwrap()
+    });
+
+    let client =
+        WebSocketTransport::<u32, u32>::connect_with_retry(&url, 20, Duration::from_millis(5));
+    let _server = server.join().unwrap();
+    client.unwrap().send(7).unwrap();
+
+    let refused = WebSocketTransport::<u32, u32>::connect_with_retry(
+        "ws://127.0.0.1:1/engine",
+        3,
+        Duration::from_millis(1),
+    );
+    assert!(matches!(
+        refused,
+        Err(Error::ConnectFailed { attempts: 3, .. })
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+enum WireCommand {
+    Spawn { id: u32, name: String },
+    Move(Vec<(f32, f32)>),
+    Reset,
+}
+
+#[cfg(feature = "serde")]
+fn codec_roundtrip(codec: impl multi_agent_engine::transport::Codec) {
+    let commands = vec![
+        WireCommand::Spawn {
+            id: 7,
+            name: "scout".to_owned(),
+        },
+        WireCommand::Move(vec![(0.5, -1.0), (2.0, 3.25)]),
+        WireCommand::Reset,
+    ];
+
+    let bytes = codec.encode(&commands).unwrap();
+    assert_eq!(codec.decode::<Vec<WireCommand>>(&bytes).unwrap(), commands);
+    assert!(
+        codec
+            .decode::<Vec<WireCommand>>(&bytes[..bytes.len() - 1])
+            .is_err()
+    );
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_codec_roundtrips() {
+    codec_roundtrip(multi_agent_engine::transport::BincodeCodec);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_codec_roundtrips() {
+    codec_roundtrip(multi_agent_engine::transport::JsonCodec);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn message_pack_codec_roundtrips() {
+    codec_roundtrip(multi_agent_engine::transport::MessagePackCodec);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn versioned_codec_rejects_messages_of_another_schema_version() {
+    use multi_agent_engine::transport::{Codec, JsonCodec, VersionedCodec};
+
+    let v1 = VersionedCodec::new(JsonCodec, 1);
+    let v2 = VersionedCodec::new(JsonCodec, 2);
+    codec_roundtrip(v2);
+
+    let bytes = v1.encode(&WireCommand::Reset).unwrap();
+    assert_eq!(bytes[0], 1);
+    assert!(matches!(
+        v2.decode::<WireCommand>(&bytes),
+        Err(Error::SchemaMismatch {
+            found: 1,
+            expected: 2
+        })
+    ));
+    assert!(v2.decode::<WireCommand>(&[]).is_err());
+}
+
+fn echo_doubled<T>(transport: &T) -> Result<usize>
+where
+    T: Transport<Outgoing = u32, Incoming = u32>,
+{
+    let incoming = transport.receive()?;
+    for msg in &incoming {
+        transport.send(msg * 2)?;
+    }
+    Ok(incoming.len())
+}
+
+#[test]
+fn in_memory_transport_delivers_after_injected_latency() {
+    let clock = MockClock::new();
+    let (client, server) = InMemoryTransport::<u32, u32>::pair();
+    let client = client
+        .with_clock(clock.clone())
+        .with_latency(Duration::from_millis(10));
+    let server = server
+        .with_clock(clock.clone())
+        .with_latency(Duration::from_millis(10));
+
+    client.send(21).unwrap();
+    assert_eq!(echo_doubled(&server).unwrap(), 0);
+
+    clock.advance(Duration::from_millis(9));
+    assert_eq!(echo_doubled(&server).unwrap(), 0);
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(echo_doubled(&server).unwrap(), 1);
+    assert!(client.receive().unwrap().is_empty());
+
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(client.receive().unwrap(), vec![42]);
+}
+
+#[test]
+fn in_memory_transport_reports_disconnected_peer() {
+    let (client, server) = InMemoryTransport::<u32, u32>::pair();
+    client.send(1).unwrap();
+    drop(client);
+
+    assert_eq!(server.receive().unwrap(), vec![1]);
+    assert!(matches!(server.receive(), Err(Error::Disconnected { .. })));
+    assert!(matches!(server.send(2), Err(Error::Disconnected { .. })));
+}
+
+#[derive(Clone, Default)]
+struct FakeLink {
+    up: Arc<AtomicBool>,
+    delivered: Arc<Mutex<Vec<u32>>>,
+}
+
+impl FakeLink {
+    fn connect(&self) -> Result<FakeLink> {
+        if self.up.load(Ordering::Relaxed) {
+            Ok(self.clone())
+        } else {
+            Err(io::Error::from(io::ErrorKind::ConnectionRefused).into())
+        }
+    }
+}
+
+impl Transport for FakeLink {
+    type Outgoing = u32;
+    type Incoming = u32;
+
+    fn send(&self, msg: u32) -> Result<()> {
+        if !self.up.load(Ordering::Relaxed) {
+            return Err(io::Error::from(io::ErrorKind::ConnectionReset).into());
+        }
+        self.delivered.lock().unwrap().push(msg);
+        Ok(())
+    }
+
+    fn receive(&self) -> Result<Vec<u32>> {
+        Ok(Vec::new())
+    }
+}
+
+#[test]
+fn reconnecting_transport_buffers_until_connection_restored() {
+    let clock = MockClock::new();
+    let link = FakeLink::default();
+    link.up.store(true, Ordering::Relaxed);
+    let transport = ReconnectingTransport::new({
+        let link = link.clone();
+        move || link.connect()
+    })
+    .with_backoff(Duration::from_millis(10), Duration::from_millis(40))
+    .with_buffer_capacity(3)
+    .with_clock(clock.clone());
+
+    transport.send(1).unwrap();
+    assert_eq!(transport.state(), ConnectionState::Connected);
+
+    link.up.store(false, Ordering::Relaxed);
+    for msg in 2..=4 {
+        transport.send(msg).unwrap();
+    }
+    assert_eq!(transport.state(), ConnectionState::Reconnecting);
+    assert_eq!(transport.buffered(), 3);
+    assert!(matches!(transport.send(5), Err(Error::Disconnected { .. })));
+
+    link.up.store(true, Ordering::Relaxed);
+    assert_eq!(transport.state(), ConnectionState::Reconnecting);
+    clock.advance(Duration::from_millis(40));
+    transport.send(6).unwrap();
+
+    assert_eq!(transport.state(), ConnectionState::Connected);
+    assert_eq!(transport.buffered(), 0);
+    assert_eq!(*link.delivered.lock().unwrap(), [1, 2, 3, 4, 6]);
+}
+
+#[test]
+fn reliable_sender_clears_buffer_on_cumulative_ack() {
+    let (client, server) = InMemoryTransport::<(u64, u32), u32>::pair();
+    let sender = ReliableSender::new(client);
+
+    for msg in 0..10 {
+        sender.send(msg * 10).unwrap();
+    }
+    assert_eq!(server.receive().unwrap().len(), 10);
+
+    sender.ack(7);
+    assert_eq!(sender.unacked(), [8, 9, 10]);
+
+    assert_eq!(sender.retransmit().unwrap(), 3);
+    assert_eq!(server.receive().unwrap(), [(8, 70), (9, 80), (10, 90)]);
+}
+
+const PEER_AHEAD: u64 = 5_000_000_000;
+
+/// A peer whose wall clock runs a fixed five seconds ahead of the local one.
+struct AheadTransport {
+    pending: Mutex<Vec<u32>>,
+}
+
+impl Transport for AheadTransport {
+    type Outgoing = u32;
+    type Incoming = Timestamped<u32>;
+
+    fn send(&self, msg: u32) -> Result<()> {
+        self.pending.lock().unwrap().push(msg);
+        Ok(())
+    }
+
+    fn receive(&self) -> Result<Vec<Timestamped<u32>>> {
+        let stamp = unix_nanos() + PEER_AHEAD;
+
+        Ok(self
+            .pending
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|message| Timestamped {
+                sent_at_ns: stamp,
+                message,
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn clock_skew_estimate_splits_the_round_trip() {
+    let skew = ClockSkew::from_exchange(1_000, 6_010, 6_020, 1_040);
+
+    assert_eq!(skew.offset_ns(), 4_995);
+    assert_eq!(skew.round_trip(), Duration::from_nanos(30));
+    assert_eq!(skew.to_local(6_020), 1_025);
+}
+
+#[test]
+fn skew_corrected_transport_moves_stamps_into_local_clock() {
+    let latency = 2_000_000;
+    let t0 = unix_nanos();
+    let t1 = t0 + latency + PEER_AHEAD;
+    let skew = ClockSkew::from_exchange(t0, t1, t1, t0 + 2 * latency);
+    assert_eq!(skew.offset_ns(), PEER_AHEAD as i64);
+
+    let transport = SkewCorrectedTransport::new(
+        AheadTransport {
+            pending: Mutex::new(Vec::new()),
+        },
+        skew,
+    );
+    transport.send(7).unwrap();
+
+    let before = unix_nanos();
+    let received = transport.receive().unwrap();
+    let after = unix_nanos();
+
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].message, 7);
+    assert!((before..=after).contains(&received[0].sent_at_ns));
+}
+
+#[test]
+fn reliable_sender_window_blocks_until_acked() {
+    let (client, server) = InMemoryTransport::<(u64, u32), u32>::pair();
+    let sender = ReliableSender::new(client).with_window(4);
+    let sent = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for msg in 0..6 {
+                sender.send(msg).unwrap();
+                sent.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(sent.load(Ordering::SeqCst), 4);
+        assert_eq!(sender.unacked(), [1, 2, 3, 4]);
+
+        sender.ack(1);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(sent.load(Ordering::SeqCst), 5);
+
+        sender.ack(3);
+    });
+
+    assert_eq!(sent.load(Ordering::SeqCst), 6);
+    assert_eq!(sender.unacked(), [4, 5, 6]);
+    assert_eq!(server.receive().unwrap().len(), 6);
+}